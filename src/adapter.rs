@@ -1,4 +1,4 @@
-use std::{future::Future, pin::Pin, time::Duration, time::Instant};
+use std::{future::Future, pin::Pin, time::Duration, time::Instant, time::SystemTime};
 
 use bytes::Bytes;
 use reqwest::header::HeaderValue;
@@ -6,7 +6,18 @@ use reqwest::{Client as ReqwestClient, Method};
 use sonic_rs::{Deserialize, from_slice};
 use thiserror::Error;
 
-use crate::fixture_policy;
+use crate::compression::{
+    RestBodyCompression, RestBodyDecoder, RestContentEncoding, strip_encoding_headers,
+};
+use crate::concurrency::{RestConcurrencyLimiter, RestConcurrencyLimits, RestConcurrencyPermit};
+use crate::fixture_policy::RestFixtureRegistry;
+use crate::headers::{self, RestHeaderNames, RestRateLimit, RestRawHeaderResponse, RestRetryAfter};
+use crate::ndjson::RestNdjsonStream;
+use crate::paginate::{RestPageStrategy, RestPaginator};
+use crate::rate_limit::{RateLimitGate, RestRateLimitKey, RestRateLimitObservation};
+use crate::sse::RestSseStream;
+use crate::stream::{RestBodyStream, RestStreamingResponse};
+use crate::transport_builder::ReqwestTransportBuilder;
 
 pub type RestBytes = Bytes;
 pub type RestFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
@@ -31,6 +42,8 @@ pub enum RestErrorKind {
     Parse,
     Internal,
     MockTransport,
+    BodyTooLarge,
+    QueueTimeout,
 }
 
 #[derive(Error, Debug)]
@@ -79,9 +92,42 @@ pub enum RestError {
     #[error("response parse error: {0}")]
     Parse(#[from] sonic_rs::Error),
 
+    #[error("ndjson parse error at line {line} (byte offset {offset}): {source}")]
+    NdjsonParse {
+        line: u64,
+        offset: u64,
+        #[source]
+        source: sonic_rs::Error,
+    },
+
     #[error("internal transport error: {message}")]
     Internal { message: String },
 
+    #[error("response body exceeds {limit} bytes (received {received})")]
+    BodyTooLarge {
+        limit: u64,
+        received: u64,
+        status: Option<u16>,
+    },
+
+    #[error("failed to decode {encoding} response body: {message}")]
+    Decode {
+        encoding: RestContentEncoding,
+        status: Option<u16>,
+        message: String,
+    },
+
+    #[error(
+        "request queued {waited:?} for the {} concurrency limit (queue timeout {timeout:?})",
+        queue_scope(host.as_deref())
+    )]
+    QueueTimeout {
+        /// The host whose limit was full, or `None` for the global limit.
+        host: Option<String>,
+        waited: Duration,
+        timeout: Duration,
+    },
+
     #[error("mock transport behavior error: {message}")]
     MockTransport {
         kind: RestErrorKind,
@@ -92,7 +138,7 @@ pub enum RestError {
 }
 
 impl RestError {
-    fn from_reqwest(kind: RestErrorKind, err: reqwest::Error) -> Self {
+    pub(crate) fn from_reqwest(kind: RestErrorKind, err: reqwest::Error) -> Self {
         let status = err.status().map(|status| status.as_u16());
         let message = err.to_string();
         if err.is_timeout() {
@@ -154,6 +200,38 @@ impl RestError {
         }
     }
 
+    pub fn ndjson_parse(line: u64, offset: u64, source: sonic_rs::Error) -> Self {
+        Self::NdjsonParse {
+            line,
+            offset,
+            source,
+        }
+    }
+
+    pub fn body_too_large(limit: u64, received: u64, status: Option<u16>) -> Self {
+        Self::BodyTooLarge {
+            limit,
+            received,
+            status,
+        }
+    }
+
+    pub fn decode(encoding: RestContentEncoding, message: impl Into<String>) -> Self {
+        Self::Decode {
+            encoding,
+            status: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn queue_timeout(host: Option<String>, waited: Duration, timeout: Duration) -> Self {
+        Self::QueueTimeout {
+            host,
+            waited,
+            timeout,
+        }
+    }
+
     pub fn mock(
         kind: RestErrorKind,
         message: impl Into<String>,
@@ -195,8 +273,10 @@ impl RestError {
             Self::Receive { .. } => RestErrorKind::Receive,
             Self::Timeout { .. } => RestErrorKind::Timeout,
             Self::Rejected { .. } => RestErrorKind::Rejected,
-            Self::Parse(_) => RestErrorKind::Parse,
+            Self::Parse(_) | Self::NdjsonParse { .. } | Self::Decode { .. } => RestErrorKind::Parse,
             Self::Internal { .. } => RestErrorKind::Internal,
+            Self::BodyTooLarge { .. } => RestErrorKind::BodyTooLarge,
+            Self::QueueTimeout { .. } => RestErrorKind::QueueTimeout,
             Self::MockTransport { kind, .. } => *kind,
         }
     }
@@ -208,8 +288,11 @@ impl RestError {
             Self::Receive { status, .. } => *status,
             Self::Timeout { status, .. } => *status,
             Self::Rejected { status, .. } => Some(*status),
-            Self::Parse(_) => None,
+            Self::Parse(_) | Self::NdjsonParse { .. } => None,
             Self::Internal { .. } => None,
+            Self::BodyTooLarge { status, .. } => *status,
+            Self::Decode { status, .. } => *status,
+            Self::QueueTimeout { .. } => None,
             Self::MockTransport { status, .. } => *status,
         }
     }
@@ -221,13 +304,22 @@ impl RestError {
             Self::Receive { retryable, .. } => *retryable,
             Self::Timeout { retryable, .. } => *retryable,
             Self::Rejected { retryable, .. } => *retryable,
-            Self::Parse(_) => false,
+            Self::Parse(_) | Self::NdjsonParse { .. } => false,
             Self::Internal { .. } => false,
+            Self::BodyTooLarge { .. } | Self::Decode { .. } => false,
+            Self::QueueTimeout { .. } => false,
             Self::MockTransport { retryable, .. } => *retryable,
         }
     }
 }
 
+fn queue_scope(host: Option<&str>) -> String {
+    match host {
+        Some(host) => format!("{host} host"),
+        None => "global".to_string(),
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RestRetryPolicy {
     pub max_retries: usize,
@@ -249,6 +341,13 @@ pub struct RestRequest {
     pub timeout: Option<Duration>,
     pub retry_policy: Option<RestRetryPolicy>,
     pub fixture_contract: Option<String>,
+    /// Largest response body accepted, buffered or streamed; larger bodies fail with
+    /// `RestErrorKind::BodyTooLarge`.
+    pub max_body_bytes: Option<u64>,
+    /// Compress the body before sending; applied once by the transport.
+    pub body_compression: Option<RestBodyCompression>,
+    /// Cost charged against the client's rate-limit gate for each attempt.
+    pub rate_limit_weight: u32,
 }
 
 impl RestRequest {
@@ -261,6 +360,9 @@ impl RestRequest {
             timeout: Some(DEFAULT_REQUEST_TIMEOUT),
             retry_policy: None,
             fixture_contract: None,
+            max_body_bytes: None,
+            body_compression: None,
+            rate_limit_weight: 1,
         }
     }
 
@@ -287,6 +389,57 @@ impl RestRequest {
         self
     }
 
+    pub fn with_max_body_bytes(mut self, max_body_bytes: u64) -> Self {
+        self.max_body_bytes = Some(max_body_bytes);
+        self
+    }
+
+    /// Send bodies of at least `min_bytes` compressed with `encoding` and a matching
+    /// `Content-Encoding`; smaller bodies go out as-is.
+    pub fn with_body_compression(
+        mut self,
+        encoding: RestContentEncoding,
+        min_bytes: usize,
+    ) -> Self {
+        self.body_compression = Some(RestBodyCompression {
+            encoding,
+            min_bytes,
+        });
+        self
+    }
+
+    /// The body with its `Content-Encoding` undone, for assertions on compressed requests.
+    pub fn decoded_body(&self) -> RestResult<Option<RestBytes>> {
+        let Some(body) = &self.body else {
+            return Ok(None);
+        };
+        match headers::header(&self.headers, "content-encoding")
+            .and_then(RestContentEncoding::parse)
+        {
+            Some(encoding) => encoding.decode(body).map(Some),
+            None => Ok(Some(body.clone())),
+        }
+    }
+
+    /// Apply `body_compression`, clearing it so a request is never compressed twice.
+    pub(crate) fn encode_body(mut self) -> RestResult<Self> {
+        let Some(compression) = self.body_compression.take() else {
+            return Ok(self);
+        };
+        let already_encoded = headers::header_bytes(&self.headers, "content-encoding").is_some();
+        if let Some(body) = &self.body
+            && !already_encoded
+            && body.len() >= compression.min_bytes
+        {
+            self.body = Some(compression.encoding.encode(body)?);
+            self.headers.push((
+                "content-encoding".to_string(),
+                RestBytes::from_static(compression.encoding.as_str().as_bytes()),
+            ));
+        }
+        Ok(self)
+    }
+
     pub fn with_retry_on_status(self, status: u16, max_retries: usize) -> Self {
         self.with_retry_on_statuses([status], max_retries)
     }
@@ -339,6 +492,11 @@ impl RestRequest {
         self.with_retry_on_statuses((100u16..200u16).chain(300u16..600u16), max_retries)
     }
 
+    pub fn with_rate_limit_weight(mut self, weight: u32) -> Self {
+        self.rate_limit_weight = weight;
+        self
+    }
+
     pub fn with_fixture_contract(mut self, contract_id: impl Into<String>) -> Self {
         self.fixture_contract = Some(contract_id.into());
         self
@@ -356,16 +514,41 @@ impl RestRequest {
 }
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct RestResponse {
     pub status: u16,
     pub headers: Vec<(String, RestBytes)>,
+    /// Decoded body; `Content-Encoding` and the wire `Content-Length` are dropped from `headers`
+    /// once a body has been decoded.
     pub body: RestBytes,
     pub elapsed: Duration,
+    /// Body size on the wire when it arrived content-encoded; `None` when it arrived as-is.
+    pub encoded_size: Option<u64>,
+    /// Time spent waiting for a `Client` concurrency slot before the request was sent.
+    pub queue_wait: Duration,
 }
 
 pub type RestRawResponse = (u16, RestBytes, Duration);
 
 impl RestResponse {
+    /// A response that arrived unencoded and without queueing; transports and test doubles
+    /// build responses through this so new fields do not break them.
+    pub fn new(
+        status: u16,
+        headers: Vec<(String, RestBytes)>,
+        body: impl Into<RestBytes>,
+        elapsed: Duration,
+    ) -> Self {
+        Self {
+            status,
+            headers,
+            body: body.into(),
+            elapsed,
+            encoded_size: None,
+            queue_wait: Duration::ZERO,
+        }
+    }
+
     pub fn status(&self) -> u16 {
         self.status
     }
@@ -378,6 +561,53 @@ impl RestResponse {
         &self.body
     }
 
+    /// Bytes received on the wire for the body.
+    pub fn wire_size(&self) -> u64 {
+        self.encoded_size.unwrap_or(self.body.len() as u64)
+    }
+
+    pub fn decoded_size(&self) -> u64 {
+        self.body.len() as u64
+    }
+
+    /// First value of header `name` (case-insensitive) that is valid UTF-8.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        headers::header(&self.headers, name)
+    }
+
+    pub fn header_bytes(&self, name: &str) -> Option<&[u8]> {
+        headers::header_bytes(&self.headers, name)
+    }
+
+    /// Every value of header `name`, in response order.
+    pub fn header_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        headers::header_all(&self.headers, name)
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        headers::header(&self.headers, "content-type")
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        headers::content_length(&self.headers)
+    }
+
+    pub fn date(&self) -> Option<SystemTime> {
+        headers::date(&self.headers)
+    }
+
+    pub fn retry_after(&self) -> Option<RestRetryAfter> {
+        headers::retry_after(&self.headers)
+    }
+
+    pub fn etag(&self) -> Option<&str> {
+        headers::header(&self.headers, "etag")
+    }
+
+    pub fn rate_limit(&self) -> Option<RestRateLimit> {
+        headers::rate_limit(&self.headers)
+    }
+
     /// Parse directly from the response body. Borrowed output types remain valid only
     /// while this `RestResponse` is alive.
     pub fn json<'de, T>(&'de self) -> RestResult<T>
@@ -423,6 +653,60 @@ pub trait RestTransport: Send + Sync {
             Ok((response.status, response.body, response.elapsed))
         })
     }
+
+    /// Response head now, body pulled chunk by chunk. The default buffers through `execute`.
+    fn execute_stream(
+        &self,
+        request: RestRequest,
+    ) -> RestFuture<RestResult<RestStreamingResponse>> {
+        let future = self.execute(request);
+        Box::pin(async move { Ok(RestStreamingResponse::buffered(future.await?)) })
+    }
+
+    /// Like `execute_raw`, but keeps the headers named in `keep` (case-insensitive).
+    fn execute_raw_with_headers(
+        &self,
+        request: RestRequest,
+        keep: RestHeaderNames,
+    ) -> RestFuture<RestResult<RestRawHeaderResponse>> {
+        let future = self.execute(request);
+        Box::pin(async move { Ok(raw_header_response(future.await?, &keep)) })
+    }
+}
+
+/// `response` as a raw response keeping only the headers named in `keep`.
+pub(crate) fn raw_header_response(
+    response: RestResponse,
+    keep: &[&'static str],
+) -> RestRawHeaderResponse {
+    RestRawHeaderResponse {
+        status: response.status,
+        headers: retain_headers(response.headers, keep),
+        body: response.body,
+        elapsed: response.elapsed,
+    }
+}
+
+/// Pair each kept header with the caller's static name, preserving response order.
+fn retain_headers<N, V>(
+    headers: impl IntoIterator<Item = (N, V)>,
+    keep: &[&'static str],
+) -> Vec<(&'static str, RestBytes)>
+where
+    N: AsRef<str>,
+    V: AsRef<[u8]>,
+{
+    if keep.is_empty() {
+        return Vec::new();
+    }
+    headers
+        .into_iter()
+        .filter_map(|(name, value)| {
+            keep.iter()
+                .find(|kept| kept.eq_ignore_ascii_case(name.as_ref()))
+                .map(|kept| (*kept, Bytes::copy_from_slice(value.as_ref())))
+        })
+        .collect()
 }
 
 pub type SharedRestTransport = dyn RestTransport + Send + Sync;
@@ -430,6 +714,22 @@ pub type SharedRestTransport = dyn RestTransport + Send + Sync;
 #[derive(Clone)]
 pub struct Client {
     transport: std::sync::Arc<SharedRestTransport>,
+    concurrency: Option<std::sync::Arc<RestConcurrencyLimiter>>,
+    rate_limit_gate: Option<std::sync::Arc<dyn RateLimitGate>>,
+}
+
+/// Pacing key and concurrency slot held for one attempt.
+struct RestAdmission {
+    rate_limit_key: Option<RestRateLimitKey>,
+    permit: Option<RestConcurrencyPermit>,
+}
+
+impl RestAdmission {
+    fn queue_wait(&self) -> Duration {
+        self.permit
+            .as_ref()
+            .map_or(Duration::ZERO, |permit| permit.queue_wait)
+    }
 }
 
 impl Client {
@@ -437,17 +737,103 @@ impl Client {
         Self::with_transport(ReqwestTransport::new())
     }
 
+    /// Live client whose fixture gate uses `registry` instead of the global registry.
+    pub fn with_fixture_registry(registry: RestFixtureRegistry) -> Self {
+        Self::with_transport(ReqwestTransport::new().with_fixture_registry(registry))
+    }
+
     pub fn with_transport<T>(transport: T) -> Self
     where
         T: RestTransport + 'static,
     {
         Self {
             transport: std::sync::Arc::new(transport),
+            concurrency: None,
+            rate_limit_gate: None,
+        }
+    }
+
+    /// Cap in-flight requests from this client and its clones. Each attempt waits for a slot;
+    /// streamed bodies keep theirs until the body ends or is dropped.
+    pub fn with_concurrency_limits(mut self, limits: RestConcurrencyLimits) -> Self {
+        self.concurrency = Some(std::sync::Arc::new(RestConcurrencyLimiter::new(limits)));
+        self
+    }
+
+    /// Pace every request from this client and its clones through `gate`, including retries,
+    /// reconnects and pages.
+    pub fn with_rate_limit_gate<G>(mut self, gate: G) -> Self
+    where
+        G: RateLimitGate + 'static,
+    {
+        self.rate_limit_gate = Some(std::sync::Arc::new(gate));
+        self
+    }
+
+    /// Waits for the rate-limit gate, then for a concurrency slot.
+    async fn admit(&self, request: &RestRequest) -> RestResult<RestAdmission> {
+        let rate_limit_key = match &self.rate_limit_gate {
+            Some(gate) => {
+                let key = RestRateLimitKey::for_request(request);
+                gate.acquire(&key).await?;
+                Some(key)
+            }
+            None => None,
+        };
+        let permit = match &self.concurrency {
+            Some(limiter) => Some(limiter.acquire(&request.url).await?),
+            None => None,
+        };
+        Ok(RestAdmission {
+            rate_limit_key,
+            permit,
+        })
+    }
+
+    fn observe(&self, admission: &RestAdmission, response: RestRateLimitObservation<'_>) {
+        if let (Some(gate), Some(key)) = (&self.rate_limit_gate, &admission.rate_limit_key) {
+            gate.observe(key, &response);
         }
     }
 
     async fn execute(&self, request: RestRequest) -> RestResult<RestResponse> {
-        self.transport.execute(request).await
+        let admission = self.admit(&request).await?;
+        let mut response = self.transport.execute(request).await?;
+        self.observe(
+            &admission,
+            RestRateLimitObservation::new(response.status, &response.headers),
+        );
+        response.queue_wait = admission.queue_wait();
+        Ok(response)
+    }
+
+    /// Status and body; keeps the headers the rate-limit gate observes when one is set.
+    async fn execute_raw(&self, request: RestRequest) -> RestResult<RestRawResponse> {
+        let admission = self.admit(&request).await?;
+        let Some(gate) = &self.rate_limit_gate else {
+            return self.transport.execute_raw(request).await;
+        };
+        let response = self
+            .transport
+            .execute_raw_with_headers(request, RestHeaderNames::Borrowed(gate.observed_headers()))
+            .await?;
+        self.observe(
+            &admission,
+            RestRateLimitObservation::kept(response.status, &response.headers),
+        );
+        Ok((response.status, response.body, response.elapsed))
+    }
+
+    async fn execute_stream(&self, request: RestRequest) -> RestResult<RestStreamingResponse> {
+        let admission = self.admit(&request).await?;
+        let mut response = self.transport.execute_stream(request).await?;
+        self.observe(
+            &admission,
+            RestRateLimitObservation::new(response.status, &response.headers),
+        );
+        response.queue_wait = admission.queue_wait();
+        response.body = response.body.with_permit(admission.permit);
+        Ok(response)
     }
 
     async fn execute_checked(&self, request: RestRequest) -> RestResult<RestResponse> {
@@ -477,7 +863,7 @@ impl Client {
     where
         T: for<'de> Deserialize<'de>,
     {
-        let (_status, body, _elapsed) = self.transport.execute_raw(request).await?;
+        let (_status, body, _elapsed) = self.execute_raw(request).await?;
         from_slice(&body).map_err(RestError::from)
     }
 
@@ -494,7 +880,7 @@ impl Client {
     {
         let mut attempt = 0usize;
         loop {
-            let (status, body, _elapsed) = self.transport.execute_raw(request.clone()).await?;
+            let (status, body, _elapsed) = self.execute_raw(request.clone()).await?;
             if (200..300).contains(&status) {
                 return from_slice(&body).map_err(RestError::from);
             }
@@ -512,6 +898,76 @@ impl Client {
         }
     }
 
+    /// Status, body and only the headers named in `keep`, without building a full
+    /// `RestResponse`. The rate-limit gate still sees the headers it observes; they are
+    /// requested alongside `keep` and dropped before the response is returned.
+    pub async fn get_raw_with_headers(
+        &self,
+        request: RestRequest,
+        keep: &'static [&'static str],
+    ) -> RestResult<RestRawHeaderResponse> {
+        let admission = self.admit(&request).await?;
+        let observed = self
+            .rate_limit_gate
+            .as_ref()
+            .map_or(&[][..], |gate| gate.observed_headers());
+        let extra = observed
+            .iter()
+            .filter(|name| !keep.iter().any(|kept| kept.eq_ignore_ascii_case(name)))
+            .copied()
+            .collect::<Vec<_>>();
+        let selection = if extra.is_empty() {
+            RestHeaderNames::Borrowed(keep)
+        } else {
+            RestHeaderNames::Owned([keep, &extra].concat())
+        };
+        let mut response = self
+            .transport
+            .execute_raw_with_headers(request, selection)
+            .await?;
+        self.observe(
+            &admission,
+            RestRateLimitObservation::kept(response.status, &response.headers),
+        );
+        if !extra.is_empty() {
+            response.headers.retain(|(name, _)| keep.contains(name));
+        }
+        Ok(response)
+    }
+
+    /// Status and headers once they arrive; read the body with `response.body.next_chunk()`.
+    pub async fn get_stream(&self, request: RestRequest) -> RestResult<RestStreamingResponse> {
+        self.execute_stream(request).await
+    }
+
+    /// Decode a newline-delimited JSON body item by item. Non-2xx responses are read in full
+    /// and rejected like `get_checked_response`.
+    pub async fn get_ndjson<T>(&self, request: RestRequest) -> RestResult<RestNdjsonStream<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let response = self.execute_stream(request).await?;
+        if response.is_success() {
+            return Ok(RestNdjsonStream::new(response.body));
+        }
+        Err(response
+            .into_response()
+            .await?
+            .ensure_success()
+            .expect_err("non-2xx responses are rejected"))
+    }
+
+    /// Pages starting at `request`, following `strategy`; nothing is sent until the first page is
+    /// requested.
+    pub fn paginate(&self, request: RestRequest, strategy: RestPageStrategy) -> RestPaginator {
+        RestPaginator::new(self.clone(), request, strategy)
+    }
+
+    /// Server-Sent Events from `request`; nothing is sent until the first `next_event`.
+    pub fn event_stream(&self, request: RestRequest) -> RestSseStream {
+        RestSseStream::new(self.clone(), request)
+    }
+
     pub async fn get_response(&self, request: RestRequest) -> RestResult<RestResponse> {
         self.execute(request).await
     }
@@ -533,20 +989,43 @@ impl Client {
     }
 }
 
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ReqwestTransport {
     client: ReqwestClient,
+    fixture_registry: RestFixtureRegistry,
 }
 
 impl ReqwestTransport {
     pub fn new() -> Self {
+        Self::with_client(ReqwestClient::new())
+    }
+
+    /// Configure pooling, TLS, proxies and the user agent without depending on reqwest directly.
+    pub fn builder() -> ReqwestTransportBuilder {
+        ReqwestTransportBuilder::new()
+    }
+
+    pub fn with_client(client: ReqwestClient) -> Self {
         Self {
-            client: ReqwestClient::new(),
+            client,
+            fixture_registry: RestFixtureRegistry::global(),
         }
     }
 
-    pub fn with_client(client: ReqwestClient) -> Self {
-        Self { client }
+    /// Gate live requests against `registry` instead of the global registry.
+    pub fn with_fixture_registry(mut self, registry: RestFixtureRegistry) -> Self {
+        self.fixture_registry = registry;
+        self
+    }
+
+    pub fn fixture_registry(&self) -> &RestFixtureRegistry {
+        &self.fixture_registry
     }
 }
 
@@ -556,87 +1035,167 @@ impl Default for ReqwestTransport {
     }
 }
 
-impl RestTransport for ReqwestTransport {
-    fn execute_raw(&self, request: RestRequest) -> RestFuture<RestResult<RestRawResponse>> {
-        let client = self.client.clone();
-        Box::pin(async move {
-            fixture_policy::ensure_live_request_allowed(&request)?;
-            let start = Instant::now();
-            let mut req = client.request(request.method.clone(), &request.url);
-
-            for (key, value) in request.headers {
-                let value = HeaderValue::from_bytes(value.as_ref())
-                    .map_err(|err| RestError::internal(err.to_string()))?;
-                req = req.header(key, value);
-            }
+fn copy_headers(headers: &reqwest::header::HeaderMap) -> Vec<(String, RestBytes)> {
+    headers
+        .iter()
+        .map(|(name, value)| (name.to_string(), Bytes::copy_from_slice(value.as_ref())))
+        .collect()
+}
 
-            if let Some(body) = request.body {
-                req = req.body(body);
-            }
+/// Just the `Content-Encoding` header, for the raw paths that skip copying headers.
+fn encoding_header(headers: &reqwest::header::HeaderMap) -> Vec<(&'static str, RestBytes)> {
+    headers
+        .get(reqwest::header::CONTENT_ENCODING)
+        .map(|value| ("content-encoding", Bytes::copy_from_slice(value.as_bytes())))
+        .into_iter()
+        .collect()
+}
 
-            if let Some(timeout) = request.timeout {
-                req = req.timeout(timeout);
-            }
+impl ReqwestTransport {
+    /// Gate and send `request`, returning the response head and the start instant.
+    async fn send(
+        client: ReqwestClient,
+        fixture_registry: RestFixtureRegistry,
+        request: RestRequest,
+    ) -> RestResult<(reqwest::Response, Instant)> {
+        fixture_registry.ensure_live_request_allowed(&request)?;
+        let request = request.encode_body()?;
+        let start = Instant::now();
+        let mut req = client.request(request.method.clone(), &request.url);
+
+        if let Some(accept_encoding) = RestContentEncoding::accept_encoding()
+            && !request
+                .headers
+                .iter()
+                .any(|(key, _)| key.eq_ignore_ascii_case("accept-encoding"))
+        {
+            req = req.header(reqwest::header::ACCEPT_ENCODING, accept_encoding);
+        }
 
-            let resp = req
-                .send()
-                .await
-                .map_err(|err| RestError::from_reqwest(RestErrorKind::Send, err))?;
+        for (key, value) in request.headers {
+            let value = HeaderValue::from_bytes(value.as_ref())
+                .map_err(|err| RestError::internal(err.to_string()))?;
+            req = req.header(key, value);
+        }
 
-            let status = resp.status().as_u16();
+        if let Some(body) = request.body {
+            req = req.body(body);
+        }
+
+        if let Some(timeout) = request.timeout {
+            req = req.timeout(timeout);
+        }
+
+        let resp = req
+            .send()
+            .await
+            .map_err(|err| RestError::from_reqwest(RestErrorKind::Send, err))?;
+        Ok((resp, start))
+    }
+
+    /// Read and decode the body, returning its wire size when it was content-encoded.
+    async fn read_body(
+        resp: reqwest::Response,
+        max_body_bytes: Option<u64>,
+        decoder: Option<RestBodyDecoder>,
+    ) -> RestResult<(RestBytes, Option<u64>)> {
+        if max_body_bytes.is_none() && decoder.is_none() {
             let body = resp
                 .bytes()
                 .await
                 .map_err(|err| RestError::from_reqwest(RestErrorKind::Receive, err))?;
-            let elapsed = start.elapsed();
+            return Ok((body, None));
+        }
+        RestBodyStream::from_reqwest(resp, max_body_bytes, decoder)?
+            .read_to_end_encoded()
+            .await
+    }
+}
 
-            Ok((status, body, elapsed))
+impl RestTransport for ReqwestTransport {
+    fn execute_raw(&self, request: RestRequest) -> RestFuture<RestResult<RestRawResponse>> {
+        let client = self.client.clone();
+        let fixture_registry = self.fixture_registry.clone();
+        Box::pin(async move {
+            let max_body_bytes = request.max_body_bytes;
+            let (resp, start) = Self::send(client, fixture_registry, request).await?;
+            let status = resp.status().as_u16();
+            let decoder = RestBodyDecoder::for_headers(&encoding_header(resp.headers()));
+            let (body, _) = Self::read_body(resp, max_body_bytes, decoder).await?;
+            Ok((status, body, start.elapsed()))
         })
     }
 
-    fn execute(&self, request: RestRequest) -> RestFuture<RestResult<RestResponse>> {
+    fn execute_raw_with_headers(
+        &self,
+        request: RestRequest,
+        keep: RestHeaderNames,
+    ) -> RestFuture<RestResult<RestRawHeaderResponse>> {
         let client = self.client.clone();
+        let fixture_registry = self.fixture_registry.clone();
         Box::pin(async move {
-            fixture_policy::ensure_live_request_allowed(&request)?;
-            let start = Instant::now();
-            let mut req = client.request(request.method.clone(), &request.url);
-
-            for (key, value) in request.headers {
-                let value = HeaderValue::from_bytes(value.as_ref())
-                    .map_err(|err| RestError::internal(err.to_string()))?;
-                req = req.header(key, value);
-            }
-
-            if let Some(body) = request.body {
-                req = req.body(body);
+            let max_body_bytes = request.max_body_bytes;
+            let (resp, start) = Self::send(client, fixture_registry, request).await?;
+            let status = resp.status().as_u16();
+            let decoder = RestBodyDecoder::for_headers(&encoding_header(resp.headers()));
+            let mut headers = retain_headers(resp.headers(), &keep);
+            if decoder.is_some() {
+                strip_encoding_headers(&mut headers);
             }
+            let (body, _) = Self::read_body(resp, max_body_bytes, decoder).await?;
+            Ok(RestRawHeaderResponse {
+                status,
+                headers,
+                body,
+                elapsed: start.elapsed(),
+            })
+        })
+    }
 
-            if let Some(timeout) = request.timeout {
-                req = req.timeout(timeout);
+    fn execute_stream(
+        &self,
+        request: RestRequest,
+    ) -> RestFuture<RestResult<RestStreamingResponse>> {
+        let client = self.client.clone();
+        let fixture_registry = self.fixture_registry.clone();
+        Box::pin(async move {
+            let max_body_bytes = request.max_body_bytes;
+            let (resp, start) = Self::send(client, fixture_registry, request).await?;
+            let status = resp.status().as_u16();
+            let mut headers = copy_headers(resp.headers());
+            let decoder = RestBodyDecoder::for_headers(&headers);
+            if decoder.is_some() {
+                strip_encoding_headers(&mut headers);
             }
+            let elapsed = start.elapsed();
+            Ok(RestStreamingResponse {
+                status,
+                headers,
+                body: RestBodyStream::from_reqwest(resp, max_body_bytes, decoder)?,
+                elapsed,
+                queue_wait: Duration::ZERO,
+            })
+        })
+    }
 
-            let resp = req
-                .send()
-                .await
-                .map_err(|err| RestError::from_reqwest(RestErrorKind::Send, err))?;
-
+    fn execute(&self, request: RestRequest) -> RestFuture<RestResult<RestResponse>> {
+        let client = self.client.clone();
+        let fixture_registry = self.fixture_registry.clone();
+        Box::pin(async move {
+            let max_body_bytes = request.max_body_bytes;
+            let (resp, start) = Self::send(client, fixture_registry, request).await?;
             let status = resp.status().as_u16();
-            let headers = resp
-                .headers()
-                .iter()
-                .map(|(name, value)| (name.to_string(), Bytes::copy_from_slice(value.as_ref())))
-                .collect();
-            let body = resp
-                .bytes()
-                .await
-                .map_err(|err| RestError::from_reqwest(RestErrorKind::Receive, err))?;
+            let mut headers = copy_headers(resp.headers());
+            let decoder = RestBodyDecoder::for_headers(&headers);
+            if decoder.is_some() {
+                strip_encoding_headers(&mut headers);
+            }
+            let (body, encoded_size) = Self::read_body(resp, max_body_bytes, decoder).await?;
             let elapsed = start.elapsed();
 
             Ok(RestResponse {
-                status,
-                headers,
-                body,
-                elapsed,
+                encoded_size,
+                ..RestResponse::new(status, headers, body, elapsed)
             })
         })
     }
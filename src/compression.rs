@@ -0,0 +1,466 @@
+//! `Content-Encoding` support for response and request bodies.
+//!
+//! Each encoding sits behind the cargo feature of the same name (`gzip`, `deflate`, `brotli`,
+//! `zstd`). Enabled encodings are advertised with `Accept-Encoding` and decoded chunk by chunk,
+//! so streamed bodies stay incremental and `max_body_bytes` limits the decoded size. A body whose
+//! encoding is not compiled in is passed through as received.
+
+use std::fmt;
+use std::io;
+#[cfg(any(
+    feature = "gzip",
+    feature = "deflate",
+    feature = "brotli",
+    feature = "zstd"
+))]
+use std::io::Write;
+
+use crate::adapter::{RestBytes, RestError, RestResult};
+use crate::headers;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RestContentEncoding {
+    Gzip,
+    /// zlib-wrapped deflate, as HTTP defines it.
+    Deflate,
+    Brotli,
+    Zstd,
+}
+
+impl RestContentEncoding {
+    pub const ALL: [Self; 4] = [Self::Gzip, Self::Deflate, Self::Brotli, Self::Zstd];
+
+    /// Token used in `Content-Encoding` and `Accept-Encoding`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+            Self::Brotli => "br",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        Self::ALL
+            .into_iter()
+            .find(|encoding| value.eq_ignore_ascii_case(encoding.as_str()))
+            .or_else(|| value.eq_ignore_ascii_case("x-gzip").then_some(Self::Gzip))
+    }
+
+    /// Whether the cargo feature for this encoding is enabled.
+    pub fn is_enabled(self) -> bool {
+        match self {
+            Self::Gzip => cfg!(feature = "gzip"),
+            Self::Deflate => cfg!(feature = "deflate"),
+            Self::Brotli => cfg!(feature = "brotli"),
+            Self::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+
+    /// `Accept-Encoding` value listing the enabled encodings; `None` when none are enabled.
+    pub fn accept_encoding() -> Option<String> {
+        let enabled = Self::ALL
+            .into_iter()
+            .filter(|encoding| encoding.is_enabled())
+            .map(Self::as_str)
+            .collect::<Vec<_>>();
+        (!enabled.is_empty()).then(|| enabled.join(", "))
+    }
+
+    pub fn encode(self, body: &[u8]) -> RestResult<RestBytes> {
+        encode_with(self, body)
+            .ok_or_else(|| self.disabled())?
+            .map(RestBytes::from)
+            .map_err(|err| RestError::internal(format!("{self} encoding failed: {err}")))
+    }
+
+    /// Decode a complete body.
+    pub fn decode(self, body: &[u8]) -> RestResult<RestBytes> {
+        RestBodyDecoder::new(self)
+            .ok_or_else(|| self.disabled())?
+            .decode_all(body)
+    }
+
+    fn disabled(self) -> RestError {
+        let feature = match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+            Self::Brotli => "brotli",
+            Self::Zstd => "zstd",
+        };
+        RestError::internal(format!(
+            "{self} support is not enabled (cargo feature `{feature}`)"
+        ))
+    }
+}
+
+impl fmt::Display for RestContentEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Opt-in request body compression, set with
+/// [`RestRequest::with_body_compression`](crate::RestRequest::with_body_compression).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RestBodyCompression {
+    pub encoding: RestContentEncoding,
+    /// Bodies shorter than this are sent uncompressed.
+    pub min_bytes: usize,
+}
+
+/// `None` when the encoding's feature is disabled.
+#[allow(unused_variables)] // `body` is unused when no encoding feature is enabled
+fn encode_with(encoding: RestContentEncoding, body: &[u8]) -> Option<io::Result<Vec<u8>>> {
+    match encoding {
+        #[cfg(feature = "gzip")]
+        RestContentEncoding::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            Some(encoder.write_all(body).and_then(|_| encoder.finish()))
+        }
+        #[cfg(feature = "deflate")]
+        RestContentEncoding::Deflate => {
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            Some(encoder.write_all(body).and_then(|_| encoder.finish()))
+        }
+        #[cfg(feature = "brotli")]
+        RestContentEncoding::Brotli => {
+            let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+            Some(
+                encoder
+                    .write_all(body)
+                    .and_then(|_| encoder.flush())
+                    .map(|_| encoder.into_inner()),
+            )
+        }
+        #[cfg(feature = "zstd")]
+        RestContentEncoding::Zstd => Some(zstd::stream::encode_all(body, 3)),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// A write-side decompressor whose output accumulates in a `Vec` that is drained after each
+/// call.
+trait ChunkDecoder: Send {
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>>;
+    fn finish(&mut self) -> io::Result<Vec<u8>>;
+}
+
+#[cfg(feature = "gzip")]
+impl ChunkDecoder for flate2::write::GzDecoder<Vec<u8>> {
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>> {
+        self.write_all(chunk)?;
+        Ok(std::mem::take(self.get_mut()))
+    }
+
+    fn finish(&mut self) -> io::Result<Vec<u8>> {
+        self.try_finish()?;
+        Ok(std::mem::take(self.get_mut()))
+    }
+}
+
+/// Zlib-wrapped deflate driven through `flate2::Decompress`, which reports the end of the
+/// stream; `flate2::write::ZlibDecoder` finishes quietly on a truncated body.
+#[cfg(feature = "deflate")]
+struct ZlibChunkDecoder {
+    inner: flate2::Decompress,
+    ended: bool,
+}
+
+#[cfg(feature = "deflate")]
+impl ZlibChunkDecoder {
+    fn new() -> Self {
+        Self {
+            inner: flate2::Decompress::new(true),
+            ended: false,
+        }
+    }
+
+    /// Decompress all of `input`, growing the output until the decompressor stops making
+    /// progress. Bytes after the end of the stream are ignored.
+    fn run(&mut self, mut input: &[u8]) -> io::Result<Vec<u8>> {
+        let mut output = Vec::with_capacity(input.len().saturating_mul(4).max(1024));
+        while !self.ended {
+            if output.len() == output.capacity() {
+                output.reserve(output.capacity());
+            }
+            let (read, written) = (self.inner.total_in(), output.len());
+            let status = self
+                .inner
+                .decompress_vec(input, &mut output, flate2::FlushDecompress::None)
+                .map_err(io::Error::other)?;
+            let consumed = (self.inner.total_in() - read) as usize;
+            input = &input[consumed..];
+            self.ended = status == flate2::Status::StreamEnd;
+            let stalled = consumed == 0 && output.len() == written;
+            if stalled || (input.is_empty() && output.len() < output.capacity()) {
+                break;
+            }
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(feature = "deflate")]
+impl ChunkDecoder for ZlibChunkDecoder {
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>> {
+        self.run(chunk)
+    }
+
+    /// Fails when the stream ended before deflate's final block and the zlib checksum.
+    fn finish(&mut self) -> io::Result<Vec<u8>> {
+        let tail = self.run(&[])?;
+        if !self.ended {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "deflate stream ended before its final block",
+            ));
+        }
+        Ok(tail)
+    }
+}
+
+#[cfg(feature = "brotli")]
+impl ChunkDecoder for brotli::DecompressorWriter<Vec<u8>> {
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>> {
+        self.write_all(chunk)?;
+        Ok(std::mem::take(self.get_mut()))
+    }
+
+    /// Fails when the stream ended before brotli's final block.
+    fn finish(&mut self) -> io::Result<Vec<u8>> {
+        self.close()?;
+        Ok(std::mem::take(self.get_mut()))
+    }
+}
+
+/// Zstd driven through the raw streaming decoder, whose size hint reaches 0 only at the end of
+/// a frame; `zstd::stream::write::Decoder` finishes quietly on a truncated frame.
+#[cfg(feature = "zstd")]
+struct ZstdChunkDecoder {
+    inner: zstd::stream::raw::Decoder<'static>,
+    frame_complete: bool,
+}
+
+#[cfg(feature = "zstd")]
+impl ZstdChunkDecoder {
+    fn new() -> io::Result<Self> {
+        Ok(Self {
+            inner: zstd::stream::raw::Decoder::new()?,
+            frame_complete: false,
+        })
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl ChunkDecoder for ZstdChunkDecoder {
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>> {
+        use zstd::stream::raw::{InBuffer, Operation, OutBuffer};
+
+        let mut input = InBuffer::around(chunk);
+        let mut decoded = Vec::new();
+        let mut scratch = [0_u8; 16 * 1024];
+        loop {
+            let mut output = OutBuffer::around(&mut scratch[..]);
+            let hint = self.inner.run(&mut input, &mut output)?;
+            let written = output.pos();
+            decoded.extend_from_slice(&scratch[..written]);
+            if written > 0 || input.pos() > 0 {
+                self.frame_complete = hint == 0;
+            }
+            if input.pos() == chunk.len() && written < scratch.len() {
+                return Ok(decoded);
+            }
+        }
+    }
+
+    /// Fails when the body ended inside a frame.
+    fn finish(&mut self) -> io::Result<Vec<u8>> {
+        let tail = self.write_chunk(&[])?;
+        if !self.frame_complete {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "zstd stream ended inside a frame",
+            ));
+        }
+        Ok(tail)
+    }
+}
+
+fn decoder_for(encoding: RestContentEncoding) -> Option<Box<dyn ChunkDecoder>> {
+    match encoding {
+        #[cfg(feature = "gzip")]
+        RestContentEncoding::Gzip => Some(Box::new(flate2::write::GzDecoder::new(Vec::new()))),
+        #[cfg(feature = "deflate")]
+        RestContentEncoding::Deflate => Some(Box::new(ZlibChunkDecoder::new())),
+        #[cfg(feature = "brotli")]
+        RestContentEncoding::Brotli => {
+            Some(Box::new(brotli::DecompressorWriter::new(Vec::new(), 4096)))
+        }
+        #[cfg(feature = "zstd")]
+        RestContentEncoding::Zstd => ZstdChunkDecoder::new()
+            .ok()
+            .map(|decoder| Box::new(decoder) as Box<dyn ChunkDecoder>),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// Wire bytes handed to a decompressor per call. A decompression bomb can still expand one
+/// slice a thousandfold or more, but that bounds the overshoot before `limit` is checked.
+const DECODE_SLICE_BYTES: usize = 512;
+
+/// Incremental decoder: feed wire chunks with `write`, then call `finish` once.
+pub(crate) struct RestBodyDecoder {
+    encoding: RestContentEncoding,
+    inner: Box<dyn ChunkDecoder>,
+    /// Decoded bytes allowed before failing with `BodyTooLarge`.
+    limit: Option<u64>,
+    decoded: u64,
+}
+
+impl RestBodyDecoder {
+    /// `None` when the encoding's feature is disabled.
+    pub(crate) fn new(encoding: RestContentEncoding) -> Option<Self> {
+        decoder_for(encoding).map(|inner| Self {
+            encoding,
+            inner,
+            limit: None,
+            decoded: 0,
+        })
+    }
+
+    /// Fail as soon as the decoded output exceeds `limit` bytes, typically the request's
+    /// `max_body_bytes`.
+    pub(crate) fn with_limit(mut self, limit: Option<u64>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Decoder for a response's `Content-Encoding`. Identity, stacked, unknown and disabled
+    /// encodings yield `None`, leaving the body as received.
+    pub(crate) fn for_headers<K: AsRef<str>>(headers: &[(K, RestBytes)]) -> Option<Self> {
+        headers::header(headers, "content-encoding")
+            .and_then(RestContentEncoding::parse)
+            .and_then(Self::new)
+    }
+
+    pub(crate) fn encoding(&self) -> RestContentEncoding {
+        self.encoding
+    }
+
+    /// Decoded output available after `chunk`; may be empty. The chunk is fed in
+    /// `DECODE_SLICE_BYTES` slices so the limit is checked as the output grows.
+    pub(crate) fn write(&mut self, chunk: &[u8]) -> RestResult<RestBytes> {
+        let mut output = Vec::new();
+        for slice in chunk.chunks(DECODE_SLICE_BYTES) {
+            let decoded = self.inner.write_chunk(slice);
+            let decoded = self.checked(decoded)?;
+            if output.is_empty() {
+                output = decoded;
+            } else {
+                output.extend_from_slice(&decoded);
+            }
+        }
+        Ok(output.into())
+    }
+
+    /// Remaining output; fails when the encoded stream was truncated.
+    pub(crate) fn finish(&mut self) -> RestResult<RestBytes> {
+        let decoded = self.inner.finish();
+        self.checked(decoded).map(RestBytes::from)
+    }
+
+    /// Decode a complete body.
+    pub(crate) fn decode_all(mut self, body: &[u8]) -> RestResult<RestBytes> {
+        let head = self.write(body)?;
+        let tail = self.finish()?;
+        if tail.is_empty() {
+            return Ok(head);
+        }
+        let mut decoded = Vec::with_capacity(head.len() + tail.len());
+        decoded.extend_from_slice(&head);
+        decoded.extend_from_slice(&tail);
+        Ok(decoded.into())
+    }
+
+    fn checked(&mut self, decoded: io::Result<Vec<u8>>) -> RestResult<Vec<u8>> {
+        let decoded = decoded.map_err(|err| RestError::decode(self.encoding, err.to_string()))?;
+        self.decoded += decoded.len() as u64;
+        match self.limit {
+            Some(limit) if self.decoded > limit => {
+                Err(RestError::body_too_large(limit, self.decoded, None))
+            }
+            _ => Ok(decoded),
+        }
+    }
+}
+
+/// Drop `Content-Encoding` and the wire `Content-Length` once a body has been decoded.
+pub(crate) fn strip_encoding_headers<K: AsRef<str>>(headers: &mut Vec<(K, RestBytes)>) {
+    headers.retain(|(name, _)| {
+        let name = name.as_ref();
+        !name.eq_ignore_ascii_case("content-encoding")
+            && !name.eq_ignore_ascii_case("content-length")
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tokens_and_lists_enabled_encodings() {
+        assert_eq!(
+            RestContentEncoding::parse(" GZIP "),
+            Some(RestContentEncoding::Gzip)
+        );
+        assert_eq!(
+            RestContentEncoding::parse("x-gzip"),
+            Some(RestContentEncoding::Gzip)
+        );
+        assert_eq!(
+            RestContentEncoding::parse("br"),
+            Some(RestContentEncoding::Brotli)
+        );
+        assert_eq!(RestContentEncoding::parse("gzip, br"), None);
+        assert_eq!(RestContentEncoding::parse("identity"), None);
+
+        let expected = RestContentEncoding::ALL
+            .into_iter()
+            .filter(|encoding| encoding.is_enabled())
+            .map(RestContentEncoding::as_str)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            RestContentEncoding::accept_encoding(),
+            (!expected.is_empty()).then(|| expected.join(", "))
+        );
+    }
+
+    #[test]
+    fn enabled_encodings_round_trip_in_small_chunks() {
+        let body = b"{\"id\":1,\"side\":\"buy\"}\n".repeat(200);
+        for encoding in RestContentEncoding::ALL {
+            if !encoding.is_enabled() {
+                assert!(encoding.encode(&body).is_err());
+                assert!(RestBodyDecoder::new(encoding).is_none());
+                continue;
+            }
+            let encoded = encoding.encode(&body).expect("encode");
+            assert!(encoded.len() < body.len(), "{encoding} compresses");
+
+            let mut decoder = RestBodyDecoder::new(encoding).expect("decoder");
+            let mut decoded = Vec::new();
+            for chunk in encoded.chunks(7) {
+                decoded.extend_from_slice(&decoder.write(chunk).expect("decode chunk"));
+            }
+            decoded.extend_from_slice(&decoder.finish().expect("finish"));
+            assert_eq!(decoded, body, "{encoding} round trip");
+            assert_eq!(encoding.decode(&encoded).expect("decode"), body);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Client-side limits on in-flight requests, globally and per host, with a bounded queue wait.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reqwest::Url;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::adapter::{RestError, RestResult};
+
+/// In-flight request limits for a [`Client`](crate::Client). Every attempt, retries included,
+/// takes a slot from its host and from the global pool, and waits in FIFO order while none is
+/// free. A limit of 0 means no limit, the same as `None`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestConcurrencyLimits {
+    /// Requests in flight across all hosts.
+    pub global: Option<usize>,
+    /// Requests in flight to any one host (`host[:port]`), unless overridden in `hosts`.
+    pub per_host: Option<usize>,
+    /// Per-host overrides; 0 lifts the `per_host` limit for that host.
+    pub hosts: HashMap<String, usize>,
+    /// Longest a request waits for a slot before failing with `RestErrorKind::QueueTimeout`;
+    /// `None` waits indefinitely.
+    pub queue_timeout: Option<Duration>,
+}
+
+impl RestConcurrencyLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_global_limit(mut self, max_in_flight: usize) -> Self {
+        self.global = Some(max_in_flight);
+        self
+    }
+
+    pub fn with_per_host_limit(mut self, max_in_flight: usize) -> Self {
+        self.per_host = Some(max_in_flight);
+        self
+    }
+
+    /// Limit for one host, as written in the URL (`api.example.com` or `127.0.0.1:8080`).
+    pub fn with_host_limit(mut self, host: impl Into<String>, max_in_flight: usize) -> Self {
+        self.hosts.insert(host.into(), max_in_flight);
+        self
+    }
+
+    pub fn with_queue_timeout(mut self, queue_timeout: Duration) -> Self {
+        self.queue_timeout = Some(queue_timeout);
+        self
+    }
+
+    fn host_limit(&self, host: &str) -> Option<usize> {
+        self.hosts
+            .get(host)
+            .copied()
+            .or(self.per_host)
+            .filter(|limit| *limit > 0)
+    }
+}
+
+/// Shared by every clone of a `Client`.
+#[derive(Debug)]
+pub(crate) struct RestConcurrencyLimiter {
+    limits: RestConcurrencyLimits,
+    global: Option<Arc<Semaphore>>,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+/// Slots held for one request; dropping it frees them.
+#[derive(Debug)]
+pub(crate) struct RestConcurrencyPermit {
+    host: Option<OwnedSemaphorePermit>,
+    global: Option<OwnedSemaphorePermit>,
+    pub(crate) queue_wait: Duration,
+}
+
+impl RestConcurrencyLimiter {
+    pub(crate) fn new(limits: RestConcurrencyLimits) -> Self {
+        Self {
+            global: limits
+                .global
+                .filter(|limit| *limit > 0)
+                .map(|limit| Arc::new(Semaphore::new(limit))),
+            limits,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait for a slot for `url`: the host's first, then the global one, so a request queued
+    /// behind a busy host does not hold a global slot.
+    pub(crate) async fn acquire(&self, url: &str) -> RestResult<RestConcurrencyPermit> {
+        let started = Instant::now();
+        let host = host_key(url);
+        let host_semaphore = host.as_deref().and_then(|host| self.host_semaphore(host));
+        let mut waiting_on = host.clone();
+        let acquire = async {
+            let host_permit = match host_semaphore {
+                Some(semaphore) => Some(semaphore.acquire_owned().await.map_err(closed)?),
+                None => None,
+            };
+            waiting_on = None;
+            let global_permit = match &self.global {
+                Some(semaphore) => Some(semaphore.clone().acquire_owned().await.map_err(closed)?),
+                None => None,
+            };
+            Ok::<_, RestError>((host_permit, global_permit))
+        };
+        let (host, global) = match self.limits.queue_timeout {
+            Some(queue_timeout) => tokio::time::timeout(queue_timeout, acquire)
+                .await
+                .map_err(|_| {
+                    RestError::queue_timeout(waiting_on.clone(), started.elapsed(), queue_timeout)
+                })??,
+            None => acquire.await?,
+        };
+        Ok(RestConcurrencyPermit {
+            host,
+            global,
+            queue_wait: started.elapsed(),
+        })
+    }
+
+    fn host_semaphore(&self, host: &str) -> Option<Arc<Semaphore>> {
+        let limit = self.limits.host_limit(host)?;
+        let mut hosts = self
+            .hosts
+            .lock()
+            .expect("concurrency limiter mutex poisoned while selecting host slots");
+        Some(
+            hosts
+                .entry(host.to_string())
+                .or_insert_with(|| Arc::new(Semaphore::new(limit)))
+                .clone(),
+        )
+    }
+}
+
+fn closed(_: tokio::sync::AcquireError) -> RestError {
+    RestError::internal("concurrency limiter closed")
+}
+
+/// `host[:port]` with the port only when the URL spells it out; `None` for unparseable URLs,
+/// which the transport rejects anyway.
+pub(crate) fn host_key(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    Some(match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_keys_keep_explicit_ports_only() {
+        assert_eq!(
+            host_key("https://api.example.com/v1/ticker").as_deref(),
+            Some("api.example.com")
+        );
+        assert_eq!(
+            host_key("https://api.example.com:443/v1").as_deref(),
+            Some("api.example.com")
+        );
+        assert_eq!(
+            host_key("http://127.0.0.1:8080/health").as_deref(),
+            Some("127.0.0.1:8080")
+        );
+        assert_eq!(host_key("not a url"), None);
+    }
+
+    #[tokio::test]
+    async fn host_overrides_take_precedence_over_the_default() {
+        let limiter = RestConcurrencyLimiter::new(
+            RestConcurrencyLimits::new()
+                .with_per_host_limit(4)
+                .with_host_limit("slow.example.com", 1)
+                .with_queue_timeout(Duration::from_millis(5)),
+        );
+        let held = limiter
+            .acquire("https://slow.example.com/a")
+            .await
+            .expect("first slot");
+        let err = limiter
+            .acquire("https://slow.example.com/b")
+            .await
+            .expect_err("override allows one");
+        assert!(matches!(
+            err,
+            RestError::QueueTimeout { host: Some(ref host), .. } if host == "slow.example.com"
+        ));
+        let _fast = [
+            limiter
+                .acquire("https://fast.example.com/a")
+                .await
+                .expect("default limit"),
+            limiter
+                .acquire("https://fast.example.com/b")
+                .await
+                .expect("default limit"),
+        ];
+        drop(held);
+        limiter
+            .acquire("https://slow.example.com/c")
+            .await
+            .expect("slot freed");
+    }
+
+    #[tokio::test]
+    async fn zero_limits_mean_no_limit() {
+        let limiter = RestConcurrencyLimiter::new(
+            RestConcurrencyLimits::new()
+                .with_global_limit(0)
+                .with_per_host_limit(1)
+                .with_host_limit("open.example.com", 0)
+                .with_queue_timeout(Duration::from_millis(5)),
+        );
+        assert!(limiter.global.is_none());
+        let _held = [
+            limiter
+                .acquire("https://open.example.com/a")
+                .await
+                .expect("no host limit"),
+            limiter
+                .acquire("https://open.example.com/b")
+                .await
+                .expect("no host limit"),
+        ];
+        let _other = limiter
+            .acquire("https://api.example.com/a")
+            .await
+            .expect("default limit");
+        limiter
+            .acquire("https://api.example.com/b")
+            .await
+            .expect_err("default limit still applies elsewhere");
+    }
+}
@@ -0,0 +1,508 @@
+//! Seeded chaos transport that wraps any [`RestTransport`] and injects faults.
+//!
+//! Every request draws the same number of values from a splitmix64 stream regardless of which
+//! faults fire, so a given seed and request order always produces the same fault sequence.
+//! Concurrent callers race for draws; replay a failure with the same request order to reproduce it.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::{Bytes, BytesMut};
+use reqwest::Method;
+
+use crate::adapter::{
+    RestError, RestFuture, RestRawResponse, RestRequest, RestResponse, RestResult, RestTransport,
+    raw_header_response,
+};
+use crate::headers::{RestHeaderNames, RestRawHeaderResponse};
+use crate::stream::RestStreamingResponse;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaultInjectionConfig {
+    pub seed: u64,
+    pub latency_rate: f64,
+    pub latency_min: Duration,
+    pub latency_max: Duration,
+    pub connect_error_rate: f64,
+    pub send_error_rate: f64,
+    pub receive_error_rate: f64,
+    pub server_error_rate: f64,
+    pub server_error_statuses: Vec<u16>,
+    pub truncate_body_rate: f64,
+    pub corrupt_json_rate: f64,
+}
+
+impl FaultInjectionConfig {
+    /// All rates start at zero, so the transport is a pass-through until faults are enabled.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            latency_rate: 0.0,
+            latency_min: Duration::ZERO,
+            latency_max: Duration::ZERO,
+            connect_error_rate: 0.0,
+            send_error_rate: 0.0,
+            receive_error_rate: 0.0,
+            server_error_rate: 0.0,
+            server_error_statuses: vec![500, 502, 503, 504],
+            truncate_body_rate: 0.0,
+            corrupt_json_rate: 0.0,
+        }
+    }
+
+    pub fn with_latency(mut self, rate: f64, min: Duration, max: Duration) -> Self {
+        self.latency_rate = rate;
+        self.latency_min = min;
+        self.latency_max = max.max(min);
+        self
+    }
+
+    pub fn with_connect_errors(mut self, rate: f64) -> Self {
+        self.connect_error_rate = rate;
+        self
+    }
+
+    pub fn with_send_errors(mut self, rate: f64) -> Self {
+        self.send_error_rate = rate;
+        self
+    }
+
+    pub fn with_receive_errors(mut self, rate: f64) -> Self {
+        self.receive_error_rate = rate;
+        self
+    }
+
+    pub fn with_server_errors(
+        mut self,
+        rate: f64,
+        statuses: impl IntoIterator<Item = u16>,
+    ) -> Self {
+        self.server_error_rate = rate;
+        let statuses = statuses.into_iter().collect::<Vec<_>>();
+        if !statuses.is_empty() {
+            self.server_error_statuses = statuses;
+        }
+        self
+    }
+
+    pub fn with_truncated_bodies(mut self, rate: f64) -> Self {
+        self.truncate_body_rate = rate;
+        self
+    }
+
+    pub fn with_corrupted_json(mut self, rate: f64) -> Self {
+        self.corrupt_json_rate = rate;
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InjectedFault {
+    Latency(Duration),
+    ConnectError,
+    SendError,
+    ReceiveError,
+    ServerError(u16),
+    TruncatedBody {
+        original_len: usize,
+        kept_len: usize,
+    },
+    CorruptedJson {
+        offset: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FaultInjectionRecord {
+    pub sequence: u64,
+    pub method: Method,
+    pub url: String,
+    pub faults: Vec<InjectedFault>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct FaultRng(u64);
+
+impl FaultRng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Faults chosen for one request, drawn up front so the RNG stream stays aligned.
+#[derive(Clone, Copy, Debug, Default)]
+struct FaultDraw {
+    latency: Option<Duration>,
+    connect: bool,
+    send: bool,
+    receive: bool,
+    server_status: Option<u16>,
+    truncate_at: Option<f64>,
+    corrupt_at: Option<f64>,
+}
+
+impl FaultDraw {
+    /// Whether a fault rewrites or fails the body, which needs the whole body in hand.
+    fn touches_body(&self) -> bool {
+        self.receive || self.truncate_at.is_some() || self.corrupt_at.is_some()
+    }
+}
+
+#[derive(Debug)]
+struct FaultState {
+    rng: FaultRng,
+    sequence: u64,
+    log: Vec<FaultInjectionRecord>,
+}
+
+pub struct FaultInjectingTransport<T> {
+    inner: Arc<T>,
+    config: FaultInjectionConfig,
+    state: Arc<Mutex<FaultState>>,
+}
+
+impl<T> Clone for FaultInjectingTransport<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            config: self.config.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T> FaultInjectingTransport<T>
+where
+    T: RestTransport + 'static,
+{
+    pub fn new(inner: T, config: FaultInjectionConfig) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            state: Arc::new(Mutex::new(FaultState {
+                rng: FaultRng(config.seed),
+                sequence: 0,
+                log: Vec::new(),
+            })),
+            config,
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.config.seed
+    }
+
+    pub fn config(&self) -> &FaultInjectionConfig {
+        &self.config
+    }
+
+    /// Requests that had at least one fault injected, in draw order.
+    pub fn injected_faults(&self) -> Vec<FaultInjectionRecord> {
+        self.state
+            .lock()
+            .expect("fault injection mutex poisoned while reading log")
+            .log
+            .clone()
+    }
+
+    /// Rewind the RNG to the configured seed and clear the fault log.
+    pub fn reset(&self) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("fault injection mutex poisoned while resetting");
+        state.rng = FaultRng(self.config.seed);
+        state.sequence = 0;
+        state.log.clear();
+    }
+
+    fn draw(&self) -> (u64, FaultDraw) {
+        let config = &self.config;
+        let mut state = self
+            .state
+            .lock()
+            .expect("fault injection mutex poisoned while drawing faults");
+        let rng = &mut state.rng;
+        let mut hit = |rate: f64| rng.next_f64() < rate;
+
+        let latency_hit = hit(config.latency_rate);
+        let connect = hit(config.connect_error_rate);
+        let send = hit(config.send_error_rate);
+        let receive = hit(config.receive_error_rate);
+        let server_hit = hit(config.server_error_rate);
+        let truncate_hit = hit(config.truncate_body_rate);
+        let corrupt_hit = hit(config.corrupt_json_rate);
+        let latency_fraction = rng.next_f64();
+        let status_pick = rng.next_u64();
+        let truncate_fraction = rng.next_f64();
+        let corrupt_fraction = rng.next_f64();
+
+        let span = config.latency_max.saturating_sub(config.latency_min);
+        let draw = FaultDraw {
+            latency: latency_hit.then(|| config.latency_min + span.mul_f64(latency_fraction)),
+            connect,
+            send,
+            receive,
+            server_status: server_hit
+                .then(|| {
+                    let statuses = &config.server_error_statuses;
+                    statuses
+                        .get((status_pick % statuses.len().max(1) as u64) as usize)
+                        .copied()
+                })
+                .flatten(),
+            truncate_at: truncate_hit.then_some(truncate_fraction),
+            corrupt_at: corrupt_hit.then_some(corrupt_fraction),
+        };
+        let sequence = state.sequence;
+        state.sequence += 1;
+        (sequence, draw)
+    }
+
+    fn record(&self, sequence: u64, request: &RestRequest, faults: Vec<InjectedFault>) {
+        if faults.is_empty() {
+            return;
+        }
+        self.state
+            .lock()
+            .expect("fault injection mutex poisoned while recording faults")
+            .log
+            .push(FaultInjectionRecord {
+                sequence,
+                method: request.method.clone(),
+                url: request.url.clone(),
+                faults,
+            });
+    }
+
+    /// Apply the pre-request faults. Returns `Some(result)` when the request must not be
+    /// forwarded to the inner transport.
+    async fn before(
+        &self,
+        draw: &FaultDraw,
+        faults: &mut Vec<InjectedFault>,
+    ) -> Option<RestResult<u16>> {
+        if let Some(latency) = draw.latency {
+            faults.push(InjectedFault::Latency(latency));
+            tokio::time::sleep(latency).await;
+        }
+        if draw.connect {
+            faults.push(InjectedFault::ConnectError);
+            return Some(Err(RestError::connect(
+                "fault injection: connect error",
+                None,
+                true,
+            )));
+        }
+        if draw.send {
+            faults.push(InjectedFault::SendError);
+            return Some(Err(RestError::send(
+                "fault injection: send error",
+                None,
+                true,
+            )));
+        }
+        if let Some(status) = draw.server_status {
+            faults.push(InjectedFault::ServerError(status));
+            return Some(Ok(status));
+        }
+        None
+    }
+
+    fn after(draw: &FaultDraw, body: Bytes, faults: &mut Vec<InjectedFault>) -> RestResult<Bytes> {
+        if draw.receive {
+            faults.push(InjectedFault::ReceiveError);
+            return Err(RestError::receive(
+                "fault injection: receive error",
+                None,
+                true,
+            ));
+        }
+        let mut body = body;
+        if let Some(fraction) = draw.truncate_at {
+            let original_len = body.len();
+            let kept_len = ((original_len as f64) * fraction) as usize;
+            body.truncate(kept_len.min(original_len.saturating_sub(1)));
+            faults.push(InjectedFault::TruncatedBody {
+                original_len,
+                kept_len: body.len(),
+            });
+        }
+        if let Some(fraction) = draw.corrupt_at {
+            let (corrupted, offset) = corrupt_json(&body, fraction);
+            faults.push(InjectedFault::CorruptedJson { offset });
+            body = corrupted;
+        }
+        Ok(body)
+    }
+
+    async fn run(&self, request: RestRequest) -> RestResult<RestResponse> {
+        let (sequence, draw) = self.draw();
+        let mut faults = Vec::new();
+        let result = match self.before(&draw, &mut faults).await {
+            Some(Ok(status)) => Ok(injected_server_error(status)),
+            Some(Err(err)) => Err(err),
+            None => match self.inner.execute(request.clone()).await {
+                Ok(mut response) => {
+                    let body = std::mem::take(&mut response.body);
+                    Self::after(&draw, body, &mut faults).map(|body| {
+                        response.body = body;
+                        response
+                    })
+                }
+                Err(err) => Err(err),
+            },
+        };
+        self.record(sequence, &request, faults);
+        result
+    }
+
+    async fn run_raw(&self, request: RestRequest) -> RestResult<RestRawResponse> {
+        let (sequence, draw) = self.draw();
+        let mut faults = Vec::new();
+        let result = match self.before(&draw, &mut faults).await {
+            Some(Ok(status)) => {
+                let response = injected_server_error(status);
+                Ok((response.status, response.body, response.elapsed))
+            }
+            Some(Err(err)) => Err(err),
+            None => match self.inner.execute_raw(request.clone()).await {
+                Ok((status, body, elapsed)) => {
+                    Self::after(&draw, body, &mut faults).map(|body| (status, body, elapsed))
+                }
+                Err(err) => Err(err),
+            },
+        };
+        self.record(sequence, &request, faults);
+        result
+    }
+
+    async fn run_raw_with_headers(
+        &self,
+        request: RestRequest,
+        keep: RestHeaderNames,
+    ) -> RestResult<RestRawHeaderResponse> {
+        let (sequence, draw) = self.draw();
+        let mut faults = Vec::new();
+        let result = match self.before(&draw, &mut faults).await {
+            Some(Ok(status)) => Ok(raw_header_response(injected_server_error(status), &keep)),
+            Some(Err(err)) => Err(err),
+            None => match self
+                .inner
+                .execute_raw_with_headers(request.clone(), keep)
+                .await
+            {
+                Ok(mut response) => {
+                    let body = std::mem::take(&mut response.body);
+                    Self::after(&draw, body, &mut faults).map(|body| {
+                        response.body = body;
+                        response
+                    })
+                }
+                Err(err) => Err(err),
+            },
+        };
+        self.record(sequence, &request, faults);
+        result
+    }
+
+    /// Head faults apply as for buffered requests. Body faults buffer the inner stream first;
+    /// otherwise the body streams through untouched.
+    async fn run_stream(&self, request: RestRequest) -> RestResult<RestStreamingResponse> {
+        let (sequence, draw) = self.draw();
+        let mut faults = Vec::new();
+        let result = match self.before(&draw, &mut faults).await {
+            Some(Ok(status)) => Ok(RestStreamingResponse::buffered(injected_server_error(
+                status,
+            ))),
+            Some(Err(err)) => Err(err),
+            None => match self.inner.execute_stream(request.clone()).await {
+                Ok(response) if draw.touches_body() => match response.into_response().await {
+                    Ok(mut response) => {
+                        let body = std::mem::take(&mut response.body);
+                        Self::after(&draw, body, &mut faults).map(|body| {
+                            response.body = body;
+                            RestStreamingResponse::buffered(response)
+                        })
+                    }
+                    Err(err) => Err(err),
+                },
+                other => other,
+            },
+        };
+        self.record(sequence, &request, faults);
+        result
+    }
+}
+
+fn injected_server_error(status: u16) -> RestResponse {
+    RestResponse::new(
+        status,
+        Vec::new(),
+        Bytes::from_static(b"fault injection: server error"),
+        Duration::ZERO,
+    )
+}
+
+/// Replace one structural JSON byte with a control character so the document cannot parse.
+fn corrupt_json(body: &Bytes, fraction: f64) -> (Bytes, usize) {
+    let candidates = body
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| matches!(byte, b'{' | b'}' | b'[' | b']' | b':' | b',' | b'"'))
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    let mut corrupted = BytesMut::from(body.as_ref());
+    if candidates.is_empty() {
+        let offset = corrupted.len();
+        corrupted.extend_from_slice(b"\x01");
+        return (corrupted.freeze(), offset);
+    }
+    let pick = ((candidates.len() as f64) * fraction) as usize;
+    let offset = candidates[pick.min(candidates.len() - 1)];
+    corrupted[offset] = 0x01;
+    (corrupted.freeze(), offset)
+}
+
+impl<T> RestTransport for FaultInjectingTransport<T>
+where
+    T: RestTransport + 'static,
+{
+    fn execute(&self, request: RestRequest) -> RestFuture<RestResult<RestResponse>> {
+        let transport = self.clone();
+        Box::pin(async move { transport.run(request).await })
+    }
+
+    fn execute_raw(&self, request: RestRequest) -> RestFuture<RestResult<RestRawResponse>> {
+        let transport = self.clone();
+        Box::pin(async move { transport.run_raw(request).await })
+    }
+
+    fn execute_raw_with_headers(
+        &self,
+        request: RestRequest,
+        keep: RestHeaderNames,
+    ) -> RestFuture<RestResult<RestRawHeaderResponse>> {
+        let transport = self.clone();
+        Box::pin(async move { transport.run_raw_with_headers(request, keep).await })
+    }
+
+    fn execute_stream(
+        &self,
+        request: RestRequest,
+    ) -> RestFuture<RestResult<RestStreamingResponse>> {
+        let transport = self.clone();
+        Box::pin(async move { transport.run_stream(request).await })
+    }
+}
@@ -0,0 +1,401 @@
+//! Typed, versioned REST contract fixture files.
+//!
+//! A fixture is one JSON document holding live-capture provenance at the top level (the fields
+//! checked by the live-request gate) plus the captured request and response:
+//!
+//! ```json
+//! {
+//!   "format_version": 1,
+//!   "source": "live_capture",
+//!   "captured_at_ms": 1760000000000,
+//!   "capture_command": "cargo run --bin capture -- instruments",
+//!   "exchange_env": "deribit_testnet",
+//!   "capture_mode": "contracts=instruments",
+//!   "contract_id": "instruments",
+//!   "request": {
+//!     "method": "GET",
+//!     "url": "https://test.deribit.com/api/v2/public/get_instruments",
+//!     "headers": [["accept", "application/json"]],
+//!     "body": null
+//!   },
+//!   "response": {
+//!     "status": 200,
+//!     "headers": [["content-type", "application/json"]],
+//!     "body": { "encoding": "text", "data": "{\"result\":[]}" }
+//!   }
+//! }
+//! ```
+//!
+//! Bodies that are not valid UTF-8 are stored with `"encoding": "base64"`.
+//!
+//! Files in the older flat shape (format_version 0: provenance plus top-level `url`, `status`
+//! and a text `body`) still load, as a `GET` of `url` answered with `status` and `body`.
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use sonic_rs::JsonValueTrait;
+
+use crate::adapter::{RestBytes, RestError, RestRequest, RestResponse, RestResult};
+use crate::mock::MockResponse;
+
+pub const REST_FIXTURE_FORMAT_VERSION: u32 = 1;
+pub const REST_FIXTURE_LIVE_CAPTURE_SOURCE: &str = "live_capture";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestFixtureProvenance {
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub captured_at_ms: u64,
+    #[serde(default)]
+    pub capture_command: String,
+    #[serde(default)]
+    pub exchange_env: String,
+    /// Capture selection active when the fixture was written, e.g. `all` or `contracts=orders.*`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_mode: Option<String>,
+}
+
+impl RestFixtureProvenance {
+    /// Provenance for a capture taken now.
+    pub fn live_capture(
+        capture_command: impl Into<String>,
+        exchange_env: impl Into<String>,
+    ) -> Self {
+        Self {
+            source: REST_FIXTURE_LIVE_CAPTURE_SOURCE.to_string(),
+            captured_at_ms: now_ms(),
+            capture_command: capture_command.into(),
+            exchange_env: exchange_env.into(),
+            capture_mode: None,
+        }
+    }
+
+    pub fn with_capture_mode(mut self, capture_mode: impl Into<String>) -> Self {
+        self.capture_mode = Some(capture_mode.into());
+        self
+    }
+
+    pub fn is_live_capture(&self) -> bool {
+        self.source == REST_FIXTURE_LIVE_CAPTURE_SOURCE
+            && self.captured_at_ms != 0
+            && !self.capture_command.trim().is_empty()
+            && !self.exchange_env.trim().is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "encoding", content = "data", rename_all = "snake_case")]
+pub enum RestFixtureBody {
+    Text(String),
+    Base64(String),
+}
+
+impl RestFixtureBody {
+    /// Store UTF-8 bodies as text and everything else as base64.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::Text(text.to_string()),
+            Err(_) => Self::Base64(BASE64.encode(bytes)),
+        }
+    }
+
+    pub fn to_bytes(&self) -> RestResult<RestBytes> {
+        match self {
+            Self::Text(text) => Ok(RestBytes::from(text.clone())),
+            Self::Base64(encoded) => BASE64
+                .decode(encoded)
+                .map(RestBytes::from)
+                .map_err(|err| RestError::internal(format!("invalid base64 fixture body: {err}"))),
+        }
+    }
+}
+
+impl Default for RestFixtureBody {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestFixtureRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: Option<RestFixtureBody>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestFixtureResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: RestFixtureBody,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestFixture {
+    #[serde(default = "default_format_version")]
+    pub format_version: u32,
+    #[serde(flatten)]
+    pub provenance: RestFixtureProvenance,
+    #[serde(default)]
+    pub contract_id: String,
+    pub request: RestFixtureRequest,
+    pub response: RestFixtureResponse,
+}
+
+fn default_format_version() -> u32 {
+    REST_FIXTURE_FORMAT_VERSION
+}
+
+impl RestFixture {
+    pub fn from_exchange(
+        contract_id: impl Into<String>,
+        request: &RestRequest,
+        response: &RestResponse,
+        provenance: RestFixtureProvenance,
+    ) -> Self {
+        Self {
+            format_version: REST_FIXTURE_FORMAT_VERSION,
+            provenance,
+            contract_id: contract_id.into(),
+            request: RestFixtureRequest {
+                method: request.method.as_str().to_string(),
+                url: request.url.clone(),
+                headers: header_pairs(&request.headers),
+                body: request.body.as_deref().map(RestFixtureBody::from_bytes),
+            },
+            response: RestFixtureResponse {
+                status: response.status,
+                headers: header_pairs(&response.headers),
+                body: RestFixtureBody::from_bytes(&response.body),
+            },
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> RestResult<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|err| {
+            RestError::internal(format!("failed to read fixture {}: {err}", path.display()))
+        })?;
+        let parse_error = |err: sonic_rs::Error| {
+            RestError::internal(format!("failed to parse fixture {}: {err}", path.display()))
+        };
+        let root = sonic_rs::from_slice::<sonic_rs::Value>(&bytes).map_err(parse_error)?;
+        let fixture = if root.get("request").is_none() && root.get("url").is_some() {
+            sonic_rs::from_slice::<FlatRestFixture>(&bytes)
+                .map_err(parse_error)?
+                .into_fixture()
+        } else {
+            sonic_rs::from_slice::<Self>(&bytes).map_err(parse_error)?
+        };
+        if fixture.format_version > REST_FIXTURE_FORMAT_VERSION {
+            return Err(RestError::internal(format!(
+                "fixture {} uses format_version {} but this crate supports up to {}",
+                path.display(),
+                fixture.format_version,
+                REST_FIXTURE_FORMAT_VERSION
+            )));
+        }
+        Ok(fixture)
+    }
+
+    /// Write the fixture as pretty-printed JSON, creating parent directories as needed.
+    pub fn save(&self, path: impl AsRef<Path>) -> RestResult<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| {
+                RestError::internal(format!(
+                    "failed to create fixture directory {}: {err}",
+                    parent.display()
+                ))
+            })?;
+        }
+        let bytes = sonic_rs::to_vec_pretty(self)?;
+        std::fs::write(path, bytes).map_err(|err| {
+            RestError::internal(format!("failed to write fixture {}: {err}", path.display()))
+        })
+    }
+
+    pub fn method(&self) -> RestResult<Method> {
+        Method::from_bytes(self.request.method.as_bytes()).map_err(|err| {
+            RestError::internal(format!(
+                "invalid fixture request method {}: {err}",
+                self.request.method
+            ))
+        })
+    }
+
+    /// Rebuild the captured request, tagged with this fixture's contract.
+    pub fn to_request(&self) -> RestResult<RestRequest> {
+        let mut request = RestRequest::new(self.method()?, self.request.url.clone());
+        for (name, value) in &self.request.headers {
+            request = request.with_header(name.clone(), value.clone());
+        }
+        if let Some(body) = &self.request.body {
+            request = request.with_body(body.to_bytes()?);
+        }
+        if !self.contract_id.is_empty() {
+            request = request.with_fixture_contract(self.contract_id.clone());
+        }
+        Ok(request)
+    }
+
+    pub fn to_mock_response(&self) -> RestResult<MockResponse> {
+        let mut response = MockResponse::new(self.response.status, self.response.body.to_bytes()?);
+        for (name, value) in &self.response.headers {
+            response = response.with_header(name.clone(), value.clone());
+        }
+        Ok(response)
+    }
+}
+
+/// The format_version 0 layout: provenance with the exchange flattened beside it.
+#[derive(Deserialize)]
+struct FlatRestFixture {
+    #[serde(flatten)]
+    provenance: RestFixtureProvenance,
+    #[serde(default)]
+    contract_id: String,
+    url: String,
+    status: u16,
+    #[serde(default)]
+    body: String,
+}
+
+impl FlatRestFixture {
+    fn into_fixture(self) -> RestFixture {
+        RestFixture {
+            format_version: 0,
+            provenance: self.provenance,
+            contract_id: self.contract_id,
+            request: RestFixtureRequest {
+                method: Method::GET.as_str().to_string(),
+                url: self.url,
+                headers: Vec::new(),
+                body: None,
+            },
+            response: RestFixtureResponse {
+                status: self.status,
+                headers: Vec::new(),
+                body: RestFixtureBody::Text(self.body),
+            },
+        }
+    }
+}
+
+fn header_pairs(headers: &[(String, RestBytes)]) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| (name.clone(), String::from_utf8_lossy(value).into_owned()))
+        .collect()
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(body: &[u8]) -> RestFixture {
+        let request = RestRequest::post("https://example.invalid/v1/orders")
+            .with_header("accept", "application/json")
+            .with_body(RestBytes::from_static(br#"{"qty":1}"#))
+            .with_fixture_contract("orders");
+        let response = RestResponse::new(
+            201,
+            vec![("x-id".to_string(), RestBytes::from_static(b"7"))],
+            RestBytes::copy_from_slice(body),
+            std::time::Duration::ZERO,
+        );
+        RestFixture::from_exchange(
+            "orders",
+            &request,
+            &response,
+            RestFixtureProvenance::live_capture("capture", "deribit_testnet"),
+        )
+    }
+
+    #[test]
+    fn fixture_roundtrips_text_and_binary_bodies() {
+        let path = std::env::temp_dir().join(format!(
+            "shared-restapi-fixture-model-{}.json",
+            std::process::id()
+        ));
+        for body in [&br#"{"id":7}"#[..], &[0xff, 0x00, 0x10][..]] {
+            let fixture = sample(body);
+            fixture.save(&path).expect("save fixture");
+            let loaded = RestFixture::load(&path).expect("load fixture");
+            assert_eq!(loaded, fixture);
+            assert!(loaded.provenance.is_live_capture());
+
+            let response = loaded.to_mock_response().expect("mock response");
+            assert_eq!(response.status, 201);
+            assert_eq!(response.body.as_ref(), body);
+            let request = loaded.to_request().expect("request");
+            assert_eq!(request.method, Method::POST);
+            assert_eq!(request.fixture_contract.as_deref(), Some("orders"));
+        }
+        assert!(matches!(
+            sample(&[0xff]).response.body,
+            RestFixtureBody::Base64(_)
+        ));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn flat_version_zero_fixtures_load_as_get_exchanges() {
+        let path = std::env::temp_dir().join(format!(
+            "shared-restapi-fixture-model-flat-{}.json",
+            std::process::id()
+        ));
+        let flat = sonic_rs::json!({
+            "source": "live_capture",
+            "captured_at_ms": 1_u64,
+            "capture_command": "capture",
+            "exchange_env": "deribit_testnet",
+            "url": "https://example.invalid/v1/ticker",
+            "status": 200,
+            "body": "{\"last\":1}"
+        });
+        std::fs::write(&path, sonic_rs::to_vec(&flat).expect("serialize")).expect("write");
+
+        let loaded = RestFixture::load(&path).expect("flat fixture loads");
+        assert_eq!(loaded.format_version, 0);
+        assert!(loaded.provenance.is_live_capture());
+        assert_eq!(loaded.method().expect("method"), Method::GET);
+        assert_eq!(loaded.request.url, "https://example.invalid/v1/ticker");
+        let response = loaded.to_mock_response().expect("mock response");
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body.as_ref(), br#"{"last":1}"#);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn fixture_rejects_newer_format_versions() {
+        let path = std::env::temp_dir().join(format!(
+            "shared-restapi-fixture-model-future-{}.json",
+            std::process::id()
+        ));
+        let mut fixture = sample(b"{}");
+        fixture.format_version = REST_FIXTURE_FORMAT_VERSION + 1;
+        fixture.save(&path).expect("save fixture");
+        let err = RestFixture::load(&path).expect_err("future version should fail");
+        assert!(err.to_string().contains("format_version"));
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,391 @@
+//! Recording and replaying transports for REST contract fixtures.
+//!
+//! [`RecordingTransport`] writes each live exchange tagged with a `fixture_contract` into the
+//! registered success or error fixture path while fixture-capture mode is enabled for that contract.
+//! [`ReplayingTransport`] serves those files back through [`MockRestAdapter`].
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::adapter::{
+    RestError, RestFuture, RestRawResponse, RestRequest, RestResponse, RestResult, RestTransport,
+    raw_header_response,
+};
+use crate::fixture::{RestFixture, RestFixtureProvenance};
+use crate::fixture_diff::{RestFixtureDiffOptions, RestFixtureRefreshReport, diff_fixtures};
+use crate::fixture_policy::{RestFixtureRegistry, RestFixtureRequirement};
+use crate::fixture_replay::RestFixtureKind;
+use crate::headers::{RestHeaderNames, RestRawHeaderResponse};
+use crate::mock::{MockResponse, MockRestAdapter};
+use crate::redact::RestRedactionRules;
+use crate::stream::RestStreamingResponse;
+
+pub const SHARED_RESTAPI_FIXTURE_CAPTURE_COMMAND_ENV: &str =
+    "SHARED_RESTAPI_FIXTURE_CAPTURE_COMMAND";
+pub const SHARED_RESTAPI_FIXTURE_EXCHANGE_ENV_ENV: &str = "SHARED_RESTAPI_FIXTURE_EXCHANGE_ENV";
+
+pub struct RecordingTransport<T> {
+    inner: Arc<T>,
+    capture_command: Option<String>,
+    exchange_env: Option<String>,
+    registry: RestFixtureRegistry,
+    redaction: Arc<RestRedactionRules>,
+    diff_options: Arc<RestFixtureDiffOptions>,
+    refresh_report_path: Option<PathBuf>,
+    recorded: Arc<Mutex<Vec<PathBuf>>>,
+    refresh: Arc<Mutex<RefreshState>>,
+}
+
+/// Fixtures as they were before this recorder first overwrote them, and the running report.
+#[derive(Default)]
+struct RefreshState {
+    baselines: HashMap<PathBuf, Option<RestFixture>>,
+    report: RestFixtureRefreshReport,
+}
+
+impl<T> Clone for RecordingTransport<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            capture_command: self.capture_command.clone(),
+            exchange_env: self.exchange_env.clone(),
+            registry: self.registry.clone(),
+            redaction: self.redaction.clone(),
+            diff_options: self.diff_options.clone(),
+            refresh_report_path: self.refresh_report_path.clone(),
+            recorded: self.recorded.clone(),
+            refresh: self.refresh.clone(),
+        }
+    }
+}
+
+impl<T> RecordingTransport<T>
+where
+    T: RestTransport + 'static,
+{
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            capture_command: None,
+            exchange_env: None,
+            registry: RestFixtureRegistry::global(),
+            redaction: Arc::new(RestRedactionRules::default()),
+            diff_options: Arc::new(RestFixtureDiffOptions::default()),
+            refresh_report_path: None,
+            recorded: Arc::new(Mutex::new(Vec::new())),
+            refresh: Arc::new(Mutex::new(RefreshState::default())),
+        }
+    }
+
+    /// Redaction applied to each fixture before it is written. Defaults to
+    /// [`RestRedactionRules::default`].
+    pub fn with_redaction(mut self, rules: RestRedactionRules) -> Self {
+        self.redaction = Arc::new(rules);
+        self
+    }
+
+    /// Resolve fixture paths from `registry` instead of the global registry.
+    pub fn with_fixture_registry(mut self, registry: RestFixtureRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Command recorded as `capture_command`. Defaults to
+    /// `SHARED_RESTAPI_FIXTURE_CAPTURE_COMMAND`, then to the current process arguments.
+    pub fn with_capture_command(mut self, command: impl Into<String>) -> Self {
+        self.capture_command = Some(command.into());
+        self
+    }
+
+    /// Environment recorded as `exchange_env`. Defaults to
+    /// `SHARED_RESTAPI_FIXTURE_EXCHANGE_ENV`; capture fails when neither is set.
+    pub fn with_exchange_env(mut self, exchange_env: impl Into<String>) -> Self {
+        self.exchange_env = Some(exchange_env.into());
+        self
+    }
+
+    pub fn with_diff_options(mut self, options: RestFixtureDiffOptions) -> Self {
+        self.diff_options = Arc::new(options);
+        self
+    }
+
+    /// Rewrite the JSON refresh report at `path` after every capture.
+    pub fn with_refresh_report(mut self, path: impl Into<PathBuf>) -> Self {
+        self.refresh_report_path = Some(path.into());
+        self
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Each fixture written so far compared against the file it replaced.
+    pub fn refresh_report(&self) -> RestFixtureRefreshReport {
+        self.refresh
+            .lock()
+            .expect("fixture recorder mutex poisoned while reading refresh report")
+            .report
+            .clone()
+    }
+
+    /// Fixture files written so far, in capture order.
+    pub fn recorded_fixtures(&self) -> Vec<PathBuf> {
+        self.recorded
+            .lock()
+            .expect("fixture recorder mutex poisoned while reading recorded fixtures")
+            .clone()
+    }
+
+    fn capture_command(&self) -> String {
+        self.capture_command
+            .clone()
+            .or_else(|| std::env::var(SHARED_RESTAPI_FIXTURE_CAPTURE_COMMAND_ENV).ok())
+            .filter(|command| !command.trim().is_empty())
+            .unwrap_or_else(|| std::env::args().collect::<Vec<_>>().join(" "))
+    }
+
+    fn exchange_env(&self) -> RestResult<String> {
+        self.exchange_env
+            .clone()
+            .or_else(|| std::env::var(SHARED_RESTAPI_FIXTURE_EXCHANGE_ENV_ENV).ok())
+            .filter(|env| !env.trim().is_empty())
+            .ok_or_else(|| {
+                RestError::internal(format!(
+                    "fixture capture requires an exchange env: call with_exchange_env or set {SHARED_RESTAPI_FIXTURE_EXCHANGE_ENV_ENV}"
+                ))
+            })
+    }
+
+    fn capturing(&self, request: &RestRequest) -> bool {
+        request
+            .fixture_contract
+            .as_deref()
+            .is_some_and(|contract_id| self.registry.capture_enabled_for(contract_id))
+    }
+
+    async fn capture(&self, request: RestRequest) -> RestResult<RestResponse> {
+        let Some(contract_id) = request.fixture_contract.clone() else {
+            return self.inner.execute(request).await;
+        };
+        let selection = self.registry.capture_selection();
+        if !selection.includes(&contract_id) {
+            return self.inner.execute(request).await;
+        }
+        let requirement = self.registry.requirement(&contract_id).ok_or_else(|| {
+            RestError::internal(format!(
+                "fixture capture failed: unregistered fixture contract {contract_id}"
+            ))
+        })?;
+        let exchange_env = self.exchange_env()?;
+
+        let response = self.inner.execute(request.clone()).await?;
+        let (kind, path) = if response.is_success() {
+            (RestFixtureKind::Success, &requirement.success_path)
+        } else {
+            (RestFixtureKind::Error, &requirement.error_path)
+        };
+        let provenance = RestFixtureProvenance::live_capture(self.capture_command(), exchange_env)
+            .with_capture_mode(selection.to_string());
+        let mut fixture =
+            RestFixture::from_exchange(contract_id.clone(), &request, &response, provenance);
+        self.redaction.redact_fixture(&mut fixture);
+        {
+            let mut refresh = self
+                .refresh
+                .lock()
+                .expect("fixture recorder mutex poisoned while diffing fixture");
+            let previous = refresh
+                .baselines
+                .entry(path.clone())
+                .or_insert_with(|| RestFixture::load(path).ok());
+            let diff = diff_fixtures(
+                contract_id,
+                kind,
+                path.clone(),
+                previous.as_ref(),
+                &fixture,
+                &self.diff_options,
+            );
+            refresh.report.push(diff);
+            fixture.save(path)?;
+            if let Some(report_path) = &self.refresh_report_path {
+                refresh.report.write(report_path)?;
+            }
+        }
+        self.recorded
+            .lock()
+            .expect("fixture recorder mutex poisoned while recording fixture path")
+            .push(path.clone());
+        Ok(response)
+    }
+}
+
+impl<T> RestTransport for RecordingTransport<T>
+where
+    T: RestTransport + 'static,
+{
+    fn execute(&self, request: RestRequest) -> RestFuture<RestResult<RestResponse>> {
+        let transport = self.clone();
+        Box::pin(async move { transport.capture(request).await })
+    }
+
+    fn execute_raw(&self, request: RestRequest) -> RestFuture<RestResult<RestRawResponse>> {
+        if !self.capturing(&request) {
+            return self.inner.execute_raw(request);
+        }
+        let transport = self.clone();
+        Box::pin(async move {
+            let response = transport.capture(request).await?;
+            Ok((response.status, response.body, response.elapsed))
+        })
+    }
+
+    fn execute_raw_with_headers(
+        &self,
+        request: RestRequest,
+        keep: RestHeaderNames,
+    ) -> RestFuture<RestResult<RestRawHeaderResponse>> {
+        if !self.capturing(&request) {
+            return self.inner.execute_raw_with_headers(request, keep);
+        }
+        let transport = self.clone();
+        Box::pin(async move {
+            Ok(raw_header_response(
+                transport.capture(request).await?,
+                &keep,
+            ))
+        })
+    }
+
+    /// Captured requests are buffered to write the fixture, then served as one chunk.
+    fn execute_stream(
+        &self,
+        request: RestRequest,
+    ) -> RestFuture<RestResult<RestStreamingResponse>> {
+        if !self.capturing(&request) {
+            return self.inner.execute_stream(request);
+        }
+        let transport = self.clone();
+        Box::pin(async move {
+            Ok(RestStreamingResponse::buffered(
+                transport.capture(request).await?,
+            ))
+        })
+    }
+}
+
+/// Serves recorded fixtures back through a [`MockRestAdapter`].
+///
+/// Requests tagged with a known `fixture_contract` receive the contract's success fixture, or its
+/// error fixture after [`serve_error`](Self::serve_error). Other requests fall through to the
+/// adapter's own queues and behavior plan.
+#[derive(Clone)]
+pub struct ReplayingTransport {
+    adapter: MockRestAdapter,
+    fixtures: Arc<HashMap<String, (MockResponse, MockResponse)>>,
+    serve_errors: Arc<Mutex<HashSet<String>>>,
+}
+
+impl ReplayingTransport {
+    pub fn from_requirements(requirements: &[RestFixtureRequirement]) -> RestResult<Self> {
+        Self::with_adapter(MockRestAdapter::new(), requirements)
+    }
+
+    /// Replay every contract in the process-wide registry.
+    pub fn from_registered() -> RestResult<Self> {
+        Self::from_registry(&RestFixtureRegistry::global())
+    }
+
+    pub fn from_registry(registry: &RestFixtureRegistry) -> RestResult<Self> {
+        Self::from_requirements(&registry.requirements())
+    }
+
+    pub fn with_adapter(
+        adapter: MockRestAdapter,
+        requirements: &[RestFixtureRequirement],
+    ) -> RestResult<Self> {
+        let mut fixtures = HashMap::with_capacity(requirements.len());
+        for requirement in requirements {
+            fixtures.insert(
+                requirement.contract_id.clone(),
+                (
+                    RestFixture::load(&requirement.success_path)?.to_mock_response()?,
+                    RestFixture::load(&requirement.error_path)?.to_mock_response()?,
+                ),
+            );
+        }
+        Ok(Self {
+            adapter,
+            fixtures: Arc::new(fixtures),
+            serve_errors: Arc::new(Mutex::new(HashSet::new())),
+        })
+    }
+
+    pub fn adapter(&self) -> &MockRestAdapter {
+        &self.adapter
+    }
+
+    pub fn serve_error(&self, contract_id: impl Into<String>) {
+        self.serve_errors
+            .lock()
+            .expect("fixture replay mutex poisoned while selecting error fixture")
+            .insert(contract_id.into());
+    }
+
+    pub fn serve_success(&self, contract_id: &str) {
+        self.serve_errors
+            .lock()
+            .expect("fixture replay mutex poisoned while selecting success fixture")
+            .remove(contract_id);
+    }
+
+    fn queue_fixture(&self, request: &RestRequest) {
+        let Some(contract_id) = request.fixture_contract.as_deref() else {
+            return;
+        };
+        let Some((success, error)) = self.fixtures.get(contract_id) else {
+            return;
+        };
+        let serve_error = self
+            .serve_errors
+            .lock()
+            .expect("fixture replay mutex poisoned while reading fixture selection")
+            .contains(contract_id);
+        let response = if serve_error { error } else { success };
+        self.adapter.queue_response_for(
+            request.method.clone(),
+            request.url.clone(),
+            response.clone(),
+        );
+    }
+}
+
+impl RestTransport for ReplayingTransport {
+    fn execute(&self, request: RestRequest) -> RestFuture<RestResult<RestResponse>> {
+        self.queue_fixture(&request);
+        self.adapter.execute(request)
+    }
+
+    fn execute_raw(&self, request: RestRequest) -> RestFuture<RestResult<RestRawResponse>> {
+        self.queue_fixture(&request);
+        self.adapter.execute_raw(request)
+    }
+
+    fn execute_raw_with_headers(
+        &self,
+        request: RestRequest,
+        keep: RestHeaderNames,
+    ) -> RestFuture<RestResult<RestRawHeaderResponse>> {
+        self.queue_fixture(&request);
+        self.adapter.execute_raw_with_headers(request, keep)
+    }
+
+    fn execute_stream(
+        &self,
+        request: RestRequest,
+    ) -> RestFuture<RestResult<RestStreamingResponse>> {
+        self.queue_fixture(&request);
+        self.adapter.execute_stream(request)
+    }
+}
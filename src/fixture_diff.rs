@@ -0,0 +1,271 @@
+//! Comparison of refreshed fixtures against their previous captures.
+//!
+//! [`diff_fixtures`] reports status changes, structural differences ([`JsonShapeChange`]) and
+//! changed values, skipping volatile fields such as timestamps. [`RestFixtureRefreshReport`]
+//! collects one entry per written fixture and serializes to a JSON summary for review.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sonic_rs::{JsonContainerTrait, JsonType, JsonValueTrait, Value};
+
+use crate::adapter::{RestError, RestResult};
+use crate::fixture::{RestFixture, now_ms};
+use crate::fixture_replay::RestFixtureKind;
+use crate::fixture_schema::{JsonShape, JsonShapeChange};
+
+const DEFAULT_VOLATILE_FIELDS: &[&str] = &[
+    "timestamp",
+    "ts",
+    "time",
+    "server_time",
+    "serverTime",
+    "usIn",
+    "usOut",
+    "usDiff",
+    "nonce",
+    "request_id",
+    "requestId",
+];
+
+const DEFAULT_MAX_VALUE_CHANGES: usize = 50;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestFixtureDiffOptions {
+    /// Object keys whose values (at any depth) are ignored when comparing values.
+    pub volatile_fields: Vec<String>,
+    /// Value changes recorded per fixture; the rest are only counted.
+    pub max_value_changes: usize,
+}
+
+impl Default for RestFixtureDiffOptions {
+    fn default() -> Self {
+        Self {
+            volatile_fields: DEFAULT_VOLATILE_FIELDS
+                .iter()
+                .map(|field| field.to_string())
+                .collect(),
+            max_value_changes: DEFAULT_MAX_VALUE_CHANGES,
+        }
+    }
+}
+
+impl RestFixtureDiffOptions {
+    pub fn with_volatile_field(mut self, field: impl Into<String>) -> Self {
+        self.volatile_fields.push(field.into());
+        self
+    }
+
+    pub fn with_max_value_changes(mut self, max_value_changes: usize) -> Self {
+        self.max_value_changes = max_value_changes;
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RestFixtureValueChange {
+    pub path: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RestFixtureDiff {
+    pub contract_id: String,
+    pub fixture: RestFixtureKind,
+    pub path: PathBuf,
+    /// No previous capture existed (or it could not be loaded).
+    pub is_new: bool,
+    pub previous_status: Option<u16>,
+    pub status: u16,
+    pub shape_changes: Vec<JsonShapeChange>,
+    pub value_changes: Vec<RestFixtureValueChange>,
+    /// Value changes beyond `max_value_changes` that were counted but not recorded.
+    pub value_changes_truncated: usize,
+}
+
+impl RestFixtureDiff {
+    pub fn status_changed(&self) -> bool {
+        self.previous_status
+            .is_some_and(|previous| previous != self.status)
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.is_new
+            || self.status_changed()
+            || !self.shape_changes.is_empty()
+            || !self.value_changes.is_empty()
+            || self.value_changes_truncated > 0
+    }
+}
+
+/// Compare a refreshed fixture against the capture it replaces.
+pub fn diff_fixtures(
+    contract_id: impl Into<String>,
+    fixture: RestFixtureKind,
+    path: impl Into<PathBuf>,
+    previous: Option<&RestFixture>,
+    current: &RestFixture,
+    options: &RestFixtureDiffOptions,
+) -> RestFixtureDiff {
+    let mut diff = RestFixtureDiff {
+        contract_id: contract_id.into(),
+        fixture,
+        path: path.into(),
+        is_new: previous.is_none(),
+        previous_status: previous.map(|previous| previous.response.status),
+        status: current.response.status,
+        shape_changes: Vec::new(),
+        value_changes: Vec::new(),
+        value_changes_truncated: 0,
+    };
+    let Some(previous) = previous else {
+        return diff;
+    };
+    let before = previous.response.body.to_bytes().unwrap_or_default();
+    let after = current.response.body.to_bytes().unwrap_or_default();
+    match (
+        sonic_rs::from_slice::<Value>(&before),
+        sonic_rs::from_slice::<Value>(&after),
+    ) {
+        (Ok(before), Ok(after)) => {
+            diff.shape_changes =
+                JsonShape::from_value(&before).diff(&JsonShape::from_value(&after));
+            let mut walker = ValueDiff {
+                options,
+                changes: &mut diff.value_changes,
+                truncated: 0,
+            };
+            walker.walk("$", &before, &after);
+            diff.value_changes_truncated = walker.truncated;
+        }
+        _ if before != after => diff.value_changes.push(RestFixtureValueChange {
+            path: "$".to_string(),
+            before: format!("{} bytes", before.len()),
+            after: format!("{} bytes", after.len()),
+        }),
+        _ => {}
+    }
+    diff
+}
+
+struct ValueDiff<'a> {
+    options: &'a RestFixtureDiffOptions,
+    changes: &'a mut Vec<RestFixtureValueChange>,
+    truncated: usize,
+}
+
+impl ValueDiff<'_> {
+    fn record(&mut self, path: String, before: String, after: String) {
+        if self.changes.len() < self.options.max_value_changes {
+            self.changes.push(RestFixtureValueChange {
+                path,
+                before,
+                after,
+            });
+        } else {
+            self.truncated += 1;
+        }
+    }
+
+    fn walk(&mut self, path: &str, before: &Value, after: &Value) {
+        match (before.get_type(), after.get_type()) {
+            (JsonType::Object, JsonType::Object) => {
+                let (Some(before), Some(after)) = (before.as_object(), after.as_object()) else {
+                    return;
+                };
+                for (name, value) in before.iter() {
+                    if self
+                        .options
+                        .volatile_fields
+                        .iter()
+                        .any(|field| field == name)
+                    {
+                        continue;
+                    }
+                    // Added and removed fields are shape changes, not value changes.
+                    if let Some(other) = after.get(&name) {
+                        self.walk(&format!("{path}.{name}"), value, other);
+                    }
+                }
+            }
+            (JsonType::Array, JsonType::Array) => {
+                let (Some(before), Some(after)) = (before.as_array(), after.as_array()) else {
+                    return;
+                };
+                if before.len() != after.len() {
+                    self.record(
+                        format!("{path}.length"),
+                        before.len().to_string(),
+                        after.len().to_string(),
+                    );
+                }
+                for (index, (before, after)) in before.iter().zip(after.iter()).enumerate() {
+                    self.walk(&format!("{path}[{index}]"), before, after);
+                }
+            }
+            (left, right) if left == right && before != after => {
+                self.record(path.to_string(), render(before), render(after));
+            }
+            // Type changes are already reported as shape changes.
+            _ => {}
+        }
+    }
+}
+
+fn render(value: &Value) -> String {
+    sonic_rs::to_string(value).unwrap_or_default()
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RestFixtureRefreshReport {
+    pub generated_at_ms: u64,
+    pub fixtures: Vec<RestFixtureDiff>,
+}
+
+impl RestFixtureRefreshReport {
+    /// Add or replace the entry for the diff's fixture path.
+    pub fn push(&mut self, diff: RestFixtureDiff) {
+        self.generated_at_ms = now_ms();
+        match self
+            .fixtures
+            .iter_mut()
+            .find(|entry| entry.path == diff.path)
+        {
+            Some(entry) => *entry = diff,
+            None => self.fixtures.push(diff),
+        }
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.fixtures.iter().any(RestFixtureDiff::has_changes)
+    }
+
+    /// Entries whose fixture changed, in capture order.
+    pub fn changed(&self) -> impl Iterator<Item = &RestFixtureDiff> {
+        self.fixtures.iter().filter(|entry| entry.has_changes())
+    }
+
+    pub fn to_json(&self) -> RestResult<Vec<u8>> {
+        Ok(sonic_rs::to_vec_pretty(self)?)
+    }
+
+    /// Write the JSON summary, creating parent directories as needed.
+    pub fn write(&self, path: impl AsRef<Path>) -> RestResult<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| {
+                RestError::internal(format!(
+                    "failed to create refresh report directory {}: {err}",
+                    parent.display()
+                ))
+            })?;
+        }
+        std::fs::write(path, self.to_json()?).map_err(|err| {
+            RestError::internal(format!(
+                "failed to write refresh report {}: {err}",
+                path.display()
+            ))
+        })
+    }
+}
@@ -0,0 +1,232 @@
+//! On-disk manifest for the REST fixture registry.
+//!
+//! A manifest lists contracts, their fixture paths relative to the manifest file, and their
+//! policies. Contracts without explicit paths, and with `"discover": true` every directory under
+//! `fixtures_dir`, follow the `<fixtures_dir>/<contract_id>/{success,error}.json` convention:
+//!
+//! ```json
+//! {
+//!   "fixtures_dir": ".",
+//!   "discover": true,
+//!   "policy": { "max_age_secs": 2592000, "allowed_exchange_envs": ["deribit_testnet"] },
+//!   "contracts": [
+//!     { "contract_id": "instruments" },
+//!     { "contract_id": "ticker", "success": "ticker/ok.json", "error": "ticker/bad.json",
+//!       "policy": { "capture_command_prefix": "cargo run --bin capture" } }
+//!   ]
+//! }
+//! ```
+//!
+//! `.toml` manifests with the same shape load when the `toml` feature is enabled.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::adapter::{RestError, RestResult};
+use crate::fixture_policy::{
+    RestFixturePolicy, RestFixtureRegistry, RestFixtureRequirement,
+    register_required_rest_contracts,
+};
+
+pub const REST_FIXTURE_SUCCESS_FILE: &str = "success.json";
+pub const REST_FIXTURE_ERROR_FILE: &str = "error.json";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestFixtureManifestPolicy {
+    #[serde(default)]
+    pub max_age_secs: Option<u64>,
+    #[serde(default)]
+    pub allowed_exchange_envs: Vec<String>,
+    #[serde(default)]
+    pub capture_command_prefix: Option<String>,
+}
+
+impl RestFixtureManifestPolicy {
+    pub fn to_policy(&self) -> RestFixturePolicy {
+        RestFixturePolicy {
+            max_age: self.max_age_secs.map(Duration::from_secs),
+            allowed_exchange_envs: self.allowed_exchange_envs.clone(),
+            capture_command_prefix: self.capture_command_prefix.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestFixtureManifestContract {
+    pub contract_id: String,
+    #[serde(default)]
+    pub success: Option<PathBuf>,
+    #[serde(default)]
+    pub error: Option<PathBuf>,
+    /// Replaces the manifest-level policy for this contract.
+    #[serde(default)]
+    pub policy: Option<RestFixtureManifestPolicy>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestFixtureManifest {
+    /// Root of the naming convention, relative to the manifest. Defaults to the manifest's directory.
+    #[serde(default)]
+    pub fixtures_dir: Option<PathBuf>,
+    /// Register every `<fixtures_dir>/<contract_id>/` holding both fixture files.
+    #[serde(default)]
+    pub discover: bool,
+    /// Default policy for every contract.
+    #[serde(default)]
+    pub policy: RestFixtureManifestPolicy,
+    #[serde(default)]
+    pub contracts: Vec<RestFixtureManifestContract>,
+}
+
+impl RestFixtureManifest {
+    pub fn from_json_slice(bytes: &[u8]) -> RestResult<Self> {
+        sonic_rs::from_slice(bytes)
+            .map_err(|err| RestError::internal(format!("failed to parse fixture manifest: {err}")))
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(raw: &str) -> RestResult<Self> {
+        toml::from_str(raw)
+            .map_err(|err| RestError::internal(format!("failed to parse fixture manifest: {err}")))
+    }
+
+    /// Load a `.json` manifest, or a `.toml` one with the `toml` feature.
+    pub fn load(path: impl AsRef<Path>) -> RestResult<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|err| {
+            RestError::internal(format!(
+                "failed to read fixture manifest {}: {err}",
+                path.display()
+            ))
+        })?;
+        let parse_error = |err: &dyn std::fmt::Display| {
+            RestError::internal(format!(
+                "failed to parse fixture manifest {}: {err}",
+                path.display()
+            ))
+        };
+        match path.extension().and_then(|ext| ext.to_str()) {
+            #[cfg(feature = "toml")]
+            Some("toml") => {
+                let raw = std::str::from_utf8(&bytes).map_err(|err| parse_error(&err))?;
+                toml::from_str(raw).map_err(|err| parse_error(&err))
+            }
+            #[cfg(not(feature = "toml"))]
+            Some("toml") => Err(RestError::internal(format!(
+                "fixture manifest {} is TOML, which requires the `toml` feature",
+                path.display()
+            ))),
+            _ => sonic_rs::from_slice(&bytes).map_err(|err| parse_error(&err)),
+        }
+    }
+
+    /// Resolve the manifest into requirements, with relative paths joined onto `base_dir`.
+    pub fn requirements(&self, base_dir: &Path) -> RestResult<Vec<RestFixtureRequirement>> {
+        let fixtures_dir = match &self.fixtures_dir {
+            Some(dir) => base_dir.join(dir),
+            None => base_dir.to_path_buf(),
+        };
+        let mut seen = HashSet::new();
+        let mut requirements = Vec::with_capacity(self.contracts.len());
+        for contract in &self.contracts {
+            if !seen.insert(contract.contract_id.clone()) {
+                return Err(RestError::internal(format!(
+                    "fixture manifest lists contract {} more than once",
+                    contract.contract_id
+                )));
+            }
+            let success = match &contract.success {
+                Some(path) => base_dir.join(path),
+                None => fixtures_dir
+                    .join(&contract.contract_id)
+                    .join(REST_FIXTURE_SUCCESS_FILE),
+            };
+            let error = match &contract.error {
+                Some(path) => base_dir.join(path),
+                None => fixtures_dir
+                    .join(&contract.contract_id)
+                    .join(REST_FIXTURE_ERROR_FILE),
+            };
+            let policy = contract.policy.as_ref().unwrap_or(&self.policy).to_policy();
+            requirements.push(
+                RestFixtureRequirement::new(contract.contract_id.clone(), success, error)
+                    .with_policy(policy),
+            );
+        }
+        if self.discover {
+            for discovered in discover_rest_fixture_contracts(&fixtures_dir)? {
+                if seen.insert(discovered.contract_id.clone()) {
+                    requirements.push(discovered.with_policy(self.policy.to_policy()));
+                }
+            }
+        }
+        Ok(requirements)
+    }
+}
+
+/// Contracts laid out as `<dir>/<contract_id>/{success,error}.json`, sorted by contract id.
+pub fn discover_rest_fixture_contracts(
+    dir: impl AsRef<Path>,
+) -> RestResult<Vec<RestFixtureRequirement>> {
+    let dir = dir.as_ref();
+    let entries = std::fs::read_dir(dir).map_err(|err| {
+        RestError::internal(format!(
+            "failed to read fixture directory {}: {err}",
+            dir.display()
+        ))
+    })?;
+    let mut requirements = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|err| {
+            RestError::internal(format!(
+                "failed to read fixture directory {}: {err}",
+                dir.display()
+            ))
+        })?;
+        let path = entry.path();
+        let success = path.join(REST_FIXTURE_SUCCESS_FILE);
+        let error = path.join(REST_FIXTURE_ERROR_FILE);
+        if !path.is_dir() || !success.is_file() || !error.is_file() {
+            continue;
+        }
+        let Some(contract_id) = entry.file_name().to_str().map(str::to_owned) else {
+            continue;
+        };
+        requirements.push(RestFixtureRequirement::new(contract_id, success, error));
+    }
+    requirements.sort_by(|left, right| left.contract_id.cmp(&right.contract_id));
+    Ok(requirements)
+}
+
+/// Load a manifest with paths relative to its own directory.
+pub fn load_rest_fixture_manifest(
+    path: impl AsRef<Path>,
+) -> RestResult<Vec<RestFixtureRequirement>> {
+    let path = path.as_ref();
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    RestFixtureManifest::load(path)?.requirements(base_dir)
+}
+
+/// Load a manifest and make it the process-wide registry, returning what was registered.
+pub fn register_rest_fixture_manifest(
+    path: impl AsRef<Path>,
+) -> RestResult<Vec<RestFixtureRequirement>> {
+    let requirements = load_rest_fixture_manifest(path)?;
+    register_required_rest_contracts(requirements.clone());
+    Ok(requirements)
+}
+
+impl RestFixtureRegistry {
+    /// Add a manifest's contracts to this registry, returning what was registered.
+    pub fn register_manifest(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> RestResult<Vec<RestFixtureRequirement>> {
+        let requirements = load_rest_fixture_manifest(path)?;
+        self.register(requirements.clone());
+        Ok(requirements)
+    }
+}
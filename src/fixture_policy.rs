@@ -1,134 +1,564 @@
-use std::path::PathBuf;
-use std::sync::{Mutex, OnceLock};
-
-use sonic_rs::JsonValueTrait;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 
+use crate::fixture::{RestFixtureProvenance, now_ms};
+use crate::redact::RestRedactionRules;
 use crate::{RestError, RestRequest, RestResult};
 
 const SHARED_RESTAPI_FIXTURE_CAPTURE_MODE_ENV: &str = "SHARED_RESTAPI_FIXTURE_CAPTURE_MODE";
 
+/// Provenance rules a contract's fixtures must satisfy beyond being live captures.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestFixturePolicy {
+    /// Reject fixtures whose `captured_at_ms` is older than this.
+    pub max_age: Option<Duration>,
+    /// Accepted `exchange_env` values; empty accepts any.
+    pub allowed_exchange_envs: Vec<String>,
+    /// Required prefix of `capture_command`.
+    pub capture_command_prefix: Option<String>,
+}
+
+impl RestFixturePolicy {
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    pub fn with_allowed_exchange_envs(
+        mut self,
+        exchange_envs: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.allowed_exchange_envs = exchange_envs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_capture_command_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.capture_command_prefix = Some(prefix.into());
+        self
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RestFixtureRequirement {
     pub contract_id: String,
     pub success_path: PathBuf,
     pub error_path: PathBuf,
+    /// Set with `with_policy`; build requirements with `new` rather than a struct literal.
+    policy: RestFixturePolicy,
+}
+
+impl RestFixtureRequirement {
+    pub fn new(
+        contract_id: impl Into<String>,
+        success_path: impl Into<PathBuf>,
+        error_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            contract_id: contract_id.into(),
+            success_path: success_path.into(),
+            error_path: error_path.into(),
+            policy: RestFixturePolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RestFixturePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn policy(&self) -> &RestFixturePolicy {
+        &self.policy
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RestFixtureViolationKind {
+    MissingFiles {
+        success_path: PathBuf,
+        error_path: PathBuf,
+    },
+    Unreadable {
+        path: PathBuf,
+        reason: String,
+    },
+    NotLiveCapture {
+        path: PathBuf,
+    },
+    Stale {
+        path: PathBuf,
+        age: Duration,
+        max_age: Duration,
+    },
+    ExchangeEnvNotAllowed {
+        path: PathBuf,
+        exchange_env: String,
+        allowed: Vec<String>,
+    },
+    CaptureCommandMismatch {
+        path: PathBuf,
+        capture_command: String,
+        required_prefix: String,
+    },
+    SecretDetected {
+        path: PathBuf,
+        finding: String,
+    },
+}
+
+/// One policy failure for one contract; [`validate_required_rest_contracts`] reports all of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestFixtureViolation {
+    pub contract_id: String,
+    pub kind: RestFixtureViolationKind,
 }
 
-#[derive(Default)]
-struct RestFixtureRegistry {
-    requirements: Vec<RestFixtureRequirement>,
+impl std::fmt::Display for RestFixtureViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "contract={}: ", self.contract_id)?;
+        match &self.kind {
+            RestFixtureViolationKind::MissingFiles {
+                success_path,
+                error_path,
+            } => write!(
+                f,
+                "missing fixture files success={} error={}",
+                success_path.display(),
+                error_path.display()
+            ),
+            RestFixtureViolationKind::Unreadable { reason, .. } => f.write_str(reason),
+            RestFixtureViolationKind::NotLiveCapture { path } => write!(
+                f,
+                "fixture {} is not compliant live-capture provenance",
+                path.display()
+            ),
+            RestFixtureViolationKind::Stale { path, age, max_age } => write!(
+                f,
+                "fixture {} is stale: captured {}s ago, max age {}s",
+                path.display(),
+                age.as_secs(),
+                max_age.as_secs()
+            ),
+            RestFixtureViolationKind::ExchangeEnvNotAllowed {
+                path,
+                exchange_env,
+                allowed,
+            } => write!(
+                f,
+                "fixture {} has exchange_env={exchange_env}, allowed: {}",
+                path.display(),
+                allowed.join(", ")
+            ),
+            RestFixtureViolationKind::CaptureCommandMismatch {
+                path,
+                capture_command,
+                required_prefix,
+            } => write!(
+                f,
+                "fixture {} has capture_command={capture_command:?}, expected prefix {required_prefix:?}",
+                path.display()
+            ),
+            RestFixtureViolationKind::SecretDetected { path, finding } => write!(
+                f,
+                "fixture {} contains an unredacted secret ({finding})",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// A set of required fixture contracts.
+///
+/// Clones share state, so a registry can be handed to a [`ReqwestTransport`](crate::ReqwestTransport)
+/// or recording transport and still be extended afterwards. [`RestFixtureRegistry::global`] is the
+/// process-wide default used by the free functions in this module; every other registry is
+/// isolated, which lets boundary actors and parallel tests keep their own contracts.
+#[derive(Clone, Debug, Default)]
+pub struct RestFixtureRegistry {
+    requirements: Arc<Mutex<Vec<RestFixtureRequirement>>>,
+    capture_selection: Arc<Mutex<Option<RestFixtureCaptureSelection>>>,
 }
 
-fn registry() -> &'static Mutex<RestFixtureRegistry> {
-    static REGISTRY: OnceLock<Mutex<RestFixtureRegistry>> = OnceLock::new();
-    REGISTRY.get_or_init(|| Mutex::new(RestFixtureRegistry::default()))
+impl RestFixtureRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_requirements(
+        requirements: impl IntoIterator<Item = RestFixtureRequirement>,
+    ) -> Self {
+        let registry = Self::new();
+        registry.register(requirements);
+        registry
+    }
+
+    /// The process-wide registry.
+    pub fn global() -> Self {
+        static GLOBAL: OnceLock<RestFixtureRegistry> = OnceLock::new();
+        GLOBAL.get_or_init(Self::default).clone()
+    }
+
+    /// Add requirements, replacing any already registered under the same contract id.
+    pub fn register(&self, requirements: impl IntoIterator<Item = RestFixtureRequirement>) {
+        let mut guard = self
+            .requirements
+            .lock()
+            .expect("rest fixture registry poisoned while registering contracts");
+        for requirement in requirements {
+            match guard
+                .iter_mut()
+                .find(|item| item.contract_id == requirement.contract_id)
+            {
+                Some(existing) => *existing = requirement,
+                None => guard.push(requirement),
+            }
+        }
+    }
+
+    /// Add every requirement of `other`; see [`register`](Self::register).
+    pub fn merge(&self, other: &RestFixtureRegistry) {
+        if Arc::ptr_eq(&self.requirements, &other.requirements) {
+            return;
+        }
+        self.register(other.requirements());
+    }
+
+    /// Replace the whole requirement list.
+    pub fn replace(&self, requirements: impl IntoIterator<Item = RestFixtureRequirement>) {
+        *self
+            .requirements
+            .lock()
+            .expect("rest fixture registry poisoned while replacing contracts") =
+            requirements.into_iter().collect();
+    }
+
+    pub fn clear(&self) {
+        self.requirements
+            .lock()
+            .expect("rest fixture registry poisoned while clearing contracts")
+            .clear();
+    }
+
+    pub fn requirements(&self) -> Vec<RestFixtureRequirement> {
+        self.requirements
+            .lock()
+            .expect("rest fixture registry poisoned while reading contracts")
+            .clone()
+    }
+
+    pub fn requirement(&self, contract_id: &str) -> Option<RestFixtureRequirement> {
+        self.requirements
+            .lock()
+            .expect("rest fixture registry poisoned while reading contracts")
+            .iter()
+            .find(|item| item.contract_id == contract_id)
+            .cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.requirements
+            .lock()
+            .expect("rest fixture registry poisoned while reading contracts")
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Override the `SHARED_RESTAPI_FIXTURE_CAPTURE_MODE` selection for this registry; `None`
+    /// returns to the environment.
+    pub fn set_capture_selection(&self, selection: Option<RestFixtureCaptureSelection>) {
+        *self
+            .capture_selection
+            .lock()
+            .expect("rest fixture registry poisoned while setting capture selection") = selection;
+    }
+
+    /// The registry's override, or the environment's selection.
+    pub fn capture_selection(&self) -> RestFixtureCaptureSelection {
+        self.capture_selection
+            .lock()
+            .expect("rest fixture registry poisoned while reading capture selection")
+            .clone()
+            .unwrap_or_else(fixture_capture_selection)
+    }
+
+    pub fn capture_enabled_for(&self, contract_id: &str) -> bool {
+        self.capture_selection().includes(contract_id)
+    }
+
+    /// Gate a live request against this registry's contracts. Contracts selected for capture
+    /// bypass the gate; every other contract is still enforced.
+    pub fn ensure_live_request_allowed(&self, request: &RestRequest) -> RestResult<()> {
+        let selection = self.capture_selection();
+        if selection == RestFixtureCaptureSelection::All {
+            return Ok(());
+        }
+        let contract_id = request.fixture_contract.as_deref().ok_or_else(|| {
+            RestError::internal("live REST request missing required fixture contract metadata")
+        })?;
+        if selection.includes(contract_id) {
+            return Ok(());
+        }
+        if self.is_empty() {
+            return Err(RestError::internal(
+                "live REST request blocked: no required fixture contracts registered",
+            ));
+        }
+        let Some(requirement) = self.requirement(contract_id) else {
+            return Err(RestError::internal(format!(
+                "live REST request blocked: unregistered fixture contract {contract_id}"
+            )));
+        };
+        let violations = requirement_violations(std::slice::from_ref(&requirement), false);
+        if !violations.is_empty() {
+            return Err(RestError::internal(format!(
+                "live REST request blocked: {}",
+                join_violations(&violations, "; ")
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn violations(&self) -> Vec<RestFixtureViolation> {
+        rest_fixture_violations(&self.requirements())
+    }
+
+    pub fn validate(&self) -> RestResult<()> {
+        validate_required_rest_contracts(&self.requirements())
+    }
 }
 
+/// Replace the global registry's contracts. Prefer [`RestFixtureRegistry::register`] on a scoped
+/// registry when several boundary actors share a binary.
 pub fn register_required_rest_contracts(
     requirements: impl IntoIterator<Item = RestFixtureRequirement>,
 ) {
-    let mut guard = registry().lock().expect("rest fixture registry poisoned");
-    guard.requirements = requirements.into_iter().collect();
+    RestFixtureRegistry::global().replace(requirements);
 }
 
 pub fn required_rest_contracts() -> Vec<RestFixtureRequirement> {
-    registry()
-        .lock()
-        .expect("rest fixture registry poisoned")
-        .requirements
-        .clone()
+    RestFixtureRegistry::global().requirements()
 }
 
 pub fn clear_required_rest_contracts_for_tests() {
-    registry()
-        .lock()
-        .expect("rest fixture registry poisoned")
-        .requirements
-        .clear();
+    RestFixtureRegistry::global().clear();
 }
 
-pub fn fixture_capture_mode_enabled() -> bool {
-    std::env::var(SHARED_RESTAPI_FIXTURE_CAPTURE_MODE_ENV)
-        .ok()
-        .map(|raw| matches!(raw.trim(), "1" | "true" | "TRUE" | "yes" | "YES"))
-        .unwrap_or(false)
+/// Which contracts are in fixture-capture mode.
+///
+/// Parsed from `SHARED_RESTAPI_FIXTURE_CAPTURE_MODE`. `1`, `true`, `yes` and `on` (any case) select
+/// every contract; unset, empty, `0`, `false`, `no` and `off` disable capture. Any other value is a
+/// comma-separated list of contract ids or globs (`*`, `?`) and selects only those, so a typo such
+/// as `enable` selects a contract named `enable` rather than every contract.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum RestFixtureCaptureSelection {
+    #[default]
+    Disabled,
+    All,
+    Contracts(Vec<String>),
 }
 
-fn ensure_live_capture_fixture(path: &PathBuf) -> RestResult<()> {
-    let bytes = std::fs::read(path).map_err(|err| {
-        RestError::internal(format!(
-            "live REST request blocked: failed to read fixture {}: {err}",
-            path.display()
-        ))
-    })?;
-    let root = sonic_rs::from_slice::<sonic_rs::Value>(&bytes).map_err(|err| {
-        RestError::internal(format!(
-            "live REST request blocked: failed to parse fixture {}: {err}",
-            path.display()
-        ))
-    })?;
-    let source = root
-        .get("source")
-        .and_then(|value| value.as_str())
-        .unwrap_or("");
-    let captured_at_ms = root
-        .get("captured_at_ms")
-        .and_then(|value| value.as_u64())
-        .unwrap_or(0);
-    let capture_command = root
-        .get("capture_command")
-        .and_then(|value| value.as_str())
-        .unwrap_or("");
-    let exchange_env = root
-        .get("exchange_env")
-        .and_then(|value| value.as_str())
-        .unwrap_or("");
-    if source != "live_capture"
-        || captured_at_ms == 0
-        || capture_command.trim().is_empty()
-        || exchange_env.trim().is_empty()
-    {
-        return Err(RestError::internal(format!(
-            "live REST request blocked: fixture {} is not compliant live-capture provenance",
-            path.display()
-        )));
+impl RestFixtureCaptureSelection {
+    pub fn contracts(patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let patterns = patterns.into_iter().map(Into::into).collect::<Vec<_>>();
+        if patterns.is_empty() {
+            Self::Disabled
+        } else {
+            Self::Contracts(patterns)
+        }
+    }
+
+    pub fn parse(raw: &str) -> Self {
+        let raw = raw.trim();
+        match raw.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Self::All,
+            "" | "0" | "false" | "no" | "off" => Self::Disabled,
+            _ => Self::contracts(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|pattern| !pattern.is_empty()),
+            ),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::Disabled
+    }
+
+    pub fn includes(&self, contract_id: &str) -> bool {
+        match self {
+            Self::Disabled => false,
+            Self::All => true,
+            Self::Contracts(patterns) => patterns
+                .iter()
+                .any(|pattern| glob_matches(pattern.as_bytes(), contract_id.as_bytes())),
+        }
     }
-    Ok(())
 }
 
-pub fn ensure_live_request_allowed(request: &RestRequest) -> RestResult<()> {
-    if fixture_capture_mode_enabled() {
-        return Ok(());
-    }
-    let contract_id = request.fixture_contract.as_deref().ok_or_else(|| {
-        RestError::internal("live REST request missing required fixture contract metadata")
-    })?;
-    let guard = registry().lock().expect("rest fixture registry poisoned");
-    if guard.requirements.is_empty() {
-        return Err(RestError::internal(
-            "live REST request blocked: no required fixture contracts registered",
-        ));
+impl std::fmt::Display for RestFixtureCaptureSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Disabled => f.write_str("disabled"),
+            Self::All => f.write_str("all"),
+            Self::Contracts(patterns) => write!(f, "contracts={}", patterns.join(",")),
+        }
     }
-    let Some(requirement) = guard
-        .requirements
-        .iter()
-        .find(|item| item.contract_id == contract_id)
-    else {
-        return Err(RestError::internal(format!(
-            "live REST request blocked: unregistered fixture contract {contract_id}"
-        )));
+}
+
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| glob_matches(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && glob_matches(rest, &text[1..]),
+        Some((byte, rest)) => text.first() == Some(byte) && glob_matches(rest, &text[1..]),
+    }
+}
+
+/// The capture selection from `SHARED_RESTAPI_FIXTURE_CAPTURE_MODE`.
+pub fn fixture_capture_selection() -> RestFixtureCaptureSelection {
+    std::env::var(SHARED_RESTAPI_FIXTURE_CAPTURE_MODE_ENV)
+        .map(|raw| RestFixtureCaptureSelection::parse(&raw))
+        .unwrap_or_default()
+}
+
+/// Whether capture mode is on for every contract. A contract list does not count; use
+/// [`fixture_capture_mode_enabled_for`] for those.
+pub fn fixture_capture_mode_enabled() -> bool {
+    fixture_capture_selection() == RestFixtureCaptureSelection::All
+}
+
+pub fn fixture_capture_mode_enabled_for(contract_id: &str) -> bool {
+    fixture_capture_selection().includes(contract_id)
+}
+
+fn known_secret_rules() -> &'static RestRedactionRules {
+    static RULES: OnceLock<RestRedactionRules> = OnceLock::new();
+    RULES.get_or_init(RestRedactionRules::default)
+}
+
+fn fixture_violations(
+    requirement: &RestFixtureRequirement,
+    path: &Path,
+    scan_secrets: bool,
+    violations: &mut Vec<RestFixtureViolation>,
+) {
+    let mut violation = |kind| {
+        violations.push(RestFixtureViolation {
+            contract_id: requirement.contract_id.clone(),
+            kind,
+        })
     };
-    if !requirement.success_path.exists() || !requirement.error_path.exists() {
-        return Err(RestError::internal(format!(
-            "live REST request blocked: missing fixture files for contract={} success={} error={}",
-            requirement.contract_id,
-            requirement.success_path.display(),
-            requirement.error_path.display()
-        )));
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            return violation(RestFixtureViolationKind::Unreadable {
+                path: path.to_path_buf(),
+                reason: format!("failed to read fixture {}: {err}", path.display()),
+            });
+        }
+    };
+    if scan_secrets {
+        for finding in known_secret_rules().find_secrets(&bytes) {
+            violation(RestFixtureViolationKind::SecretDetected {
+                path: path.to_path_buf(),
+                finding,
+            });
+        }
     }
-    ensure_live_capture_fixture(&requirement.success_path)?;
-    ensure_live_capture_fixture(&requirement.error_path)?;
-    Ok(())
+    let provenance = match sonic_rs::from_slice::<RestFixtureProvenance>(&bytes) {
+        Ok(provenance) => provenance,
+        Err(err) => {
+            return violation(RestFixtureViolationKind::Unreadable {
+                path: path.to_path_buf(),
+                reason: format!("failed to parse fixture {}: {err}", path.display()),
+            });
+        }
+    };
+    if !provenance.is_live_capture() {
+        return violation(RestFixtureViolationKind::NotLiveCapture {
+            path: path.to_path_buf(),
+        });
+    }
+
+    let policy = &requirement.policy;
+    if let Some(max_age) = policy.max_age {
+        let age = Duration::from_millis(now_ms().saturating_sub(provenance.captured_at_ms));
+        if age > max_age {
+            violation(RestFixtureViolationKind::Stale {
+                path: path.to_path_buf(),
+                age,
+                max_age,
+            });
+        }
+    }
+    if !policy.allowed_exchange_envs.is_empty()
+        && !policy
+            .allowed_exchange_envs
+            .contains(&provenance.exchange_env)
+    {
+        violation(RestFixtureViolationKind::ExchangeEnvNotAllowed {
+            path: path.to_path_buf(),
+            exchange_env: provenance.exchange_env.clone(),
+            allowed: policy.allowed_exchange_envs.clone(),
+        });
+    }
+    if let Some(prefix) = &policy.capture_command_prefix
+        && !provenance.capture_command.starts_with(prefix.as_str())
+    {
+        violation(RestFixtureViolationKind::CaptureCommandMismatch {
+            path: path.to_path_buf(),
+            capture_command: provenance.capture_command,
+            required_prefix: prefix.clone(),
+        });
+    }
+}
+
+/// Every violation across `requirements`, in registration order.
+pub fn rest_fixture_violations(
+    requirements: &[RestFixtureRequirement],
+) -> Vec<RestFixtureViolation> {
+    requirement_violations(requirements, true)
+}
+
+/// Violations across `requirements`; the live-request gate skips the secret scan, which
+/// validation already covers, so each request only re-checks provenance.
+fn requirement_violations(
+    requirements: &[RestFixtureRequirement],
+    scan_secrets: bool,
+) -> Vec<RestFixtureViolation> {
+    let mut violations = Vec::new();
+    for requirement in requirements {
+        if !requirement.success_path.exists() || !requirement.error_path.exists() {
+            violations.push(RestFixtureViolation {
+                contract_id: requirement.contract_id.clone(),
+                kind: RestFixtureViolationKind::MissingFiles {
+                    success_path: requirement.success_path.clone(),
+                    error_path: requirement.error_path.clone(),
+                },
+            });
+            continue;
+        }
+        fixture_violations(
+            requirement,
+            &requirement.success_path,
+            scan_secrets,
+            &mut violations,
+        );
+        fixture_violations(
+            requirement,
+            &requirement.error_path,
+            scan_secrets,
+            &mut violations,
+        );
+    }
+    violations
+}
+
+pub fn ensure_live_request_allowed(request: &RestRequest) -> RestResult<()> {
+    RestFixtureRegistry::global().ensure_live_request_allowed(request)
 }
 
 pub fn validate_required_rest_contracts(requirements: &[RestFixtureRequirement]) -> RestResult<()> {
@@ -138,22 +568,26 @@ pub fn validate_required_rest_contracts(requirements: &[RestFixtureRequirement])
         ));
     }
 
-    for requirement in requirements {
-        if !requirement.success_path.exists() || !requirement.error_path.exists() {
-            return Err(RestError::internal(format!(
-                "required REST fixture validation failed: missing fixture files for contract={} success={} error={}",
-                requirement.contract_id,
-                requirement.success_path.display(),
-                requirement.error_path.display()
-            )));
-        }
-        ensure_live_capture_fixture(&requirement.success_path)?;
-        ensure_live_capture_fixture(&requirement.error_path)?;
+    let violations = rest_fixture_violations(requirements);
+    if !violations.is_empty() {
+        return Err(RestError::internal(format!(
+            "required REST fixture validation failed: {} violation(s)\n  {}",
+            violations.len(),
+            join_violations(&violations, "\n  ")
+        )));
     }
 
     Ok(())
 }
 
+fn join_violations(violations: &[RestFixtureViolation], separator: &str) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,22 +642,23 @@ mod tests {
 
     #[test]
     fn live_request_rejects_non_live_capture_fixtures() {
-        clear_required_rest_contracts_for_tests();
         let success = temp_path("success.json");
         let error = temp_path("error.json");
         let _ = std::fs::remove_file(&success);
         let _ = std::fs::remove_file(&error);
         write_fixture(success.as_path(), "synthesized");
         write_fixture(error.as_path(), "live_capture");
-        register_required_rest_contracts([RestFixtureRequirement {
+        let registry = RestFixtureRegistry::from_requirements([RestFixtureRequirement {
             contract_id: "contract-a".to_string(),
             success_path: success.clone(),
             error_path: error.clone(),
+            policy: RestFixturePolicy::default(),
         }]);
         let request = RestRequest::new(Method::GET, "https://example.invalid")
             .with_fixture_contract("contract-a");
-        let err =
-            ensure_live_request_allowed(&request).expect_err("non-live provenance should fail");
+        let err = registry
+            .ensure_live_request_allowed(&request)
+            .expect_err("non-live provenance should fail");
         assert!(
             err.to_string()
                 .contains("not compliant live-capture provenance")
@@ -234,21 +669,23 @@ mod tests {
 
     #[test]
     fn live_request_accepts_live_capture_fixtures() {
-        clear_required_rest_contracts_for_tests();
         let success = temp_path("good-success.json");
         let error = temp_path("good-error.json");
         let _ = std::fs::remove_file(&success);
         let _ = std::fs::remove_file(&error);
         write_fixture(success.as_path(), "live_capture");
         write_fixture(error.as_path(), "live_capture");
-        register_required_rest_contracts([RestFixtureRequirement {
+        let registry = RestFixtureRegistry::from_requirements([RestFixtureRequirement {
             contract_id: "contract-a".to_string(),
             success_path: success.clone(),
             error_path: error.clone(),
+            policy: RestFixturePolicy::default(),
         }]);
         let request = RestRequest::new(Method::GET, "https://example.invalid")
             .with_fixture_contract("contract-a");
-        ensure_live_request_allowed(&request).expect("live-captured fixtures should pass");
+        registry
+            .ensure_live_request_allowed(&request)
+            .expect("live-captured fixtures should pass");
         let _ = std::fs::remove_file(success);
         let _ = std::fs::remove_file(error);
     }
@@ -271,6 +708,7 @@ mod tests {
             contract_id: "contract-a".to_string(),
             success_path: success.clone(),
             error_path: error.clone(),
+            policy: RestFixturePolicy::default(),
         }])
         .expect_err("missing files should fail validation");
         assert!(err.to_string().contains("missing fixture files"));
@@ -289,10 +727,178 @@ mod tests {
             contract_id: "contract-a".to_string(),
             success_path: success.clone(),
             error_path: error.clone(),
+            policy: RestFixturePolicy::default(),
         }])
         .expect("live-captured fixtures should pass validation");
 
         let _ = std::fs::remove_file(success);
         let _ = std::fs::remove_file(error);
     }
+
+    #[test]
+    fn validator_reports_every_policy_violation_across_contracts() {
+        let success = temp_path("policy-success.json");
+        let error = temp_path("policy-error.json");
+        let missing = temp_path("policy-missing.json");
+        let _ = std::fs::remove_file(&missing);
+        write_fixture(success.as_path(), "live_capture");
+        write_fixture(error.as_path(), "live_capture");
+
+        let policy = RestFixturePolicy::default()
+            .with_max_age(Duration::from_secs(30 * 24 * 60 * 60))
+            .with_allowed_exchange_envs(["deribit_mainnet"])
+            .with_capture_command_prefix("cargo run --bin capture");
+        let requirements = [
+            RestFixtureRequirement::new("contract-a", success.clone(), error.clone())
+                .with_policy(policy),
+            RestFixtureRequirement::new("contract-b", missing.clone(), error.clone()),
+            RestFixtureRequirement::new("contract-c", success.clone(), error.clone()),
+        ];
+
+        let violations = rest_fixture_violations(&requirements);
+        assert_eq!(violations.len(), 7);
+        assert!(
+            violations[..6]
+                .iter()
+                .all(|v| v.contract_id == "contract-a")
+        );
+        assert!(matches!(
+            violations[0].kind,
+            RestFixtureViolationKind::Stale { .. }
+        ));
+        assert!(matches!(
+            violations[1].kind,
+            RestFixtureViolationKind::ExchangeEnvNotAllowed { .. }
+        ));
+        assert!(matches!(
+            violations[2].kind,
+            RestFixtureViolationKind::CaptureCommandMismatch { .. }
+        ));
+        assert!(matches!(
+            violations[6].kind,
+            RestFixtureViolationKind::MissingFiles { .. }
+        ));
+
+        let err = validate_required_rest_contracts(&requirements)
+            .expect_err("policy violations should fail validation")
+            .to_string();
+        assert!(err.contains("7 violation(s)"));
+        assert!(err.contains("contract=contract-a: fixture"));
+        assert!(err.contains("contract=contract-b: missing fixture files"));
+        assert!(err.contains("exchange_env=deribit_testnet, allowed: deribit_mainnet"));
+
+        let _ = std::fs::remove_file(success);
+        let _ = std::fs::remove_file(error);
+    }
+
+    #[test]
+    fn scoped_registries_merge_additively_and_stay_isolated() {
+        let a = RestFixtureRequirement::new("contract-a", "a/success.json", "a/error.json");
+        let b = RestFixtureRequirement::new("contract-b", "b/success.json", "b/error.json");
+        let a_moved = RestFixtureRequirement::new("contract-a", "a2/success.json", "a2/error.json");
+
+        let first = RestFixtureRegistry::from_requirements([a.clone()]);
+        let second = RestFixtureRegistry::new();
+        second.register([b.clone()]);
+        first.merge(&second);
+        assert_eq!(first.requirements(), vec![a, b.clone()]);
+        assert_eq!(second.requirements(), vec![b.clone()]);
+
+        let shared = first.clone();
+        shared.register([a_moved.clone()]);
+        assert_eq!(first.requirements(), vec![a_moved, b]);
+        assert_eq!(first.len(), 2);
+
+        let isolated = RestFixtureRegistry::new();
+        let request = RestRequest::new(Method::GET, "https://example.invalid")
+            .with_fixture_contract("contract-a");
+        let err = isolated
+            .ensure_live_request_allowed(&request)
+            .expect_err("empty scoped registry should block");
+        assert!(
+            err.to_string()
+                .contains("no required fixture contracts registered")
+        );
+    }
+
+    #[test]
+    fn capture_selection_parses_switches_and_contract_globs() {
+        assert_eq!(
+            RestFixtureCaptureSelection::parse("1"),
+            RestFixtureCaptureSelection::All
+        );
+        assert_eq!(
+            RestFixtureCaptureSelection::parse(" false "),
+            RestFixtureCaptureSelection::Disabled
+        );
+        assert_eq!(
+            RestFixtureCaptureSelection::parse(" , "),
+            RestFixtureCaptureSelection::Disabled
+        );
+        let selection = RestFixtureCaptureSelection::parse("instruments, orders.*,ticker-?");
+        assert_eq!(
+            selection.to_string(),
+            "contracts=instruments,orders.*,ticker-?"
+        );
+        assert!(selection.includes("instruments"));
+        assert!(selection.includes("orders.open"));
+        assert!(selection.includes("orders."));
+        assert!(selection.includes("ticker-1"));
+        assert!(!selection.includes("ticker-10"));
+        assert!(!selection.includes("instruments.v2"));
+        assert!(!RestFixtureCaptureSelection::Disabled.includes("instruments"));
+    }
+
+    #[test]
+    fn capture_selection_switches_ignore_case() {
+        for raw in ["True", "YES", "On", " on "] {
+            assert_eq!(
+                RestFixtureCaptureSelection::parse(raw),
+                RestFixtureCaptureSelection::All,
+                "{raw}"
+            );
+        }
+        for raw in ["False", "No", "OFF", "0"] {
+            assert_eq!(
+                RestFixtureCaptureSelection::parse(raw),
+                RestFixtureCaptureSelection::Disabled,
+                "{raw}"
+            );
+        }
+        // Unknown words are contract ids, never a switch for every contract.
+        let unknown = RestFixtureCaptureSelection::parse("enabled");
+        assert_eq!(unknown, RestFixtureCaptureSelection::contracts(["enabled"]));
+        assert!(!unknown.includes("instruments"));
+    }
+
+    #[test]
+    fn capture_selection_only_opens_the_gate_for_selected_contracts() {
+        let registry = RestFixtureRegistry::from_requirements([
+            RestFixtureRequirement::new(
+                "orders.open",
+                temp_path("selection-orders-success.json"),
+                temp_path("selection-orders-error.json"),
+            ),
+            RestFixtureRequirement::new(
+                "ticker",
+                temp_path("selection-ticker-success.json"),
+                temp_path("selection-ticker-error.json"),
+            ),
+        ]);
+        registry.set_capture_selection(Some(RestFixtureCaptureSelection::contracts(["orders.*"])));
+
+        let selected = RestRequest::new(Method::GET, "https://example.invalid")
+            .with_fixture_contract("orders.open");
+        registry
+            .ensure_live_request_allowed(&selected)
+            .expect("selected contract bypasses the gate");
+        let other = RestRequest::new(Method::GET, "https://example.invalid")
+            .with_fixture_contract("ticker");
+        let err = registry
+            .ensure_live_request_allowed(&other)
+            .expect_err("unselected contract is still gated");
+        assert!(err.to_string().contains("missing fixture files"));
+        let untagged = RestRequest::new(Method::GET, "https://example.invalid");
+        assert!(registry.ensure_live_request_allowed(&untagged).is_err());
+    }
 }
@@ -0,0 +1,200 @@
+//! Replay harness that drives every registered contract fixture through [`MockRestAdapter`].
+//!
+//! Each contract needs a decoder; contracts without one, fixtures that fail to load, and
+//! fixtures that fail to decode are all reported, so replay coverage is enforced by a single test.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use serde::Serialize;
+use sonic_rs::Deserialize;
+
+use crate::adapter::{Client, RestError, RestResponse, RestResult};
+use crate::fixture::RestFixture;
+use crate::fixture_policy::{RestFixtureRegistry, RestFixtureRequirement};
+use crate::mock::MockRestAdapter;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestFixtureKind {
+    Success,
+    Error,
+}
+
+impl std::fmt::Display for RestFixtureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Success => f.write_str("success"),
+            Self::Error => f.write_str("error"),
+        }
+    }
+}
+
+pub type RestReplayDecoder =
+    Arc<dyn Fn(RestFixtureKind, &RestResponse) -> RestResult<()> + Send + Sync>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestReplayOutcome {
+    pub contract_id: String,
+    pub kind: RestFixtureKind,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestReplayReport {
+    pub outcomes: Vec<RestReplayOutcome>,
+    /// Registered contracts with no decoder.
+    pub missing_decoders: Vec<String>,
+    /// Decoders supplied for contracts that are not registered.
+    pub unknown_decoders: Vec<String>,
+}
+
+impl RestReplayReport {
+    pub fn failures(&self) -> impl Iterator<Item = &RestReplayOutcome> {
+        self.outcomes
+            .iter()
+            .filter(|outcome| outcome.error.is_some())
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+            && self.missing_decoders.is_empty()
+            && self.unknown_decoders.is_empty()
+    }
+
+    /// Contracts with at least one failing fixture or no decoder, sorted and deduplicated.
+    pub fn failed_contracts(&self) -> Vec<String> {
+        let mut contracts = self
+            .failures()
+            .map(|outcome| outcome.contract_id.clone())
+            .chain(self.missing_decoders.iter().cloned())
+            .collect::<Vec<_>>();
+        contracts.sort();
+        contracts.dedup();
+        contracts
+    }
+
+    pub fn ensure_all_passed(&self) -> RestResult<()> {
+        if self.is_success() {
+            return Ok(());
+        }
+        let mut message = String::from("REST fixture replay failed:");
+        for contract_id in &self.missing_decoders {
+            let _ = write!(message, "\n  contract={contract_id}: no replay decoder");
+        }
+        for contract_id in &self.unknown_decoders {
+            let _ = write!(
+                message,
+                "\n  contract={contract_id}: decoder supplied for unregistered contract"
+            );
+        }
+        for outcome in self.failures() {
+            let _ = write!(
+                message,
+                "\n  contract={} fixture={}: {}",
+                outcome.contract_id,
+                outcome.kind,
+                outcome.error.as_deref().unwrap_or_default()
+            );
+        }
+        Err(RestError::internal(message))
+    }
+}
+
+pub struct RestReplayHarness {
+    requirements: Vec<RestFixtureRequirement>,
+    decoders: BTreeMap<String, RestReplayDecoder>,
+}
+
+impl RestReplayHarness {
+    pub fn new(requirements: impl IntoIterator<Item = RestFixtureRequirement>) -> Self {
+        Self {
+            requirements: requirements.into_iter().collect(),
+            decoders: BTreeMap::new(),
+        }
+    }
+
+    /// Harness over every contract in the process-wide registry.
+    pub fn from_registered() -> Self {
+        Self::from_registry(&RestFixtureRegistry::global())
+    }
+
+    pub fn from_registry(registry: &RestFixtureRegistry) -> Self {
+        Self::new(registry.requirements())
+    }
+
+    pub fn with_decoder<F>(mut self, contract_id: impl Into<String>, decoder: F) -> Self
+    where
+        F: Fn(RestFixtureKind, &RestResponse) -> RestResult<()> + Send + Sync + 'static,
+    {
+        self.decoders.insert(contract_id.into(), Arc::new(decoder));
+        self
+    }
+
+    /// Decode the success fixture into `S` and the error fixture into `E`.
+    pub fn with_json_decoder<S, E>(self, contract_id: impl Into<String>) -> Self
+    where
+        S: for<'de> Deserialize<'de>,
+        E: for<'de> Deserialize<'de>,
+    {
+        self.with_decoder(contract_id, |kind, response| match kind {
+            RestFixtureKind::Success => response.json_owned::<S>().map(drop),
+            RestFixtureKind::Error => response.json_owned::<E>().map(drop),
+        })
+    }
+
+    pub async fn run(&self) -> RestReplayReport {
+        let mut report = RestReplayReport {
+            unknown_decoders: self
+                .decoders
+                .keys()
+                .filter(|contract_id| {
+                    !self
+                        .requirements
+                        .iter()
+                        .any(|requirement| &requirement.contract_id == *contract_id)
+                })
+                .cloned()
+                .collect(),
+            ..RestReplayReport::default()
+        };
+
+        for requirement in &self.requirements {
+            let Some(decoder) = self.decoders.get(&requirement.contract_id) else {
+                report
+                    .missing_decoders
+                    .push(requirement.contract_id.clone());
+                continue;
+            };
+            for (kind, path) in [
+                (RestFixtureKind::Success, &requirement.success_path),
+                (RestFixtureKind::Error, &requirement.error_path),
+            ] {
+                let error = match RestFixture::load(path) {
+                    Ok(fixture) => replay(&fixture, kind, decoder.as_ref()).await.err(),
+                    Err(err) => Some(err),
+                };
+                report.outcomes.push(RestReplayOutcome {
+                    contract_id: requirement.contract_id.clone(),
+                    kind,
+                    error: error.map(|err| err.to_string()),
+                });
+            }
+        }
+        report
+    }
+}
+
+async fn replay(
+    fixture: &RestFixture,
+    kind: RestFixtureKind,
+    decoder: &(dyn Fn(RestFixtureKind, &RestResponse) -> RestResult<()> + Send + Sync),
+) -> RestResult<()> {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_fixture(fixture)?;
+    let response = Client::with_transport(adapter)
+        .get_response(fixture.to_request()?)
+        .await?;
+    decoder(kind, &response)
+}
@@ -0,0 +1,562 @@
+//! Structural JSON shapes derived from captured fixtures, and drift checks against them.
+//!
+//! [`JsonShape`] records field names, value types, nullability and array element shapes — never
+//! values. [`SchemaCheckingTransport`] compares live success responses for a contract against the
+//! shape of its captured success fixture and reports added, removed and retyped fields.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use sonic_rs::{JsonContainerTrait, JsonType, JsonValueTrait, Value};
+
+use crate::adapter::{
+    RestError, RestFuture, RestRawResponse, RestRequest, RestResponse, RestResult, RestTransport,
+};
+use crate::fixture::RestFixture;
+use crate::fixture_policy::{RestFixtureRegistry, RestFixtureRequirement};
+use crate::headers::{RestHeaderNames, RestRawHeaderResponse};
+use crate::stream::RestStreamingResponse;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JsonShape {
+    Null,
+    Bool,
+    Number,
+    String,
+    /// Element shape merged across every element; [`JsonShape::Unknown`] for empty arrays.
+    Array(Box<JsonShape>),
+    Object(BTreeMap<String, JsonShape>),
+    /// Seen both as null (or absent in some array elements) and as the inner shape.
+    Nullable(Box<JsonShape>),
+    /// Elements of an empty array; compatible with anything.
+    Unknown,
+    /// Conflicting non-null types across array elements; compatible with anything.
+    Any,
+}
+
+impl JsonShape {
+    pub fn from_value(value: &Value) -> Self {
+        match value.get_type() {
+            JsonType::Null => Self::Null,
+            JsonType::Boolean => Self::Bool,
+            JsonType::Number => Self::Number,
+            JsonType::String => Self::String,
+            JsonType::Array => {
+                let element = value
+                    .as_array()
+                    .map(|items| {
+                        items
+                            .iter()
+                            .map(Self::from_value)
+                            .reduce(Self::merge)
+                            .unwrap_or(Self::Unknown)
+                    })
+                    .unwrap_or(Self::Unknown);
+                Self::Array(Box::new(element))
+            }
+            JsonType::Object => Self::Object(
+                value
+                    .as_object()
+                    .map(|object| {
+                        object
+                            .iter()
+                            .map(|(name, field)| (name.to_string(), Self::from_value(field)))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+
+    pub fn from_json(bytes: &[u8]) -> RestResult<Self> {
+        let value = sonic_rs::from_slice::<Value>(bytes)?;
+        Ok(Self::from_value(&value))
+    }
+
+    /// Shape of a contract's captured success fixture body.
+    pub fn from_success_fixture(requirement: &RestFixtureRequirement) -> RestResult<Self> {
+        let fixture = RestFixture::load(&requirement.success_path)?;
+        Self::from_json(&fixture.response.body.to_bytes()?).map_err(|err| {
+            RestError::internal(format!(
+                "success fixture {} for contract={} is not JSON: {err}",
+                requirement.success_path.display(),
+                requirement.contract_id
+            ))
+        })
+    }
+
+    /// Combine two observations of the same position, e.g. two array elements.
+    pub fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (left, right) if left == right => left,
+            (Self::Unknown, shape) | (shape, Self::Unknown) => shape,
+            (Self::Any, _) | (_, Self::Any) => Self::Any,
+            (Self::Null, Self::Nullable(inner)) | (Self::Nullable(inner), Self::Null) => {
+                Self::Nullable(inner)
+            }
+            (Self::Null, shape) | (shape, Self::Null) => Self::Nullable(Box::new(shape)),
+            (Self::Nullable(left), Self::Nullable(right)) => left.merge(*right).nullable(),
+            (Self::Nullable(left), right) | (right, Self::Nullable(left)) => {
+                left.merge(right).nullable()
+            }
+            (Self::Array(left), Self::Array(right)) => Self::Array(Box::new(left.merge(*right))),
+            (Self::Object(mut left), Self::Object(mut right)) => {
+                let mut fields = BTreeMap::new();
+                for (name, shape) in std::mem::take(&mut left) {
+                    let merged = match right.remove(&name) {
+                        Some(other) => shape.merge(other),
+                        None => shape.nullable(),
+                    };
+                    fields.insert(name, merged);
+                }
+                for (name, shape) in right {
+                    fields.insert(name, shape.nullable());
+                }
+                Self::Object(fields)
+            }
+            _ => Self::Any,
+        }
+    }
+
+    fn nullable(self) -> Self {
+        match self {
+            Self::Null | Self::Nullable(_) | Self::Unknown | Self::Any => self,
+            shape => Self::Nullable(Box::new(shape)),
+        }
+    }
+
+    fn accepts(&self, actual: &Self) -> bool {
+        match (self, actual) {
+            (Self::Unknown | Self::Any, _) | (_, Self::Unknown | Self::Any) => true,
+            // A fixture field only ever captured as null says nothing about its type.
+            (Self::Null, _) | (Self::Nullable(_), Self::Null) => true,
+            (Self::Nullable(expected), Self::Nullable(actual)) => expected.accepts(actual),
+            (Self::Nullable(inner), actual) => inner.accepts(actual),
+            (Self::Array(_), Self::Array(_)) | (Self::Object(_), Self::Object(_)) => true,
+            (expected, actual) => {
+                std::mem::discriminant(expected) == std::mem::discriminant(actual)
+            }
+        }
+    }
+
+    /// Every structural difference between `self` (expected) and `actual`.
+    pub fn diff(&self, actual: &Self) -> Vec<JsonShapeChange> {
+        let mut changes = Vec::new();
+        diff_shapes("$", self, actual, &mut changes);
+        changes
+    }
+}
+
+impl fmt::Display for JsonShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool => f.write_str("bool"),
+            Self::Number => f.write_str("number"),
+            Self::String => f.write_str("string"),
+            Self::Array(element) => write!(f, "array<{element}>"),
+            Self::Object(_) => f.write_str("object"),
+            Self::Nullable(inner) => write!(f, "nullable<{inner}>"),
+            Self::Unknown => f.write_str("unknown"),
+            Self::Any => f.write_str("any"),
+        }
+    }
+}
+
+fn diff_shapes(
+    path: &str,
+    expected: &JsonShape,
+    actual: &JsonShape,
+    out: &mut Vec<JsonShapeChange>,
+) {
+    if !expected.accepts(actual) {
+        out.push(JsonShapeChange {
+            path: path.to_string(),
+            kind: JsonShapeChangeKind::Retyped {
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            },
+        });
+        return;
+    }
+    let expected = match expected {
+        JsonShape::Nullable(inner) => inner.as_ref(),
+        shape => shape,
+    };
+    let actual = match actual {
+        JsonShape::Nullable(inner) => inner.as_ref(),
+        shape => shape,
+    };
+    match (expected, actual) {
+        (JsonShape::Array(expected), JsonShape::Array(actual)) => {
+            diff_shapes(&format!("{path}[]"), expected, actual, out);
+        }
+        (JsonShape::Object(expected), JsonShape::Object(actual)) => {
+            for (name, shape) in expected {
+                let field_path = format!("{path}.{name}");
+                match actual.get(name) {
+                    Some(actual) => diff_shapes(&field_path, shape, actual, out),
+                    // Fields seen as optional in the fixture may be absent.
+                    None if matches!(shape, JsonShape::Nullable(_)) => {}
+                    None => out.push(JsonShapeChange {
+                        path: field_path,
+                        kind: JsonShapeChangeKind::Removed {
+                            expected: shape.to_string(),
+                        },
+                    }),
+                }
+            }
+            for (name, shape) in actual {
+                if !expected.contains_key(name) {
+                    out.push(JsonShapeChange {
+                        path: format!("{path}.{name}"),
+                        kind: JsonShapeChangeKind::Added {
+                            actual: shape.to_string(),
+                        },
+                    });
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum JsonShapeChangeKind {
+    Added { actual: String },
+    Removed { expected: String },
+    Retyped { expected: String, actual: String },
+}
+
+/// One structural difference at `path` (`$.result[].name` style).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct JsonShapeChange {
+    pub path: String,
+    #[serde(flatten)]
+    pub kind: JsonShapeChangeKind,
+}
+
+impl fmt::Display for JsonShapeChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            JsonShapeChangeKind::Added { actual } => write!(f, "added {} ({actual})", self.path),
+            JsonShapeChangeKind::Removed { expected } => {
+                write!(f, "removed {} ({expected})", self.path)
+            }
+            JsonShapeChangeKind::Retyped { expected, actual } => {
+                write!(f, "retyped {} ({expected} -> {actual})", self.path)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RestSchemaCheckMode {
+    #[default]
+    Off,
+    /// Record drift and return the response unchanged.
+    Report,
+    /// Record drift and fail the request.
+    Enforce,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestSchemaDrift {
+    pub contract_id: String,
+    pub url: String,
+    pub changes: Vec<JsonShapeChange>,
+}
+
+impl fmt::Display for RestSchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "response schema drift for contract={} url={}:",
+            self.contract_id, self.url
+        )?;
+        for change in &self.changes {
+            write!(f, " {change};")?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks 2xx responses for requests tagged with a `fixture_contract` against the shape of that
+/// contract's captured success fixture.
+pub struct SchemaCheckingTransport<T> {
+    inner: Arc<T>,
+    mode: RestSchemaCheckMode,
+    capture_mode_only: bool,
+    registry: RestFixtureRegistry,
+    shapes: Arc<Mutex<HashMap<String, Arc<JsonShape>>>>,
+    drift: Arc<Mutex<Vec<RestSchemaDrift>>>,
+}
+
+impl<T> Clone for SchemaCheckingTransport<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            mode: self.mode,
+            capture_mode_only: self.capture_mode_only,
+            registry: self.registry.clone(),
+            shapes: self.shapes.clone(),
+            drift: self.drift.clone(),
+        }
+    }
+}
+
+impl<T> SchemaCheckingTransport<T>
+where
+    T: RestTransport + 'static,
+{
+    pub fn new(inner: T, mode: RestSchemaCheckMode) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            mode,
+            capture_mode_only: false,
+            registry: RestFixtureRegistry::global(),
+            shapes: Arc::new(Mutex::new(HashMap::new())),
+            drift: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Only check contracts currently selected for fixture capture.
+    pub fn with_capture_mode_only(mut self, capture_mode_only: bool) -> Self {
+        self.capture_mode_only = capture_mode_only;
+        self
+    }
+
+    pub fn with_fixture_registry(mut self, registry: RestFixtureRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn mode(&self) -> RestSchemaCheckMode {
+        self.mode
+    }
+
+    /// Drift observed so far, in request order.
+    pub fn drift(&self) -> Vec<RestSchemaDrift> {
+        self.drift
+            .lock()
+            .expect("schema check mutex poisoned while reading drift")
+            .clone()
+    }
+
+    fn should_check(&self, request: &RestRequest) -> bool {
+        self.mode != RestSchemaCheckMode::Off
+            && request
+                .fixture_contract
+                .as_deref()
+                .is_some_and(|contract_id| {
+                    !self.capture_mode_only || self.registry.capture_enabled_for(contract_id)
+                })
+    }
+
+    fn expected_shape(&self, contract_id: &str) -> RestResult<Arc<JsonShape>> {
+        if let Some(shape) = self
+            .shapes
+            .lock()
+            .expect("schema check mutex poisoned while reading shapes")
+            .get(contract_id)
+        {
+            return Ok(shape.clone());
+        }
+        let requirement = self.registry.requirement(contract_id).ok_or_else(|| {
+            RestError::internal(format!(
+                "schema check failed: unregistered fixture contract {contract_id}"
+            ))
+        })?;
+        let shape = Arc::new(JsonShape::from_success_fixture(&requirement)?);
+        self.shapes
+            .lock()
+            .expect("schema check mutex poisoned while caching shape")
+            .insert(contract_id.to_string(), shape.clone());
+        Ok(shape)
+    }
+
+    fn check(&self, request: &RestRequest, status: u16, body: &[u8]) -> RestResult<()> {
+        let Some(contract_id) = request.fixture_contract.as_deref() else {
+            return Ok(());
+        };
+        if !(200..300).contains(&status) {
+            return Ok(());
+        }
+        let expected = self.expected_shape(contract_id)?;
+        let changes = match JsonShape::from_json(body) {
+            Ok(actual) => expected.diff(&actual),
+            Err(_) => vec![JsonShapeChange {
+                path: "$".to_string(),
+                kind: JsonShapeChangeKind::Retyped {
+                    expected: expected.to_string(),
+                    actual: "non-json".to_string(),
+                },
+            }],
+        };
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let drift = RestSchemaDrift {
+            contract_id: contract_id.to_string(),
+            url: request.url.clone(),
+            changes,
+        };
+        let message = drift.to_string();
+        self.drift
+            .lock()
+            .expect("schema check mutex poisoned while recording drift")
+            .push(drift);
+        match self.mode {
+            RestSchemaCheckMode::Enforce => Err(RestError::internal(message)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T> RestTransport for SchemaCheckingTransport<T>
+where
+    T: RestTransport + 'static,
+{
+    fn execute(&self, request: RestRequest) -> RestFuture<RestResult<RestResponse>> {
+        if !self.should_check(&request) {
+            return self.inner.execute(request);
+        }
+        let transport = self.clone();
+        Box::pin(async move {
+            let response = transport.inner.execute(request.clone()).await?;
+            transport.check(&request, response.status, &response.body)?;
+            Ok(response)
+        })
+    }
+
+    fn execute_raw(&self, request: RestRequest) -> RestFuture<RestResult<RestRawResponse>> {
+        if !self.should_check(&request) {
+            return self.inner.execute_raw(request);
+        }
+        let transport = self.clone();
+        Box::pin(async move {
+            let response = transport.inner.execute_raw(request.clone()).await?;
+            transport.check(&request, response.0, &response.1)?;
+            Ok(response)
+        })
+    }
+
+    fn execute_raw_with_headers(
+        &self,
+        request: RestRequest,
+        keep: RestHeaderNames,
+    ) -> RestFuture<RestResult<RestRawHeaderResponse>> {
+        if !self.should_check(&request) {
+            return self.inner.execute_raw_with_headers(request, keep);
+        }
+        let transport = self.clone();
+        Box::pin(async move {
+            let response = transport
+                .inner
+                .execute_raw_with_headers(request.clone(), keep)
+                .await?;
+            transport.check(&request, response.status, &response.body)?;
+            Ok(response)
+        })
+    }
+
+    /// Checked success bodies are buffered for the shape check, then served as one chunk.
+    fn execute_stream(
+        &self,
+        request: RestRequest,
+    ) -> RestFuture<RestResult<RestStreamingResponse>> {
+        if !self.should_check(&request) {
+            return self.inner.execute_stream(request);
+        }
+        let transport = self.clone();
+        Box::pin(async move {
+            let response = transport.inner.execute_stream(request.clone()).await?;
+            if !response.is_success() {
+                return Ok(response);
+            }
+            let response = response.into_response().await?;
+            transport.check(&request, response.status, &response.body)?;
+            Ok(RestStreamingResponse::buffered(response))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(json: &str) -> JsonShape {
+        JsonShape::from_json(json.as_bytes()).expect("valid json")
+    }
+
+    #[test]
+    fn array_elements_merge_into_nullable_and_optional_fields() {
+        let derived =
+            shape(r#"{"result":[{"id":1,"mark":null},{"id":2,"mark":1.5,"tag":"x"}],"ok":true}"#);
+        let JsonShape::Object(fields) = &derived else {
+            panic!("expected object shape");
+        };
+        assert_eq!(fields["ok"], JsonShape::Bool);
+        let JsonShape::Array(element) = &fields["result"] else {
+            panic!("expected array shape");
+        };
+        assert_eq!(
+            **element,
+            JsonShape::Object(BTreeMap::from([
+                ("id".to_string(), JsonShape::Number),
+                (
+                    "mark".to_string(),
+                    JsonShape::Nullable(Box::new(JsonShape::Number))
+                ),
+                (
+                    "tag".to_string(),
+                    JsonShape::Nullable(Box::new(JsonShape::String))
+                ),
+            ]))
+        );
+        assert_eq!(shape("[]"), JsonShape::Array(Box::new(JsonShape::Unknown)));
+        assert_eq!(
+            shape(r#"[1,"a"]"#),
+            JsonShape::Array(Box::new(JsonShape::Any))
+        );
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_retyped_fields() {
+        let expected = shape(r#"{"result":[{"id":1,"mark":null,"name":"a"}],"ts":1}"#);
+        let actual = shape(r#"{"result":[{"id":"1","mark":2.0,"kind":"perp"}],"ts":1}"#);
+        let changes = expected
+            .diff(&actual)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                "retyped $.result[].id (number -> string)",
+                "removed $.result[].name (string)",
+                "added $.result[].kind (string)",
+            ]
+        );
+        assert!(expected.diff(&shape(r#"{"result":[],"ts":2}"#)).is_empty());
+    }
+
+    #[test]
+    fn nullable_fields_match_themselves() {
+        let orders = shape(r#"{"orders":[{"id":1,"price":1.5},{"id":2,"price":null}]}"#);
+        assert_eq!(orders.diff(&orders), Vec::new());
+        assert_eq!(
+            orders.diff(&shape(
+                r#"{"orders":[{"id":3,"price":null},{"id":4,"price":2}]}"#
+            )),
+            Vec::new()
+        );
+    }
+}
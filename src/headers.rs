@@ -0,0 +1,251 @@
+//! Case-insensitive response header lookup and typed accessors.
+//!
+//! [`RestResponse`](crate::RestResponse) and [`RestRawHeaderResponse`] expose the same helpers;
+//! they forward to the functions here, which work over any `(name, value)` header list.
+
+use std::borrow::Cow;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::adapter::RestBytes;
+
+/// Parsed `Retry-After` header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestRetryAfter {
+    Delay(Duration),
+    At(SystemTime),
+}
+
+impl RestRetryAfter {
+    /// Time left to wait as of `now`; zero once an absolute date has passed.
+    pub fn delay_from(&self, now: SystemTime) -> Duration {
+        match self {
+            Self::Delay(delay) => *delay,
+            Self::At(at) => at.duration_since(now).unwrap_or_default(),
+        }
+    }
+}
+
+/// Rate-limit headers, from the IETF `RateLimit-*` fields or the common `X-RateLimit-*` ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RestRateLimit {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// Reset value as sent: delta seconds for `RateLimit-Reset`, while some vendors send epoch
+    /// seconds in `X-RateLimit-Reset`.
+    pub reset: Option<u64>,
+}
+
+/// Header names a selected-header request keeps. Usually the caller's static list; owned when
+/// the client adds the names its rate-limit gate observes.
+pub type RestHeaderNames = Cow<'static, [&'static str]>;
+
+/// A raw response that keeps only the headers selected by the caller.
+///
+/// Header names are the caller's `&'static str`s, so only the retained values are copied.
+#[derive(Clone, Debug)]
+pub struct RestRawHeaderResponse {
+    pub status: u16,
+    pub headers: Vec<(&'static str, RestBytes)>,
+    pub body: RestBytes,
+    pub elapsed: Duration,
+}
+
+impl RestRawHeaderResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, name)
+    }
+
+    pub fn header_bytes(&self, name: &str) -> Option<&[u8]> {
+        header_bytes(&self.headers, name)
+    }
+
+    pub fn header_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        header_all(&self.headers, name)
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        header(&self.headers, "content-type")
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        content_length(&self.headers)
+    }
+
+    pub fn date(&self) -> Option<SystemTime> {
+        date(&self.headers)
+    }
+
+    pub fn retry_after(&self) -> Option<RestRetryAfter> {
+        retry_after(&self.headers)
+    }
+
+    pub fn etag(&self) -> Option<&str> {
+        header(&self.headers, "etag")
+    }
+
+    pub fn rate_limit(&self) -> Option<RestRateLimit> {
+        rate_limit(&self.headers)
+    }
+}
+
+pub(crate) fn header_bytes<'a, N>(headers: &'a [(N, RestBytes)], name: &str) -> Option<&'a [u8]>
+where
+    N: AsRef<str>,
+{
+    headers
+        .iter()
+        .find(|(key, _)| key.as_ref().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_ref())
+}
+
+/// First value for `name` that is valid UTF-8, with surrounding whitespace trimmed.
+pub(crate) fn header<'a, N>(headers: &'a [(N, RestBytes)], name: &str) -> Option<&'a str>
+where
+    N: AsRef<str>,
+{
+    header_all(headers, name).next()
+}
+
+pub(crate) fn header_all<'a, N>(
+    headers: &'a [(N, RestBytes)],
+    name: &str,
+) -> impl Iterator<Item = &'a str>
+where
+    N: AsRef<str>,
+{
+    headers
+        .iter()
+        .filter(move |(key, _)| key.as_ref().eq_ignore_ascii_case(name))
+        .filter_map(|(_, value)| std::str::from_utf8(value).ok())
+        .map(str::trim)
+}
+
+pub(crate) fn content_length<N: AsRef<str>>(headers: &[(N, RestBytes)]) -> Option<u64> {
+    header(headers, "content-length")?.parse().ok()
+}
+
+pub(crate) fn date<N: AsRef<str>>(headers: &[(N, RestBytes)]) -> Option<SystemTime> {
+    parse_http_date(header(headers, "date")?)
+}
+
+pub(crate) fn retry_after<N: AsRef<str>>(headers: &[(N, RestBytes)]) -> Option<RestRetryAfter> {
+    let value = header(headers, "retry-after")?;
+    match value.parse::<u64>() {
+        Ok(seconds) => Some(RestRetryAfter::Delay(Duration::from_secs(seconds))),
+        Err(_) => parse_http_date(value).map(RestRetryAfter::At),
+    }
+}
+
+pub(crate) fn rate_limit<N: AsRef<str>>(headers: &[(N, RestBytes)]) -> Option<RestRateLimit> {
+    let field = |suffix: &str| {
+        header(headers, &format!("ratelimit-{suffix}"))
+            .or_else(|| header(headers, &format!("x-ratelimit-{suffix}")))
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+    let limit = RestRateLimit {
+        limit: field("limit"),
+        remaining: field("remaining"),
+        reset: field("reset"),
+    };
+    (limit != RestRateLimit::default()).then_some(limit)
+}
+
+/// Parse an IMF-fixdate (`Sun, 06 Nov 1994 08:49:37 GMT`), the only HTTP-date form senders
+/// may generate.
+pub fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let (_weekday, rest) = value.trim().split_once(", ")?;
+    let mut parts = rest.split(' ');
+    let day = parts.next()?.parse::<u32>().ok()?;
+    let month = match parts.next()? {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let year = parts.next()?.parse::<i64>().ok()?;
+    let mut clock = parts.next()?.split(':');
+    let hour = clock.next()?.parse::<u64>().ok()?;
+    let minute = clock.next()?.parse::<u64>().ok()?;
+    let second = clock.next()?.parse::<u64>().ok()?;
+    if parts.next()? != "GMT" || parts.next().is_some() || clock.next().is_some() {
+        return None;
+    }
+    if !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_dates_parse_as_imf_fixdate_only() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(784_111_777))
+        );
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 00:00:00 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_164_800))
+        );
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 UTC"), None);
+        assert_eq!(parse_http_date("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+    }
+
+    #[test]
+    fn lookups_ignore_case_and_skip_non_utf8_values() {
+        let headers = vec![
+            ("Set-Cookie".to_string(), RestBytes::from_static(b"a=1")),
+            ("set-cookie".to_string(), RestBytes::from_static(&[0xff])),
+            ("SET-COOKIE".to_string(), RestBytes::from_static(b" b=2 ")),
+            (
+                "X-RateLimit-Remaining".to_string(),
+                RestBytes::from_static(b"7"),
+            ),
+        ];
+        assert_eq!(header(&headers, "set-cookie"), Some("a=1"));
+        assert_eq!(
+            header_all(&headers, "Set-Cookie").collect::<Vec<_>>(),
+            vec!["a=1", "b=2"]
+        );
+        assert_eq!(header_bytes(&headers, "SET-cookie"), Some(&b"a=1"[..]));
+        assert_eq!(
+            rate_limit(&headers),
+            Some(RestRateLimit {
+                remaining: Some(7),
+                ..RestRateLimit::default()
+            })
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+}
@@ -4,8 +4,26 @@
 #![allow(dead_code)]
 
 pub mod adapter;
+pub mod compression;
+pub mod concurrency;
+pub mod fault;
+pub mod fixture;
+pub mod fixture_capture;
+pub mod fixture_diff;
+pub mod fixture_manifest;
 pub mod fixture_policy;
+pub mod fixture_replay;
+pub mod fixture_schema;
+pub mod headers;
 pub mod mock;
+pub mod mock_expect;
+pub mod ndjson;
+pub mod paginate;
+pub mod rate_limit;
+pub mod redact;
+pub mod sse;
+pub mod stream;
+pub mod transport_builder;
 
 pub use reqwest::Method;
 
@@ -13,12 +31,58 @@ pub use adapter::{
     Client, ReqwestTransport, RestBytes, RestError, RestErrorKind, RestFuture, RestRequest,
     RestResponse, RestResult, RestRetryPolicy, RestTransport, RestTransportState,
 };
+pub use compression::{RestBodyCompression, RestContentEncoding};
+pub use concurrency::RestConcurrencyLimits;
+pub use fault::{
+    FaultInjectingTransport, FaultInjectionConfig, FaultInjectionRecord, InjectedFault,
+};
+pub use fixture::{
+    REST_FIXTURE_FORMAT_VERSION, RestFixture, RestFixtureBody, RestFixtureProvenance,
+    RestFixtureRequest, RestFixtureResponse,
+};
+pub use fixture_capture::{
+    RecordingTransport, ReplayingTransport, SHARED_RESTAPI_FIXTURE_CAPTURE_COMMAND_ENV,
+    SHARED_RESTAPI_FIXTURE_EXCHANGE_ENV_ENV,
+};
+pub use fixture_diff::{
+    RestFixtureDiff, RestFixtureDiffOptions, RestFixtureRefreshReport, RestFixtureValueChange,
+    diff_fixtures,
+};
+pub use fixture_manifest::{
+    REST_FIXTURE_ERROR_FILE, REST_FIXTURE_SUCCESS_FILE, RestFixtureManifest,
+    RestFixtureManifestContract, RestFixtureManifestPolicy, discover_rest_fixture_contracts,
+    load_rest_fixture_manifest, register_rest_fixture_manifest,
+};
 pub use fixture_policy::{
-    RestFixtureRequirement, clear_required_rest_contracts_for_tests, ensure_live_request_allowed,
-    fixture_capture_mode_enabled as rest_fixture_capture_mode_enabled,
-    register_required_rest_contracts, required_rest_contracts, validate_required_rest_contracts,
+    RestFixtureCaptureSelection, RestFixturePolicy, RestFixtureRegistry, RestFixtureRequirement,
+    RestFixtureViolation, RestFixtureViolationKind, clear_required_rest_contracts_for_tests,
+    ensure_live_request_allowed, fixture_capture_mode_enabled as rest_fixture_capture_mode_enabled,
+    fixture_capture_mode_enabled_for as rest_fixture_capture_mode_enabled_for,
+    fixture_capture_selection as rest_fixture_capture_selection, register_required_rest_contracts,
+    required_rest_contracts, rest_fixture_violations, validate_required_rest_contracts,
+};
+pub use fixture_replay::{
+    RestFixtureKind, RestReplayDecoder, RestReplayHarness, RestReplayOutcome, RestReplayReport,
+};
+pub use fixture_schema::{
+    JsonShape, JsonShapeChange, JsonShapeChangeKind, RestSchemaCheckMode, RestSchemaDrift,
+    SchemaCheckingTransport,
+};
+pub use headers::{
+    RestHeaderNames, RestRateLimit, RestRawHeaderResponse, RestRetryAfter, parse_http_date,
 };
 pub use mock::{
-    MockBehavior, MockBehaviorPlan, MockOperation, MockResponse, MockRestAdapter,
-    MockRestStateSnapshot, MockScenario, MockScenarioStep, MockScenarioStepKind,
+    MockBehavior, MockBehaviorPlan, MockChunk, MockOperation, MockResponse, MockRestAdapter,
+    MockRestStateSnapshot, MockRoute, MockScenario, MockScenarioStep, MockScenarioStepKind,
+    MockSseFrame, MockUrlMatcher,
+};
+pub use mock_expect::{MockCallCount, MockExpectation, MockExpectationError};
+pub use ndjson::RestNdjsonStream;
+pub use paginate::{RestPageItems, RestPageStrategy, RestPaginator};
+pub use rate_limit::{RateLimitGate, RestRateLimitKey, RestRateLimitObservation, RestTokenBucket};
+pub use redact::{REST_REDACTED, RestRedactionRules};
+pub use sse::{RestSseEvent, RestSseStream};
+pub use stream::{RestBodyStream, RestStreamingResponse};
+pub use transport_builder::{
+    ReqwestTransportBuilder, RestCertificate, RestClientIdentity, RestProxy,
 };
@@ -8,6 +8,12 @@ use bytes::Bytes;
 use reqwest::Method;
 use sonic_rs::{Serialize, to_vec};
 
+use crate::compression::{RestBodyDecoder, RestContentEncoding, strip_encoding_headers};
+use crate::fixture::RestFixture;
+use crate::mock_expect::{self, MockExpectation, MockExpectationError};
+use crate::redact::RestRedactionRules;
+use crate::stream::{RestBodyStream, RestStreamingResponse};
+
 use super::adapter::{
     RestBytes, RestError, RestErrorKind, RestFuture, RestRawResponse, RestRequest, RestResponse,
     RestResult, RestTransport, RestTransportState,
@@ -28,8 +34,50 @@ pub struct MockScenarioStep {
     pub status: Option<u16>,
     pub message: Option<String>,
     pub delay: Option<Duration>,
+    /// Frames served by a `Replay` step, one per request the step applies to, in order.
+    pub responses: Vec<MockResponse>,
+    /// Restrict the step to matching requests; `None` uses the global sequence.
+    pub route: Option<MockRoute>,
 }
 
+impl MockScenarioStep {
+    pub fn new(kind: MockScenarioStepKind) -> Self {
+        Self {
+            kind,
+            status: None,
+            message: None,
+            delay: None,
+            responses: Vec::new(),
+            route: None,
+        }
+    }
+
+    pub fn with_route(mut self, route: MockRoute) -> Self {
+        self.route = Some(route);
+        self
+    }
+
+    /// The behavior the mock applies when this step is reached.
+    pub fn behavior(&self) -> MockBehavior {
+        match self.kind {
+            MockScenarioStepKind::Pass => MockBehavior::Pass,
+            MockScenarioStepKind::Delay => MockBehavior::Delay(self.delay.unwrap_or_default()),
+            MockScenarioStepKind::Reject => MockBehavior::Reject {
+                status: self.status.unwrap_or(500),
+                reason: self
+                    .message
+                    .clone()
+                    .unwrap_or_else(|| "rejected".to_string()),
+            },
+            MockScenarioStepKind::Drop => MockBehavior::Drop,
+            MockScenarioStepKind::Replay => MockBehavior::Replay(self.responses.clone()),
+        }
+    }
+}
+
+/// Ordered list of mock steps that can be composed with [`then`](Self::then),
+/// [`repeat`](Self::repeat), [`interleave`](Self::interleave) and scoped with
+/// [`routed`](Self::routed).
 #[derive(Clone, Debug)]
 pub struct MockScenario(Vec<MockScenarioStep>);
 
@@ -43,45 +91,95 @@ impl MockScenario {
         self
     }
 
-    pub fn pass(mut self) -> Self {
-        self.0.push(MockScenarioStep {
-            kind: MockScenarioStepKind::Pass,
-            status: None,
-            message: None,
-            delay: None,
-        });
-        self
+    pub fn pass(self) -> Self {
+        self.push(MockScenarioStep::new(MockScenarioStepKind::Pass))
     }
 
-    pub fn delay(mut self, duration: Duration) -> Self {
-        self.0.push(MockScenarioStep {
-            kind: MockScenarioStepKind::Delay,
-            status: None,
-            message: None,
+    pub fn delay(self, duration: Duration) -> Self {
+        self.push(MockScenarioStep {
             delay: Some(duration),
-        });
-        self
+            ..MockScenarioStep::new(MockScenarioStepKind::Delay)
+        })
     }
 
-    pub fn reject(mut self, status: u16, message: impl Into<String>) -> Self {
-        self.0.push(MockScenarioStep {
-            kind: MockScenarioStepKind::Reject,
+    pub fn reject(self, status: u16, message: impl Into<String>) -> Self {
+        self.push(MockScenarioStep {
             status: Some(status),
             message: Some(message.into()),
-            delay: None,
-        });
+            ..MockScenarioStep::new(MockScenarioStepKind::Reject)
+        })
+    }
+
+    pub fn drop_response(self) -> Self {
+        self.push(MockScenarioStep::new(MockScenarioStepKind::Drop))
+    }
+
+    pub fn replay(self, frames: impl IntoIterator<Item = MockResponse>) -> Self {
+        self.push(MockScenarioStep {
+            responses: frames.into_iter().collect(),
+            ..MockScenarioStep::new(MockScenarioStepKind::Replay)
+        })
+    }
+
+    /// Scope every step that has no route yet to `route`.
+    pub fn routed(mut self, route: MockRoute) -> Self {
+        for step in &mut self.0 {
+            if step.route.is_none() {
+                step.route = Some(route.clone());
+            }
+        }
         self
     }
 
-    pub fn drop_response(mut self) -> Self {
-        self.0.push(MockScenarioStep {
-            kind: MockScenarioStepKind::Drop,
-            status: None,
-            message: None,
-            delay: None,
-        });
+    /// Append the steps of `next` after this scenario.
+    pub fn then(mut self, next: MockScenario) -> Self {
+        self.0.extend(next.0);
         self
     }
+
+    pub fn sequence(scenarios: impl IntoIterator<Item = MockScenario>) -> Self {
+        scenarios
+            .into_iter()
+            .fold(Self::new(), |scenario, next| scenario.then(next))
+    }
+
+    /// Run this scenario `times` times back to back.
+    pub fn repeat(self, times: usize) -> Self {
+        let steps = self.0;
+        let mut repeated = Vec::with_capacity(steps.len() * times);
+        for _ in 0..times {
+            repeated.extend(steps.iter().cloned());
+        }
+        Self(repeated)
+    }
+
+    /// Merge scenarios round-robin: first step of each, then second step of each, and so on.
+    pub fn interleave(scenarios: impl IntoIterator<Item = MockScenario>) -> Self {
+        let mut sources = scenarios
+            .into_iter()
+            .map(|scenario| scenario.0.into_iter())
+            .collect::<Vec<_>>();
+        let mut steps = Vec::new();
+        loop {
+            let before = steps.len();
+            steps.extend(sources.iter_mut().filter_map(Iterator::next));
+            if steps.len() == before {
+                return Self(steps);
+            }
+        }
+    }
+
+    pub fn steps(&self) -> &[MockScenarioStep] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 impl Default for MockScenario {
@@ -90,8 +188,9 @@ impl Default for MockScenario {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub enum MockBehavior {
+    #[default]
     Pass,
     Delay(Duration),
     Reject {
@@ -193,10 +292,117 @@ pub enum MockOperation {
     Request,
 }
 
+/// How a [`MockRoute`] compares against a request URL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MockUrlMatcher {
+    Any,
+    Exact(String),
+    Prefix(String),
+    /// Matches the URL path only, ignoring scheme, host, query string and fragment.
+    Path(String),
+}
+
+impl MockUrlMatcher {
+    fn matches(&self, url: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Exact(expected) => url == expected,
+            Self::Prefix(prefix) => url.starts_with(prefix.as_str()),
+            Self::Path(path) => url_path(url) == path,
+        }
+    }
+}
+
+fn url_path(url: &str) -> &str {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = without_scheme
+        .find('/')
+        .map_or("/", |index| &without_scheme[index..]);
+    path.split(['?', '#']).next().unwrap_or(path)
+}
+
+/// Request matcher used by mock expectations and route-scoped behavior.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockRoute {
+    pub method: Option<Method>,
+    pub url: MockUrlMatcher,
+}
+
+impl MockRoute {
+    pub fn any() -> Self {
+        Self {
+            method: None,
+            url: MockUrlMatcher::Any,
+        }
+    }
+
+    pub fn exact(method: Method, url: impl Into<String>) -> Self {
+        Self {
+            method: Some(method),
+            url: MockUrlMatcher::Exact(url.into()),
+        }
+    }
+
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::exact(Method::GET, url)
+    }
+
+    pub fn post(url: impl Into<String>) -> Self {
+        Self::exact(Method::POST, url)
+    }
+
+    pub fn prefix(method: Method, prefix: impl Into<String>) -> Self {
+        Self {
+            method: Some(method),
+            url: MockUrlMatcher::Prefix(prefix.into()),
+        }
+    }
+
+    pub fn path(method: Method, path: impl Into<String>) -> Self {
+        Self {
+            method: Some(method),
+            url: MockUrlMatcher::Path(path.into()),
+        }
+    }
+
+    /// Drop the method constraint so the route matches every HTTP method.
+    pub fn any_method(mut self) -> Self {
+        self.method = None;
+        self
+    }
+
+    pub fn matches(&self, request: &RestRequest) -> bool {
+        self.method
+            .as_ref()
+            .is_none_or(|method| *method == request.method)
+            && self.url.matches(&request.url)
+    }
+}
+
+impl std::fmt::Display for MockRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.method {
+            Some(method) => write!(f, "{method} ")?,
+            None => f.write_str("* ")?,
+        }
+        match &self.url {
+            MockUrlMatcher::Any => f.write_str("*"),
+            MockUrlMatcher::Exact(url) => f.write_str(url),
+            MockUrlMatcher::Prefix(prefix) => write!(f, "{prefix}*"),
+            MockUrlMatcher::Path(path) => write!(f, "*{path}"),
+        }
+    }
+}
+
+/// Queued behaviors, either global or scoped to a [`MockRoute`].
+///
+/// Route-scoped sequences only advance when a matching request arrives; requests that match no
+/// route with pending behaviors fall back to the global queue.
 #[derive(Clone, Debug, Default)]
 pub struct MockBehaviorPlan {
     request: VecDeque<MockBehavior>,
-    scenario: VecDeque<MockScenarioStep>,
+    routes: Vec<(MockRoute, VecDeque<MockBehavior>)>,
+    consumed: usize,
 }
 
 impl MockBehaviorPlan {
@@ -209,46 +415,166 @@ impl MockBehaviorPlan {
         self.push(behavior)
     }
 
+    /// Append `behavior` to the sequence for `route`. Routes are checked in the order they were
+    /// first added.
+    pub fn push_for(&mut self, route: MockRoute, behavior: MockBehavior) -> &mut Self {
+        match self
+            .routes
+            .iter_mut()
+            .find(|(existing, _)| *existing == route)
+        {
+            Some((_, queue)) => queue.push_back(behavior),
+            None => self.routes.push((route, VecDeque::from([behavior]))),
+        }
+        self
+    }
+
     pub fn pop(&mut self, _operation: MockOperation) -> MockBehavior {
-        match _operation {
-            MockOperation::Request => self.request.pop_front().unwrap_or_default(),
+        let behavior = match _operation {
+            MockOperation::Request => Self::take_step(&mut self.request, &mut self.consumed),
+        };
+        behavior.unwrap_or_default()
+    }
+
+    /// Pop the front behavior of `queue`. A `Replay` step with several frames hands out one
+    /// frame and stays at the front until its last frame is served, so its frames only reach
+    /// requests the step applies to; the step counts as consumed once it is used up.
+    fn take_step(queue: &mut VecDeque<MockBehavior>, consumed: &mut usize) -> Option<MockBehavior> {
+        let behavior = match queue.front_mut()? {
+            MockBehavior::Replay(frames) if frames.len() > 1 => {
+                return Some(MockBehavior::Replay(vec![frames.remove(0)]));
+            }
+            _ => queue.pop_front()?,
+        };
+        *consumed += 1;
+        Some(behavior)
+    }
+
+    /// Pop the next behavior for `request`, preferring the first matching route sequence that
+    /// still has entries and falling back to the global queue.
+    pub fn pop_for(&mut self, operation: MockOperation, request: &RestRequest) -> MockBehavior {
+        let routed = self
+            .routes
+            .iter_mut()
+            .find(|(route, queue)| !queue.is_empty() && route.matches(request))
+            .and_then(|(_, queue)| Self::take_step(queue, &mut self.consumed));
+        match routed {
+            Some(behavior) => behavior,
+            None => self.pop(operation),
         }
     }
 
+    pub fn remaining(&self) -> usize {
+        self.request.len()
+    }
+
+    pub fn route_remaining(&self) -> usize {
+        self.routes.iter().map(|(_, queue)| queue.len()).sum()
+    }
+
+    /// Number of planned behaviors consumed so far, i.e. the index of the next scenario step.
+    pub fn step_index(&self) -> usize {
+        self.consumed
+    }
+
     pub fn push_scenario_step(&mut self, step: MockScenarioStep) -> &mut Self {
-        self.scenario.push_back(step);
+        let behavior = step.behavior();
+        match step.route {
+            Some(route) => self.push_for(route, behavior),
+            None => self.push(behavior),
+        }
+    }
+
+    pub fn push_scenario(&mut self, scenario: MockScenario) -> &mut Self {
+        for step in scenario.0 {
+            self.push_scenario_step(step);
+        }
         self
     }
 
     pub fn scenario(scenario: MockScenario) -> Self {
-        let steps = scenario.0;
+        let mut plan = Self::default();
+        plan.push_scenario(scenario);
+        plan
+    }
+}
+
+/// One piece of a chunked mock body, delivered to streaming readers after `delay`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockChunk {
+    pub delay: Duration,
+    pub bytes: RestBytes,
+}
+
+impl MockChunk {
+    pub fn new(bytes: impl Into<RestBytes>) -> Self {
         Self {
-            request: steps
-                .iter()
-                .map(|step| match step.kind {
-                    MockScenarioStepKind::Pass => MockBehavior::Pass,
-                    MockScenarioStepKind::Delay => {
-                        MockBehavior::Delay(step.delay.unwrap_or_else(|| Duration::from_millis(0)))
-                    }
-                    MockScenarioStepKind::Reject => MockBehavior::Reject {
-                        status: step.status.unwrap_or(500),
-                        reason: step
-                            .message
-                            .clone()
-                            .unwrap_or_else(|| "rejected".to_string()),
-                    },
-                    MockScenarioStepKind::Drop => MockBehavior::Drop,
-                    MockScenarioStepKind::Replay => MockBehavior::Pass,
-                })
-                .collect(),
-            scenario: steps.into_iter().collect(),
+            delay: Duration::ZERO,
+            bytes: bytes.into(),
         }
     }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
 }
 
-impl Default for MockBehavior {
-    fn default() -> Self {
-        Self::Pass
+/// One Server-Sent Events frame for [`MockResponse::sse`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MockSseFrame {
+    pub delay: Duration,
+    pub event: Option<String>,
+    pub data: String,
+    pub id: Option<String>,
+    pub retry: Option<Duration>,
+}
+
+impl MockSseFrame {
+    pub fn new(data: impl Into<String>) -> Self {
+        Self {
+            data: data.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Wire encoding; multi-line data becomes one `data:` line per line.
+    pub fn encode(&self) -> String {
+        let mut frame = String::new();
+        if let Some(retry) = self.retry {
+            frame.push_str(&format!("retry: {}\n", retry.as_millis()));
+        }
+        if let Some(event) = &self.event {
+            frame.push_str(&format!("event: {event}\n"));
+        }
+        if let Some(id) = &self.id {
+            frame.push_str(&format!("id: {id}\n"));
+        }
+        for line in self.data.split('\n') {
+            frame.push_str(&format!("data: {line}\n"));
+        }
+        frame.push('\n');
+        frame
     }
 }
 
@@ -257,6 +583,8 @@ pub struct MockResponse {
     pub status: u16,
     pub headers: Vec<(String, RestBytes)>,
     pub body: RestBytes,
+    /// Scripted chunks for `Client::get_stream`; empty streams `body` as one chunk.
+    pub chunks: Vec<MockChunk>,
 }
 
 impl MockResponse {
@@ -265,7 +593,35 @@ impl MockResponse {
             status,
             headers: Vec::new(),
             body: body.into(),
+            chunks: Vec::new(),
+        }
+    }
+
+    /// A response streamed in `chunks`; buffered reads see the concatenated body.
+    pub fn chunked(status: u16, chunks: impl IntoIterator<Item = MockChunk>) -> Self {
+        Self::new(status, Bytes::new()).with_chunks(chunks)
+    }
+
+    /// A `text/event-stream` response delivering one chunk per frame; the body ends after the
+    /// last frame, so readers reconnect.
+    pub fn sse(frames: impl IntoIterator<Item = MockSseFrame>) -> Self {
+        Self::chunked(
+            200,
+            frames
+                .into_iter()
+                .map(|frame| MockChunk::new(frame.encode()).with_delay(frame.delay)),
+        )
+        .with_header("content-type", "text/event-stream")
+    }
+
+    pub fn with_chunks(mut self, chunks: impl IntoIterator<Item = MockChunk>) -> Self {
+        self.chunks = chunks.into_iter().collect();
+        let mut body = Vec::with_capacity(self.chunks.iter().map(|chunk| chunk.bytes.len()).sum());
+        for chunk in &self.chunks {
+            body.extend_from_slice(&chunk.bytes);
         }
+        self.body = Bytes::from(body);
+        self
     }
 
     pub fn with_header(mut self, key: impl Into<String>, value: impl Into<RestBytes>) -> Self {
@@ -273,6 +629,24 @@ impl MockResponse {
         self
     }
 
+    /// Serve the body compressed with `Content-Encoding: encoding`. Chunked responses keep their
+    /// chunk count and delays, with the encoded bytes split evenly across the chunks.
+    pub fn with_content_encoding(mut self, encoding: RestContentEncoding) -> RestResult<Self> {
+        let encoded = encoding.encode(&self.body)?;
+        if !self.chunks.is_empty() {
+            let size = encoded.len().div_ceil(self.chunks.len()).max(1);
+            let mut pieces = encoded.chunks(size);
+            for chunk in &mut self.chunks {
+                chunk.bytes = pieces
+                    .next()
+                    .map(|piece| encoded.slice_ref(piece))
+                    .unwrap_or_default();
+            }
+        }
+        self.body = encoded;
+        Ok(self.with_header("content-encoding", encoding.as_str()))
+    }
+
     pub fn bytes(status: u16, body: impl Into<RestBytes>) -> Self {
         Self::new(status, body)
     }
@@ -302,6 +676,8 @@ pub struct MockRestStateSnapshot {
     pub last_url: Option<String>,
     pub last_status: Option<u16>,
     pub behavior_remaining: usize,
+    pub route_behavior_remaining: usize,
+    pub scenario_step: usize,
     pub response_queue_len: usize,
     pub route_queue_len: usize,
     pub inbound_count: usize,
@@ -323,6 +699,7 @@ struct MockRestAdapterState {
     pub inbound_log: Vec<RestResponse>,
     pub last_error: Option<String>,
     pub elapsed_total: Duration,
+    pub redaction: Option<RestRedactionRules>,
 }
 
 impl MockRestAdapterState {
@@ -332,7 +709,9 @@ impl MockRestAdapterState {
             request_count: self.request_count,
             last_url: self.last_url.clone(),
             last_status: self.last_status,
-            behavior_remaining: self.behavior_plan.request.len(),
+            behavior_remaining: self.behavior_plan.remaining(),
+            route_behavior_remaining: self.behavior_plan.route_remaining(),
+            scenario_step: self.behavior_plan.step_index(),
             response_queue_len: self.default_response_queue.len(),
             route_queue_len: self.route_response_queues.values().map(VecDeque::len).sum(),
             inbound_count: self.inbound_log.len(),
@@ -357,6 +736,7 @@ impl Default for MockRestAdapterState {
             inbound_log: Vec::new(),
             last_error: None,
             elapsed_total: Duration::from_millis(0),
+            redaction: None,
         }
     }
 }
@@ -374,8 +754,10 @@ impl MockRestAdapter {
     }
 
     pub fn with_behavior_plan(behavior_plan: MockBehaviorPlan) -> Self {
-        let mut state = MockRestAdapterState::default();
-        state.behavior_plan = behavior_plan;
+        let state = MockRestAdapterState {
+            behavior_plan,
+            ..MockRestAdapterState::default()
+        };
         Self {
             state: Arc::new(Mutex::new(state)),
         }
@@ -385,6 +767,16 @@ impl MockRestAdapter {
         Self::with_behavior_plan(MockBehaviorPlan::scenario(scenario))
     }
 
+    /// Redact requests before they enter the outbound log, so expectations, order checks and
+    /// debug output never hold credentials.
+    pub fn with_redaction(self, rules: RestRedactionRules) -> Self {
+        self.state
+            .lock()
+            .expect("mock-restapi mutex poisoned while setting redaction")
+            .redaction = Some(rules);
+        self
+    }
+
     pub fn snapshot(&self) -> MockRestStateSnapshot {
         self.state
             .lock()
@@ -392,6 +784,22 @@ impl MockRestAdapter {
             .snapshot()
     }
 
+    pub fn push_behavior(&self, behavior: MockBehavior) {
+        self.state
+            .lock()
+            .expect("mock-restapi mutex poisoned while pushing behavior")
+            .behavior_plan
+            .push(behavior);
+    }
+
+    pub fn push_behavior_for(&self, route: MockRoute, behavior: MockBehavior) {
+        self.state
+            .lock()
+            .expect("mock-restapi mutex poisoned while pushing route behavior")
+            .behavior_plan
+            .push_for(route, behavior);
+    }
+
     pub fn queue_response(&self, response: MockResponse) {
         self.state
             .lock()
@@ -416,6 +824,16 @@ impl MockRestAdapter {
             .push_back(response);
     }
 
+    /// Queue a fixture's response for the fixture's own request method and URL.
+    pub fn queue_fixture(&self, fixture: &RestFixture) -> RestResult<()> {
+        self.queue_response_for(
+            fixture.method()?,
+            fixture.request.url.clone(),
+            fixture.to_mock_response()?,
+        );
+        Ok(())
+    }
+
     pub fn queue_post_response(&self, url: impl Into<String>, response: MockResponse) {
         self.queue_response_for(Method::POST, url, response);
     }
@@ -479,6 +897,43 @@ impl MockRestAdapter {
             .len()
     }
 
+    /// Requests recorded so far, in the order the transport received them.
+    pub fn outbound_requests(&self) -> Vec<RestRequest> {
+        self.state
+            .lock()
+            .expect("mock-restapi mutex poisoned while reading outbound log")
+            .outbound_log
+            .clone()
+    }
+
+    /// Responses delivered so far, in the order the transport produced them.
+    pub fn inbound_responses(&self) -> Vec<RestResponse> {
+        self.state
+            .lock()
+            .expect("mock-restapi mutex poisoned while reading inbound log")
+            .inbound_log
+            .clone()
+    }
+
+    /// Start an expectation over the outbound log for requests matching `route`.
+    pub fn expect(&self, route: MockRoute) -> MockExpectation {
+        MockExpectation::new(self.outbound_requests(), route)
+    }
+
+    /// Check that each route was requested in the given relative order.
+    pub fn verify_order(
+        &self,
+        routes: impl IntoIterator<Item = MockRoute>,
+    ) -> Result<(), MockExpectationError> {
+        mock_expect::verify_order(&self.outbound_requests(), routes)
+    }
+
+    pub fn assert_order(&self, routes: impl IntoIterator<Item = MockRoute>) {
+        if let Err(err) = self.verify_order(routes) {
+            panic!("{err}");
+        }
+    }
+
     pub fn clear_logs(&self) {
         let mut state = self
             .state
@@ -488,39 +943,12 @@ impl MockRestAdapter {
         state.inbound_log.clear();
     }
 
-    fn pop_behavior(&self, operation: MockOperation) -> MockBehavior {
-        let (behavior, step) = {
-            let mut state = self
-                .state
-                .lock()
-                .expect("mock-restapi mutex poisoned while reading behavior plan");
-            let behavior = state.behavior_plan.pop(operation);
-            let step = state.behavior_plan.scenario.pop_front();
-            (behavior, step)
-        };
-
-        if let Some(step) = step {
-            match step.kind {
-                MockScenarioStepKind::Drop => return MockBehavior::Drop,
-                MockScenarioStepKind::Pass => {}
-                MockScenarioStepKind::Delay => {
-                    if let Some(delay) = step.delay {
-                        return MockBehavior::Delay(delay);
-                    }
-                }
-                MockScenarioStepKind::Reject => {
-                    return MockBehavior::Reject {
-                        status: step.status.unwrap_or(500),
-                        reason: step
-                            .message
-                            .clone()
-                            .unwrap_or_else(|| "rejected".to_string()),
-                    };
-                }
-                MockScenarioStepKind::Replay => {}
-            }
-        }
-        behavior
+    fn pop_behavior(&self, operation: MockOperation, request: &RestRequest) -> MockBehavior {
+        self.state
+            .lock()
+            .expect("mock-restapi mutex poisoned while reading behavior plan")
+            .behavior_plan
+            .pop_for(operation, request)
     }
 
     fn apply_delay(behavior: &MockBehavior) {
@@ -535,28 +963,189 @@ impl MockRestAdapter {
             .lock()
             .expect("mock-restapi mutex poisoned while selecting default response");
         let route_key = (request.method.clone(), request.url.clone());
-        if let Some(queue) = state.route_response_queues.get_mut(&route_key) {
-            if let Some(response) = queue.pop_front() {
-                return Some(response);
-            }
+        if let Some(queue) = state.route_response_queues.get_mut(&route_key)
+            && let Some(response) = queue.pop_front()
+        {
+            return Some(response);
         }
         state.default_response_queue.pop_front()
     }
 
-    fn push_inbound_log(&self, response: RestResponse) {
-        let mut state = self
-            .state
-            .lock()
-            .expect("mock-restapi mutex poisoned while pushing inbound log");
-        state.inbound_log.push(response);
+    /// Buffered dispatch, enforcing the request's `max_body_bytes`.
+    fn dispatch(&self, request: RestRequest) -> RestResult<RestResponse> {
+        let max_body_bytes = request.max_body_bytes;
+        let (response, _chunks) = self.dispatch_chunked(request)?;
+        if let Some(limit) = max_body_bytes
+            && response.body.len() as u64 > limit
+        {
+            return Err(RestError::body_too_large(
+                limit,
+                response.body.len() as u64,
+                Some(response.status),
+            ));
+        }
+        Ok(response)
     }
 
-    fn push_outbound_log(&self, request: RestRequest) {
+    /// Single request pipeline behind `execute`, `execute_raw` and `execute_stream`, so behavior
+    /// plans, logs, snapshot counters and headers do not depend on which `Client` method ran.
+    fn dispatch_chunked(&self, request: RestRequest) -> RestResult<(RestResponse, Vec<MockChunk>)> {
+        let behavior = self.pop_behavior(MockOperation::Request, &request);
+        Self::apply_delay(&behavior);
+
+        let start = Instant::now();
+        let max_body_bytes = request.max_body_bytes;
+        let sent = request.clone().encode_body()?;
+        {
+            let mut state = self
+                .state
+                .lock()
+                .expect("mock-restapi mutex poisoned while recording outbound request");
+            // Redact the plain body, then compress it the way it was sent. Encoding already
+            // succeeded for `sent`; if it fails here, nothing is logged rather than `sent`.
+            let logged = match &state.redaction {
+                Some(rules) => rules.redact_request(&request).encode_body()?,
+                None => sent,
+            };
+            state.request_count += 1;
+            state.last_url = Some(request.url.clone());
+            state.state = RestTransportState::Busy;
+            state.last_error = None;
+            state.outbound_log.push(logged);
+        }
+
+        let response = match behavior {
+            MockBehavior::Drop => {
+                return Err(self.error(
+                    RestErrorKind::Timeout,
+                    None,
+                    "mock transport dropped response",
+                    false,
+                ));
+            }
+            MockBehavior::ConnectError {
+                status,
+                reason,
+                retryable,
+            } => return Err(self.error(RestErrorKind::Connect, status, reason, retryable)),
+            MockBehavior::SendError {
+                status,
+                reason,
+                retryable,
+            } => return Err(self.error(RestErrorKind::Send, status, reason, retryable)),
+            MockBehavior::ReceiveError {
+                status,
+                reason,
+                retryable,
+            } => return Err(self.error(RestErrorKind::Receive, status, reason, retryable)),
+            MockBehavior::TimeoutError {
+                status,
+                reason,
+                retryable,
+            } => return Err(self.error(RestErrorKind::Timeout, status, reason, retryable)),
+            MockBehavior::InternalError { reason } => {
+                return Err(self.error(RestErrorKind::Internal, None, reason, false));
+            }
+            MockBehavior::Reject { status, reason } => {
+                return Err(self.error(RestErrorKind::Rejected, Some(status), reason, true));
+            }
+            // The plan hands out one frame per request; an empty replay falls back to the queues.
+            MockBehavior::Replay(frames) => frames
+                .into_iter()
+                .next()
+                .or_else(|| self.next_default_response(&request)),
+            MockBehavior::Delay(_) | MockBehavior::Pass => self.next_default_response(&request),
+        }
+        .unwrap_or_else(|| MockResponse::new(200, Bytes::new()));
+
+        let MockResponse {
+            status,
+            mut headers,
+            mut body,
+            mut chunks,
+        } = response;
+        let mut encoded_size = None;
+        if let Some(decoder) = RestBodyDecoder::for_headers(&headers) {
+            strip_encoding_headers(&mut headers);
+            encoded_size = Some(body.len() as u64);
+            (body, chunks) = self.decode_body(decoder, status, max_body_bytes, &body, chunks)?;
+        }
+        let response = RestResponse {
+            encoded_size,
+            ..RestResponse::new(status, headers, body, start.elapsed())
+        };
         let mut state = self
             .state
             .lock()
-            .expect("mock-restapi mutex poisoned while pushing outbound log");
-        state.outbound_log.push(request);
+            .expect("mock-restapi mutex poisoned while recording inbound response");
+        state.last_status = Some(response.status);
+        state.state = RestTransportState::Idle;
+        state.elapsed_total += response.elapsed;
+        state.inbound_log.push(response.clone());
+        Ok((response, chunks))
+    }
+
+    /// Decode a content-encoded mock body, and its scripted chunks one by one so streamed reads
+    /// see the decoder's incremental output with the original delays. Decoding stops once the
+    /// output passes `max_body_bytes`, as it does for a real transport.
+    fn decode_body(
+        &self,
+        decoder: RestBodyDecoder,
+        status: u16,
+        max_body_bytes: Option<u64>,
+        body: &RestBytes,
+        chunks: Vec<MockChunk>,
+    ) -> RestResult<(RestBytes, Vec<MockChunk>)> {
+        let encoding = decoder.encoding();
+        let full = RestBodyDecoder::new(encoding)
+            .expect("decoder exists for an encoding that already has one")
+            .with_limit(max_body_bytes);
+        let decoded = full.decode_all(body).and_then(|decoded| {
+            if chunks.is_empty() {
+                return Ok((decoded, chunks));
+            }
+            let mut decoder = decoder.with_limit(max_body_bytes);
+            let mut decoded_chunks = Vec::with_capacity(chunks.len());
+            let mut pending_delay = Duration::ZERO;
+            for chunk in chunks {
+                pending_delay += chunk.delay;
+                let bytes = decoder.write(&chunk.bytes)?;
+                if !bytes.is_empty() {
+                    decoded_chunks.push(MockChunk::new(bytes).with_delay(pending_delay));
+                    pending_delay = Duration::ZERO;
+                }
+            }
+            let tail = decoder.finish()?;
+            if !tail.is_empty() {
+                decoded_chunks.push(MockChunk::new(tail).with_delay(pending_delay));
+            }
+            Ok((decoded, decoded_chunks))
+        });
+        decoded.map_err(|err| {
+            let message = err.to_string();
+            let mut state = self
+                .state
+                .lock()
+                .expect("mock-restapi mutex poisoned while recording decode error");
+            state.state = RestTransportState::Error;
+            state.last_error = Some(message.clone());
+            state.last_status = Some(status);
+            match err {
+                RestError::BodyTooLarge {
+                    limit, received, ..
+                } => RestError::body_too_large(limit, received, Some(status)),
+                RestError::Decode { message, .. } => RestError::Decode {
+                    encoding,
+                    status: Some(status),
+                    message,
+                },
+                _ => RestError::Decode {
+                    encoding,
+                    status: Some(status),
+                    message,
+                },
+            }
+        })
     }
 
     fn error(
@@ -580,6 +1169,9 @@ impl MockRestAdapter {
             RestErrorKind::Receive => RestError::receive(message.clone(), status, retryable),
             RestErrorKind::Internal => RestError::internal(message.clone()),
             RestErrorKind::Parse => RestError::internal(format!("mock parse error: {message}")),
+            RestErrorKind::BodyTooLarge | RestErrorKind::QueueTimeout => {
+                RestError::mock(kind, message.clone(), status, retryable)
+            }
         };
 
         let mut state = self
@@ -603,246 +1195,38 @@ impl RestTransport for MockRestAdapter {
     fn execute_raw(&self, request: RestRequest) -> RestFuture<RestResult<RestRawResponse>> {
         let adapter = self.clone();
         Box::pin(async move {
-            let behavior = adapter.pop_behavior(MockOperation::Request);
-            Self::apply_delay(&behavior);
-
-            let start = Instant::now();
-            adapter.push_outbound_log(request.clone());
-
-            let mut state = adapter
-                .state
-                .lock()
-                .expect("mock-restapi mutex poisoned while updating state before execute_raw");
-            state.request_count += 1;
-            state.last_url = Some(request.url.clone());
-            state.state = RestTransportState::Busy;
-            state.last_error = None;
-            drop(state);
-
-            match behavior {
-                MockBehavior::Drop => {
-                    let error = adapter.error(
-                        RestErrorKind::Timeout,
-                        None,
-                        "mock transport dropped response",
-                        false,
-                    );
-                    return Err(error);
-                }
-                MockBehavior::ConnectError {
-                    status,
-                    reason,
-                    retryable,
-                } => {
-                    return Err(adapter.error(RestErrorKind::Connect, status, reason, retryable));
-                }
-                MockBehavior::SendError {
-                    status,
-                    reason,
-                    retryable,
-                } => {
-                    return Err(adapter.error(RestErrorKind::Send, status, reason, retryable));
-                }
-                MockBehavior::ReceiveError {
-                    status,
-                    reason,
-                    retryable,
-                } => {
-                    return Err(adapter.error(RestErrorKind::Receive, status, reason, retryable));
-                }
-                MockBehavior::TimeoutError {
-                    status,
-                    reason,
-                    retryable,
-                } => {
-                    return Err(adapter.error(RestErrorKind::Timeout, status, reason, retryable));
-                }
-                MockBehavior::InternalError { reason } => {
-                    return Err(adapter.error(RestErrorKind::Internal, None, reason, false));
-                }
-                MockBehavior::Reject { status, reason } => {
-                    return Err(adapter.error(RestErrorKind::Rejected, Some(status), reason, true));
-                }
-                MockBehavior::Delay(_) | MockBehavior::Pass | MockBehavior::Replay(_) => {}
-            }
-
-            let maybe_response = if let MockBehavior::Replay(list) = behavior {
-                let mut state = adapter
-                    .state
-                    .lock()
-                    .expect("mock-restapi mutex poisoned while enqueueing replay responses");
-                state.default_response_queue.extend(list.into_iter());
-                drop(state);
-                adapter.next_default_response(&request)
-            } else {
-                adapter.next_default_response(&request)
-            };
-
-            let (status, body, elapsed) = match maybe_response {
-                Some(response) => {
-                    let elapsed = start.elapsed();
-                    let status = response.status;
-                    let body = response.body;
-
-                    adapter.push_inbound_log(RestResponse {
-                        status,
-                        headers: Vec::new(),
-                        body: body.clone(),
-                        elapsed,
-                    });
-                    {
-                        let mut state = adapter.state.lock().expect(
-                            "mock-restapi mutex poisoned while recording inbound raw response",
-                        );
-                        state.last_status = Some(status);
-                        state.state = RestTransportState::Idle;
-                        state.elapsed_total += elapsed;
-                    }
-                    (status, body, elapsed)
-                }
-                None => {
-                    let elapsed = start.elapsed();
-                    let body = Bytes::new();
-                    adapter.push_inbound_log(RestResponse {
-                        status: 200,
-                        headers: Vec::new(),
-                        body: body.clone(),
-                        elapsed,
-                    });
-                    {
-                        let mut state = adapter.state.lock().expect(
-                            "mock-restapi mutex poisoned while recording fallback raw response",
-                        );
-                        state.last_status = Some(200);
-                        state.state = RestTransportState::Idle;
-                    }
-                    (200, body, elapsed)
-                }
-            };
-
-            Ok((status, body, elapsed))
+            let response = adapter.dispatch(request)?;
+            Ok((response.status, response.body, response.elapsed))
         })
     }
 
     fn execute(&self, request: RestRequest) -> RestFuture<RestResult<RestResponse>> {
         let adapter = self.clone();
-        Box::pin(async move {
-            let behavior = adapter.pop_behavior(MockOperation::Request);
-            Self::apply_delay(&behavior);
-
-            let start = Instant::now();
-            adapter.push_outbound_log(request.clone());
-
-            let mut state = adapter
-                .state
-                .lock()
-                .expect("mock-restapi mutex poisoned while updating state before execute");
-            state.request_count += 1;
-            state.last_url = Some(request.url.clone());
-            state.state = RestTransportState::Busy;
-            state.last_error = None;
-            drop(state);
-
-            match behavior {
-                MockBehavior::Drop => {
-                    let error = adapter.error(
-                        RestErrorKind::Timeout,
-                        None,
-                        "mock transport dropped response",
-                        false,
-                    );
-                    return Err(error);
-                }
-                MockBehavior::ConnectError {
-                    status,
-                    reason,
-                    retryable,
-                } => {
-                    return Err(adapter.error(RestErrorKind::Connect, status, reason, retryable));
-                }
-                MockBehavior::SendError {
-                    status,
-                    reason,
-                    retryable,
-                } => {
-                    return Err(adapter.error(RestErrorKind::Send, status, reason, retryable));
-                }
-                MockBehavior::ReceiveError {
-                    status,
-                    reason,
-                    retryable,
-                } => {
-                    return Err(adapter.error(RestErrorKind::Receive, status, reason, retryable));
-                }
-                MockBehavior::TimeoutError {
-                    status,
-                    reason,
-                    retryable,
-                } => {
-                    return Err(adapter.error(RestErrorKind::Timeout, status, reason, retryable));
-                }
-                MockBehavior::InternalError { reason } => {
-                    return Err(adapter.error(RestErrorKind::Internal, None, reason, false));
-                }
-                MockBehavior::Reject { status, reason } => {
-                    return Err(adapter.error(RestErrorKind::Rejected, Some(status), reason, true));
-                }
-                MockBehavior::Delay(_) | MockBehavior::Pass | MockBehavior::Replay(_) => {}
-            }
+        Box::pin(async move { adapter.dispatch(request) })
+    }
 
-            let maybe_response = if let MockBehavior::Replay(list) = behavior {
-                let mut state = adapter
-                    .state
-                    .lock()
-                    .expect("mock-restapi mutex poisoned while enqueueing replay responses");
-                state.default_response_queue.extend(list.into_iter());
-                drop(state);
-                adapter.next_default_response(&request)
+    fn execute_stream(
+        &self,
+        request: RestRequest,
+    ) -> RestFuture<RestResult<RestStreamingResponse>> {
+        let adapter = self.clone();
+        Box::pin(async move {
+            let max_body_bytes = request.max_body_bytes;
+            let (response, chunks) = adapter.dispatch_chunked(request)?;
+            let body = if chunks.is_empty() {
+                RestBodyStream::from_bytes(response.body)
             } else {
-                adapter.next_default_response(&request)
+                RestBodyStream::scripted(chunks.into_iter().map(|chunk| (chunk.delay, chunk.bytes)))
             };
-
-            let response = match maybe_response {
-                Some(response) => {
-                    let elapsed = start.elapsed();
-                    let response = RestResponse {
-                        status: response.status,
-                        headers: response.headers,
-                        body: response.body,
-                        elapsed,
-                    };
-                    adapter.push_inbound_log(response.clone());
-                    {
-                        let mut state = adapter
-                            .state
-                            .lock()
-                            .expect("mock-restapi mutex poisoned while recording inbound response");
-                        state.last_status = Some(response.status);
-                        state.state = RestTransportState::Idle;
-                        state.elapsed_total += elapsed;
-                    }
-                    Ok(response)
-                }
-                None => {
-                    let fallback = RestResponse {
-                        status: 200,
-                        headers: Vec::new(),
-                        body: Bytes::new(),
-                        elapsed: start.elapsed(),
-                    };
-                    adapter.push_inbound_log(fallback.clone());
-                    {
-                        let mut state = adapter.state.lock().expect(
-                            "mock-restapi mutex poisoned while recording fallback response",
-                        );
-                        state.last_status = Some(200);
-                        state.state = RestTransportState::Idle;
-                    }
-                    Ok(fallback)
-                }
-            };
-
-            response
+            Ok(RestStreamingResponse {
+                status: response.status,
+                headers: response.headers,
+                body: body
+                    .with_status(response.status)
+                    .with_max_body_bytes(max_body_bytes),
+                elapsed: response.elapsed,
+                queue_wait: Duration::ZERO,
+            })
         })
     }
 }
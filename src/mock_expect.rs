@@ -0,0 +1,361 @@
+//! Assertions over the requests recorded by [`MockRestAdapter`](crate::MockRestAdapter).
+
+use std::fmt::Write as _;
+
+use sonic_rs::{JsonContainerTrait, JsonType, JsonValueTrait, Value};
+use thiserror::Error;
+
+use crate::adapter::{RestBytes, RestRequest};
+use crate::mock::MockRoute;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{message}")]
+pub struct MockExpectationError {
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MockCallCount {
+    Exactly(usize),
+    AtLeast(usize),
+    AtMost(usize),
+}
+
+impl MockCallCount {
+    fn accepts(self, count: usize) -> bool {
+        match self {
+            Self::Exactly(expected) => count == expected,
+            Self::AtLeast(min) => count >= min,
+            Self::AtMost(max) => count <= max,
+        }
+    }
+}
+
+impl std::fmt::Display for MockCallCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exactly(count) => write!(f, "exactly {count}"),
+            Self::AtLeast(count) => write!(f, "at least {count}"),
+            Self::AtMost(count) => write!(f, "at most {count}"),
+        }
+    }
+}
+
+/// Fluent expectation built by [`MockRestAdapter::expect`](crate::MockRestAdapter::expect).
+///
+/// The outbound log is captured when the expectation is created; call [`verify`](Self::verify)
+/// or [`assert`](Self::assert) once the filters are configured.
+#[derive(Clone, Debug)]
+pub struct MockExpectation {
+    log: Vec<RestRequest>,
+    route: MockRoute,
+    count: MockCallCount,
+    headers: Vec<(String, RestBytes)>,
+    body: Option<RestBytes>,
+    json_body: Option<Value>,
+}
+
+impl MockExpectation {
+    pub(crate) fn new(log: Vec<RestRequest>, route: MockRoute) -> Self {
+        Self {
+            log,
+            route,
+            count: MockCallCount::AtLeast(1),
+            headers: Vec::new(),
+            body: None,
+            json_body: None,
+        }
+    }
+
+    pub fn times(mut self, count: usize) -> Self {
+        self.count = MockCallCount::Exactly(count);
+        self
+    }
+
+    pub fn once(self) -> Self {
+        self.times(1)
+    }
+
+    pub fn never(self) -> Self {
+        self.times(0)
+    }
+
+    pub fn at_least(mut self, count: usize) -> Self {
+        self.count = MockCallCount::AtLeast(count);
+        self
+    }
+
+    pub fn at_most(mut self, count: usize) -> Self {
+        self.count = MockCallCount::AtMost(count);
+        self
+    }
+
+    /// Require a header with this exact value. Header names compare case-insensitively.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<RestBytes>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Require the (decoded) request body to equal these bytes exactly.
+    pub fn with_body(mut self, body: impl Into<RestBytes>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Require the request body to be JSON containing `expected` as a partial document:
+    /// every object key in `expected` must be present with a matching value, extra keys in
+    /// the request are ignored, and arrays must match element by element.
+    pub fn with_json_body(mut self, expected: Value) -> Self {
+        self.json_body = Some(expected);
+        self
+    }
+
+    /// Recorded requests that satisfy the route and every configured filter.
+    pub fn matched(&self) -> Vec<&RestRequest> {
+        self.log
+            .iter()
+            .filter(|request| self.route.matches(request) && self.mismatch(request).is_none())
+            .collect()
+    }
+
+    pub fn verify(&self) -> Result<(), MockExpectationError> {
+        let matched = self.matched().len();
+        if self.count.accepts(matched) {
+            return Ok(());
+        }
+
+        let mut message = format!(
+            "mock expectation failed: expected {} to be called {} time(s)",
+            self.route, self.count
+        );
+        for (name, value) in &self.headers {
+            let _ = write!(
+                message,
+                " with header `{name}: {}`",
+                String::from_utf8_lossy(value)
+            );
+        }
+        if self.body.is_some() {
+            message.push_str(" with exact body");
+        }
+        if let Some(expected) = &self.json_body {
+            let _ = write!(message, " with json body containing {expected}");
+        }
+        let _ = write!(message, ", matched {matched}");
+        write_log(&mut message, &self.log, |_, request| {
+            if !self.route.matches(request) {
+                return LogMark::Other;
+            }
+            match self.mismatch(request) {
+                None => LogMark::Matched,
+                Some(reason) => LogMark::Rejected(reason),
+            }
+        });
+        Err(MockExpectationError { message })
+    }
+
+    pub fn assert(self) {
+        if let Err(err) = self.verify() {
+            panic!("{err}");
+        }
+    }
+
+    fn mismatch(&self, request: &RestRequest) -> Option<String> {
+        for (name, expected) in &self.headers {
+            let found = request
+                .headers
+                .iter()
+                .filter(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value)
+                .collect::<Vec<_>>();
+            if !found.contains(&expected) {
+                let found = match found.as_slice() {
+                    [] => "none".to_string(),
+                    values => values
+                        .iter()
+                        .map(|value| format!("{:?}", String::from_utf8_lossy(value)))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                };
+                return Some(format!(
+                    "header `{name}` expected {:?}, found {found}",
+                    String::from_utf8_lossy(expected)
+                ));
+            }
+        }
+
+        // Bodies are compared after undoing any `Content-Encoding`.
+        let body = if self.body.is_some() || self.json_body.is_some() {
+            match request.decoded_body() {
+                Ok(body) => body,
+                Err(err) => return Some(format!("body could not be decoded: {err}")),
+            }
+        } else {
+            None
+        };
+
+        if let Some(expected) = &self.body {
+            let actual = body.as_deref().unwrap_or_default();
+            if actual != expected.as_ref() {
+                return Some(format!(
+                    "body expected {:?}, found {:?}",
+                    String::from_utf8_lossy(expected),
+                    String::from_utf8_lossy(actual)
+                ));
+            }
+        }
+
+        if let Some(expected) = &self.json_body {
+            let Some(body) = body.as_deref() else {
+                return Some("json body expected, request has no body".to_string());
+            };
+            let actual = match sonic_rs::from_slice::<Value>(body) {
+                Ok(actual) => actual,
+                Err(err) => return Some(format!("json body expected, parse failed: {err}")),
+            };
+            if let Err(reason) = json_contains(expected, &actual, &mut String::new()) {
+                return Some(reason);
+            }
+        }
+
+        None
+    }
+}
+
+enum LogMark {
+    Matched,
+    Rejected(String),
+    Other,
+}
+
+fn write_log(
+    message: &mut String,
+    log: &[RestRequest],
+    mut mark: impl FnMut(usize, &RestRequest) -> LogMark,
+) {
+    let _ = write!(message, "\nrecorded outbound requests ({}):", log.len());
+    for (index, request) in log.iter().enumerate() {
+        let _ = match mark(index, request) {
+            LogMark::Matched => write!(
+                message,
+                "\n  + [{index}] {} {}",
+                request.method, request.url
+            ),
+            LogMark::Rejected(reason) => write!(
+                message,
+                "\n  - [{index}] {} {}: {reason}",
+                request.method, request.url
+            ),
+            LogMark::Other => write!(
+                message,
+                "\n    [{index}] {} {}",
+                request.method, request.url
+            ),
+        };
+    }
+}
+
+fn json_contains(expected: &Value, actual: &Value, path: &mut String) -> Result<(), String> {
+    let location = |path: &str| if path.is_empty() { "/" } else { path }.to_string();
+    match expected.get_type() {
+        JsonType::Object => {
+            let (Some(expected), Some(actual)) = (expected.as_object(), actual.as_object()) else {
+                return Err(format!(
+                    "json body at {} expected an object, found {actual}",
+                    location(path)
+                ));
+            };
+            for (key, expected_value) in expected.iter() {
+                let len = path.len();
+                path.push('/');
+                path.push_str(key);
+                let result = match actual.get(&key) {
+                    Some(actual_value) => json_contains(expected_value, actual_value, path),
+                    None => Err(format!("json body missing {}", location(path))),
+                };
+                path.truncate(len);
+                result?;
+            }
+            Ok(())
+        }
+        JsonType::Array => {
+            let (Some(expected), Some(actual)) = (expected.as_array(), actual.as_array()) else {
+                return Err(format!(
+                    "json body at {} expected an array, found {actual}",
+                    location(path)
+                ));
+            };
+            if expected.len() != actual.len() {
+                return Err(format!(
+                    "json body at {} expected {} element(s), found {}",
+                    location(path),
+                    expected.len(),
+                    actual.len()
+                ));
+            }
+            for (index, (expected_value, actual_value)) in
+                expected.iter().zip(actual.iter()).enumerate()
+            {
+                let len = path.len();
+                let _ = write!(path, "/{index}");
+                let result = json_contains(expected_value, actual_value, path);
+                path.truncate(len);
+                result?;
+            }
+            Ok(())
+        }
+        _ if expected == actual => Ok(()),
+        _ => Err(format!(
+            "json body at {} expected {expected}, found {actual}",
+            location(path)
+        )),
+    }
+}
+
+pub(crate) fn verify_order(
+    log: &[RestRequest],
+    routes: impl IntoIterator<Item = MockRoute>,
+) -> Result<(), MockExpectationError> {
+    let routes = routes.into_iter().collect::<Vec<_>>();
+    let mut positions = Vec::with_capacity(routes.len());
+    let mut next = 0usize;
+    for route in &routes {
+        match log[next..]
+            .iter()
+            .position(|request| route.matches(request))
+        {
+            Some(offset) => {
+                positions.push(next + offset);
+                next += offset + 1;
+            }
+            None => {
+                let mut message = format!(
+                    "mock order expectation failed: expected {}",
+                    routes
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" -> ")
+                );
+                let _ = write!(message, ", no request matching {route} after");
+                match positions.last() {
+                    Some(position) => {
+                        let _ = write!(message, " [{position}]");
+                    }
+                    None => message.push_str(" start of log"),
+                }
+                write_log(&mut message, log, |index, request| {
+                    if positions.contains(&index) {
+                        LogMark::Matched
+                    } else if route.matches(request) {
+                        LogMark::Rejected("matches but out of order".to_string())
+                    } else {
+                        LogMark::Other
+                    }
+                });
+                return Err(MockExpectationError { message });
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,106 @@
+//! Incremental NDJSON / JSON-lines decoding over a [`RestBodyStream`].
+//!
+//! Only the current line is buffered. Blank lines are skipped, `\r\n` endings and a final line
+//! without a newline are accepted, and parse failures carry the 1-based line number and the byte
+//! offset of the line's start within the body.
+
+use std::marker::PhantomData;
+
+use bytes::BytesMut;
+use sonic_rs::Deserialize;
+
+use crate::adapter::{RestError, RestResult};
+use crate::stream::RestBodyStream;
+
+pub struct RestNdjsonStream<T> {
+    body: RestBodyStream,
+    buffer: BytesMut,
+    /// Prefix of `buffer` already searched for a newline.
+    scanned: usize,
+    lines: u64,
+    /// Body offset of `buffer[0]`.
+    offset: u64,
+    eof: bool,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T> RestNdjsonStream<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    pub fn new(body: RestBodyStream) -> Self {
+        Self {
+            body,
+            buffer: BytesMut::new(),
+            scanned: 0,
+            lines: 0,
+            offset: 0,
+            eof: false,
+            _item: PhantomData,
+        }
+    }
+
+    /// Lines consumed so far, including blank ones.
+    pub fn lines_read(&self) -> u64 {
+        self.lines
+    }
+
+    /// The next decoded item, or `None` once the body is exhausted.
+    pub async fn next_item(&mut self) -> RestResult<Option<T>> {
+        loop {
+            if let Some(position) = self.buffer[self.scanned..]
+                .iter()
+                .position(|byte| *byte == b'\n')
+            {
+                let end = self.scanned + position;
+                let line = self.buffer.split_to(end + 1);
+                self.scanned = 0;
+                if let Some(item) = self.take_line(&line[..end], line.len())? {
+                    return Ok(Some(item));
+                }
+                continue;
+            }
+            self.scanned = self.buffer.len();
+
+            if self.eof {
+                if self.buffer.is_empty() {
+                    return Ok(None);
+                }
+                let line = self.buffer.split();
+                self.scanned = 0;
+                if let Some(item) = self.take_line(&line, line.len())? {
+                    return Ok(Some(item));
+                }
+                continue;
+            }
+            match self.body.next_chunk().await? {
+                Some(chunk) => self.buffer.extend_from_slice(&chunk),
+                None => self.eof = true,
+            }
+        }
+    }
+
+    /// Decode every remaining item.
+    pub async fn collect_items(mut self) -> RestResult<Vec<T>> {
+        let mut items = Vec::new();
+        while let Some(item) = self.next_item().await? {
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    /// `consumed` includes the line's newline, when it had one.
+    fn take_line(&mut self, line: &[u8], consumed: usize) -> RestResult<Option<T>> {
+        let line_number = self.lines + 1;
+        let offset = self.offset;
+        self.lines += 1;
+        self.offset += consumed as u64;
+        let content = line.strip_suffix(b"\r").unwrap_or(line);
+        if content.iter().all(u8::is_ascii_whitespace) {
+            return Ok(None);
+        }
+        sonic_rs::from_slice(content)
+            .map(Some)
+            .map_err(|source| RestError::ndjson_parse(line_number, offset, source))
+    }
+}
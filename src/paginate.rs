@@ -0,0 +1,507 @@
+//! Pagination over [`Client`] for cursor, offset/limit and `Link`-header APIs.
+//!
+//! A [`RestPaginator`] sends the first request as given and derives each following request from
+//! the previous response with a [`RestPageStrategy`], cloning the request so headers, retries and
+//! fixture contracts carry over. Pages are fetched with `get_checked_response`, so a non-2xx page
+//! ends iteration with the rejection error. Nothing is fetched until the first `next_*` call.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use reqwest::Url;
+use sonic_rs::{Deserialize, JsonValueTrait, LazyValue, PointerNode};
+
+use crate::adapter::{Client, RestError, RestRequest, RestResponse, RestResult};
+
+type RestNextPageFn =
+    dyn Fn(&RestRequest, &RestResponse) -> RestResult<Option<RestRequest>> + Send + Sync;
+
+/// How the request for the next page is derived from the current page.
+#[derive(Clone)]
+pub enum RestPageStrategy {
+    /// Read the next cursor at JSON pointer `pointer` and send it as query parameter `param`.
+    /// A missing, `null` or empty cursor ends iteration; a cursor equal to the one just sent is
+    /// an error rather than a loop over the same page.
+    Cursor { pointer: String, param: String },
+    /// Advance query parameter `offset_param` by `page_size`, sending `limit_param=page_size`
+    /// on every page. A page with fewer than `page_size` items at `items_pointer` is the last.
+    /// A `page_size` of zero fails on the first request.
+    Offset {
+        offset_param: String,
+        limit_param: String,
+        page_size: u64,
+        items_pointer: String,
+    },
+    /// Follow `Link: <url>; rel="next"`, resolving relative URLs against the current request.
+    LinkHeader,
+    /// Build the next request from the current request and response; `None` ends iteration.
+    Custom(Arc<RestNextPageFn>),
+}
+
+impl fmt::Debug for RestPageStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cursor { pointer, param } => f
+                .debug_struct("Cursor")
+                .field("pointer", pointer)
+                .field("param", param)
+                .finish(),
+            Self::Offset {
+                offset_param,
+                limit_param,
+                page_size,
+                items_pointer,
+            } => f
+                .debug_struct("Offset")
+                .field("offset_param", offset_param)
+                .field("limit_param", limit_param)
+                .field("page_size", page_size)
+                .field("items_pointer", items_pointer)
+                .finish(),
+            Self::LinkHeader => f.write_str("LinkHeader"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl RestPageStrategy {
+    pub fn cursor(pointer: impl Into<String>, param: impl Into<String>) -> Self {
+        Self::Cursor {
+            pointer: pointer.into(),
+            param: param.into(),
+        }
+    }
+
+    pub fn offset(
+        offset_param: impl Into<String>,
+        limit_param: impl Into<String>,
+        page_size: u64,
+        items_pointer: impl Into<String>,
+    ) -> Self {
+        Self::Offset {
+            offset_param: offset_param.into(),
+            limit_param: limit_param.into(),
+            page_size,
+            items_pointer: items_pointer.into(),
+        }
+    }
+
+    pub fn link_header() -> Self {
+        Self::LinkHeader
+    }
+
+    pub fn custom<F>(next: F) -> Self
+    where
+        F: Fn(&RestRequest, &RestResponse) -> RestResult<Option<RestRequest>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self::Custom(Arc::new(next))
+    }
+
+    /// The first request, with offset parameters applied.
+    fn first(&self, request: RestRequest) -> RestResult<RestRequest> {
+        match self {
+            Self::Offset {
+                offset_param,
+                limit_param,
+                page_size,
+                ..
+            } => {
+                if *page_size == 0 {
+                    return Err(RestError::internal(
+                        "offset pagination needs a page_size above zero",
+                    ));
+                }
+                let offset = query_param(&request.url, offset_param)?
+                    .map(|value| parse_offset(offset_param, &value))
+                    .transpose()?
+                    .unwrap_or(0);
+                with_query_params(
+                    request,
+                    &[
+                        (offset_param, &offset.to_string()),
+                        (limit_param, &page_size.to_string()),
+                    ],
+                )
+            }
+            _ => Ok(request),
+        }
+    }
+
+    fn next(
+        &self,
+        request: &RestRequest,
+        response: &RestResponse,
+    ) -> RestResult<Option<RestRequest>> {
+        match self {
+            Self::Cursor { pointer, param } => {
+                let Some(cursor) = lookup(response.body(), pointer)? else {
+                    return Ok(None);
+                };
+                let cursor = if cursor.is_null() {
+                    None
+                } else if let Some(text) = cursor.as_str() {
+                    Some(text.to_string())
+                } else if cursor.is_number() {
+                    Some(cursor.as_raw_str().to_string())
+                } else {
+                    return Err(RestError::internal(format!(
+                        "pagination cursor at {pointer} is not a string or number"
+                    )));
+                };
+                let Some(cursor) = cursor.filter(|cursor| !cursor.is_empty()) else {
+                    return Ok(None);
+                };
+                if query_param(&request.url, param)?.as_deref() == Some(cursor.as_str()) {
+                    return Err(RestError::internal(format!(
+                        "pagination cursor at {pointer} repeats the current cursor {cursor:?}"
+                    )));
+                }
+                with_query_params(request.clone(), &[(param, &cursor)]).map(Some)
+            }
+            Self::Offset {
+                offset_param,
+                page_size,
+                items_pointer,
+                ..
+            } => {
+                if item_count(response.body(), items_pointer)? < *page_size {
+                    return Ok(None);
+                }
+                let offset = query_param(&request.url, offset_param)?
+                    .map(|value| parse_offset(offset_param, &value))
+                    .transpose()?
+                    .unwrap_or(0);
+                let next = (offset + page_size).to_string();
+                with_query_params(request.clone(), &[(offset_param, &next)]).map(Some)
+            }
+            Self::LinkHeader => {
+                let Some(target) = response
+                    .header_all("link")
+                    .find_map(|value| next_link(value).map(str::to_string))
+                else {
+                    return Ok(None);
+                };
+                let url = parse_url(&request.url)?
+                    .join(&target)
+                    .map_err(|err| RestError::internal(format!("invalid next link: {err}")))?;
+                let mut next = request.clone();
+                next.url = url.to_string();
+                Ok(Some(next))
+            }
+            Self::Custom(next) => next(request, response),
+        }
+    }
+}
+
+/// Fetches pages lazily from a [`Client`]. Created with [`Client::paginate`].
+pub struct RestPaginator {
+    client: Client,
+    strategy: RestPageStrategy,
+    next_request: Option<RestRequest>,
+    started: bool,
+    max_pages: Option<u64>,
+    pages: u64,
+}
+
+impl RestPaginator {
+    pub fn new(client: Client, request: RestRequest, strategy: RestPageStrategy) -> Self {
+        Self {
+            client,
+            strategy,
+            next_request: Some(request),
+            started: false,
+            max_pages: None,
+            pages: 0,
+        }
+    }
+
+    /// Stop after `max_pages` pages.
+    pub fn with_max_pages(mut self, max_pages: u64) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    pub fn pages_fetched(&self) -> u64 {
+        self.pages
+    }
+
+    /// The next page as a response, or `None` once the strategy or `max_pages` ends iteration.
+    pub async fn next_response(&mut self) -> RestResult<Option<RestResponse>> {
+        if self.max_pages.is_some_and(|max| self.pages >= max) {
+            self.next_request = None;
+        }
+        let Some(request) = self.next_request.take() else {
+            return Ok(None);
+        };
+        let request = if self.started {
+            request
+        } else {
+            self.started = true;
+            self.strategy.first(request)?
+        };
+        let response = self.client.get_checked_response(request.clone()).await?;
+        self.pages += 1;
+        self.next_request = self.strategy.next(&request, &response)?;
+        Ok(Some(response))
+    }
+
+    /// The next page parsed as `P`.
+    pub async fn next_page<P>(&mut self) -> RestResult<Option<P>>
+    where
+        P: for<'de> Deserialize<'de>,
+    {
+        match self.next_response().await? {
+            Some(response) => response.json_owned().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Every remaining page parsed as `P`.
+    pub async fn collect_pages<P>(mut self) -> RestResult<Vec<P>>
+    where
+        P: for<'de> Deserialize<'de>,
+    {
+        let mut pages = Vec::new();
+        while let Some(page) = self.next_page().await? {
+            pages.push(page);
+        }
+        Ok(pages)
+    }
+
+    /// Items of type `T` from the array at JSON pointer `items_pointer` of every page.
+    pub fn items<T>(self, items_pointer: impl Into<String>) -> RestPageItems<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        RestPageItems {
+            pages: self,
+            items_pointer: items_pointer.into(),
+            buffered: VecDeque::new(),
+            max_items: None,
+            items: 0,
+            _item: PhantomData,
+        }
+    }
+}
+
+/// Items flattened across pages. Created with [`RestPaginator::items`].
+pub struct RestPageItems<T> {
+    pages: RestPaginator,
+    items_pointer: String,
+    buffered: VecDeque<T>,
+    max_items: Option<u64>,
+    items: u64,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T> RestPageItems<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    /// Stop after `max_items` items; no further page is fetched once the limit is reached.
+    pub fn with_max_items(mut self, max_items: u64) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    pub fn pages_fetched(&self) -> u64 {
+        self.pages.pages_fetched()
+    }
+
+    pub fn items_yielded(&self) -> u64 {
+        self.items
+    }
+
+    pub async fn next_item(&mut self) -> RestResult<Option<T>> {
+        if self.max_items.is_some_and(|max| self.items >= max) {
+            return Ok(None);
+        }
+        while self.buffered.is_empty() {
+            let Some(response) = self.pages.next_response().await? else {
+                return Ok(None);
+            };
+            let items = match lookup(response.body(), &self.items_pointer)? {
+                Some(items) => sonic_rs::from_str::<Vec<T>>(items.as_raw_str())?,
+                None => Vec::new(),
+            };
+            self.buffered.extend(items);
+        }
+        self.items += 1;
+        Ok(self.buffered.pop_front())
+    }
+
+    /// Every remaining item, up to `max_items`.
+    pub async fn collect_items(mut self) -> RestResult<Vec<T>> {
+        let mut items = Vec::new();
+        while let Some(item) = self.next_item().await? {
+            items.push(item);
+        }
+        Ok(items)
+    }
+}
+
+/// Value at RFC 6901 `pointer`; `None` when it is absent. Numeric tokens index arrays.
+fn lookup<'a>(body: &'a [u8], pointer: &str) -> RestResult<Option<LazyValue<'a>>> {
+    let path = pointer
+        .split('/')
+        .skip(1)
+        .map(|token| match token.parse::<usize>() {
+            Ok(index) => PointerNode::Index(index),
+            Err(_) => PointerNode::Key(token.replace("~1", "/").replace("~0", "~").into()),
+        })
+        .collect::<Vec<_>>();
+    match sonic_rs::get_from_slice(body, &path) {
+        Ok(value) => Ok(Some(value)),
+        Err(err) if err.is_not_found() => Ok(None),
+        Err(err) => Err(RestError::from(err)),
+    }
+}
+
+fn item_count(body: &[u8], items_pointer: &str) -> RestResult<u64> {
+    let Some(items) = lookup(body, items_pointer)? else {
+        return Ok(0);
+    };
+    let items = sonic_rs::to_array_iter(items.as_raw_str());
+    let mut count = 0;
+    for item in items {
+        item?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Target of the `rel="next"` entry in one `Link` header value.
+fn next_link(value: &str) -> Option<&str> {
+    let mut rest = value;
+    while let Some(open) = rest.find('<') {
+        let close = open + rest[open..].find('>')?;
+        let target = &rest[open + 1..close];
+        let params_end = rest[close..]
+            .find('<')
+            .map_or(rest.len(), |next| close + next);
+        let is_next = rest[close + 1..params_end].split(';').any(|param| {
+            param.split_once('=').is_some_and(|(name, value)| {
+                name.trim().eq_ignore_ascii_case("rel")
+                    && value
+                        .trim()
+                        .trim_end_matches(',')
+                        .trim_end()
+                        .trim_matches('"')
+                        .split_ascii_whitespace()
+                        .any(|rel| rel.eq_ignore_ascii_case("next"))
+            })
+        });
+        if is_next {
+            return Some(target);
+        }
+        rest = &rest[params_end..];
+    }
+    None
+}
+
+fn parse_url(url: &str) -> RestResult<Url> {
+    Url::parse(url).map_err(|err| RestError::internal(format!("invalid request url {url}: {err}")))
+}
+
+fn parse_offset(param: &str, value: &str) -> RestResult<u64> {
+    value
+        .parse()
+        .map_err(|_| RestError::internal(format!("pagination {param}={value} is not an offset")))
+}
+
+fn query_param(url: &str, name: &str) -> RestResult<Option<String>> {
+    Ok(parse_url(url)?
+        .query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned()))
+}
+
+/// Set query parameters, replacing existing values in place and appending new ones.
+fn with_query_params(
+    mut request: RestRequest,
+    params: &[(&String, &str)],
+) -> RestResult<RestRequest> {
+    let mut url = parse_url(&request.url)?;
+    let mut pairs = url
+        .query_pairs()
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect::<Vec<_>>();
+    for (name, value) in params {
+        match pairs.iter_mut().find(|(key, _)| key == *name) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => pairs.push((name.to_string(), value.to_string())),
+        }
+    }
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+    request.url = url.to_string();
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn link_headers_yield_the_next_target() {
+        assert_eq!(
+            next_link(
+                r#"<https://api.example.com/items?page=1>; rel="prev", <https://api.example.com/items?page=3>; rel="next""#
+            ),
+            Some("https://api.example.com/items?page=3")
+        );
+        assert_eq!(
+            next_link(r#"</items?page=2&a=1,2>; title="x"; REL="last next""#),
+            Some("/items?page=2&a=1,2")
+        );
+        assert_eq!(
+            next_link(r#"</items?page=2>; rel="next", </items?page=9>; rel="last""#),
+            Some("/items?page=2")
+        );
+        assert_eq!(
+            next_link(r#"<https://api.example.com/items?page=1>; rel="first""#),
+            None
+        );
+        assert_eq!(next_link("garbage"), None);
+    }
+
+    #[test]
+    fn pointers_find_nested_values_and_query_params_replace_in_place() {
+        let body = br#"{"result":{"data":[1,2,3],"next":null,"a/b":"slash"}}"#;
+        assert_eq!(item_count(body, "/result/data").expect("count"), 3);
+        assert_eq!(item_count(body, "/result/missing").expect("absent"), 0);
+        assert!(
+            lookup(body, "/result/next")
+                .expect("null")
+                .is_some_and(|v| v.is_null())
+        );
+        assert_eq!(
+            lookup(body, "/result/a~1b")
+                .expect("escaped")
+                .map(|v| v.as_raw_str().to_string()),
+            Some("\"slash\"".to_string())
+        );
+        assert_eq!(
+            lookup(body, "/result/data/1")
+                .expect("index")
+                .map(|v| v.as_raw_str().to_string()),
+            Some("2".to_string())
+        );
+
+        let offset = "offset".to_string();
+        let limit = "limit".to_string();
+        let request = with_query_params(
+            RestRequest::get("https://api.example.com/trades?offset=0&currency=BTC"),
+            &[(&offset, "50"), (&limit, "50")],
+        )
+        .expect("url parses");
+        assert_eq!(
+            request.url,
+            "https://api.example.com/trades?offset=50&currency=BTC&limit=50"
+        );
+    }
+}
@@ -0,0 +1,273 @@
+//! Request pacing hook for [`Client`](crate::Client), with a built-in token bucket.
+//!
+//! The client awaits [`RateLimitGate::acquire`] before every attempt, retries and reconnects
+//! included, and hands each response head to [`RateLimitGate::observe`] so a gate can follow
+//! the quota the server reports.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::adapter::{RestBytes, RestError, RestFuture, RestRequest, RestResult};
+use crate::concurrency::host_key;
+use crate::headers::{self, RestRateLimit};
+
+/// What a request costs and which budget it draws from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RestRateLimitKey {
+    /// `host[:port]` as written in the URL; empty when the URL does not parse.
+    pub host: String,
+    /// The request's `fixture_contract`.
+    pub contract: Option<String>,
+    /// `RestRequest::rate_limit_weight`, 1 unless declared.
+    pub weight: u32,
+}
+
+impl RestRateLimitKey {
+    pub fn for_request(request: &RestRequest) -> Self {
+        Self {
+            host: host_key(&request.url).unwrap_or_default(),
+            contract: request.fixture_contract.clone(),
+            weight: request.rate_limit_weight,
+        }
+    }
+}
+
+enum ObservedHeaders<'a> {
+    Owned(&'a [(String, RestBytes)]),
+    Kept(&'a [(&'static str, RestBytes)]),
+}
+
+/// A response head as seen by [`RateLimitGate::observe`].
+pub struct RestRateLimitObservation<'a> {
+    pub status: u16,
+    headers: ObservedHeaders<'a>,
+}
+
+impl<'a> RestRateLimitObservation<'a> {
+    pub(crate) fn new(status: u16, headers: &'a [(String, RestBytes)]) -> Self {
+        Self {
+            status,
+            headers: ObservedHeaders::Owned(headers),
+        }
+    }
+
+    pub(crate) fn kept(status: u16, headers: &'a [(&'static str, RestBytes)]) -> Self {
+        Self {
+            status,
+            headers: ObservedHeaders::Kept(headers),
+        }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&'a str> {
+        match self.headers {
+            ObservedHeaders::Owned(headers) => headers::header(headers, name),
+            ObservedHeaders::Kept(headers) => headers::header(headers, name),
+        }
+    }
+
+    pub fn rate_limit(&self) -> Option<RestRateLimit> {
+        match self.headers {
+            ObservedHeaders::Owned(headers) => headers::rate_limit(headers),
+            ObservedHeaders::Kept(headers) => headers::rate_limit(headers),
+        }
+    }
+}
+
+/// Paces requests sent through a [`Client`](crate::Client) configured with
+/// `with_rate_limit_gate`.
+pub trait RateLimitGate: Send + Sync {
+    /// Resolves once a request with `key` may be sent; an error fails the attempt unsent.
+    fn acquire(&self, key: &RestRateLimitKey) -> RestFuture<RestResult<()>>;
+
+    /// Headers `observe` reads. JSON helpers that otherwise drop headers keep these, and
+    /// `get_raw_with_headers` requests them alongside the caller's selection, which is all its
+    /// response carries.
+    fn observed_headers(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Called with every response head, successful or not.
+    fn observe(&self, _key: &RestRateLimitKey, _response: &RestRateLimitObservation<'_>) {}
+}
+
+/// Buckets keyed by host and, with `per_contract`, contract.
+type Buckets = HashMap<(String, Option<String>), BucketState>;
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// `capacity` weight per `interval` for each host (or host and contract), refilled continuously.
+/// Clones share their buckets.
+#[derive(Clone, Debug)]
+pub struct RestTokenBucket {
+    capacity: u32,
+    interval: Duration,
+    per_contract: bool,
+    used_quota_headers: &'static [&'static str],
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+impl RestTokenBucket {
+    pub fn new(capacity: u32, interval: Duration) -> Self {
+        Self {
+            capacity,
+            interval,
+            per_contract: false,
+            used_quota_headers: &[],
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Separate buckets per contract on each host.
+    pub fn per_contract(mut self) -> Self {
+        self.per_contract = true;
+        self
+    }
+
+    /// Headers reporting quota already used in the current window (e.g.
+    /// `x-mbx-used-weight-1m`). The bucket never holds more than `capacity` minus the highest
+    /// reported value, so weight spent by other processes or underestimated locally is
+    /// accounted for.
+    pub fn with_used_quota_headers(mut self, headers: &'static [&'static str]) -> Self {
+        self.used_quota_headers = headers;
+        self
+    }
+
+    /// Whole tokens available to `key` now.
+    pub fn available(&self, key: &RestRateLimitKey) -> u32 {
+        let mut buckets = self.lock();
+        let bucket = self.bucket(&mut buckets, key);
+        bucket.tokens as u32
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Buckets> {
+        self.buckets
+            .lock()
+            .expect("token bucket mutex poisoned while reading buckets")
+    }
+
+    /// The refilled bucket for `key`, created full.
+    fn bucket<'a>(&self, buckets: &'a mut Buckets, key: &RestRateLimitKey) -> &'a mut BucketState {
+        let contract = if self.per_contract {
+            key.contract.clone()
+        } else {
+            None
+        };
+        let capacity = f64::from(self.capacity);
+        let now = Instant::now();
+        let bucket = buckets
+            .entry((key.host.clone(), contract))
+            .or_insert(BucketState {
+                tokens: capacity,
+                refilled_at: now,
+            });
+        let refill = if self.interval.is_zero() {
+            capacity
+        } else {
+            capacity * now.duration_since(bucket.refilled_at).as_secs_f64()
+                / self.interval.as_secs_f64()
+        };
+        bucket.tokens = (bucket.tokens + refill).min(capacity);
+        bucket.refilled_at = now;
+        bucket
+    }
+
+    /// Take `key.weight` tokens, or the time until enough have refilled.
+    fn try_take(&self, key: &RestRateLimitKey) -> Result<(), Duration> {
+        let weight = f64::from(key.weight);
+        let mut buckets = self.lock();
+        let bucket = self.bucket(&mut buckets, key);
+        if bucket.tokens >= weight {
+            bucket.tokens -= weight;
+            return Ok(());
+        }
+        let missing = weight - bucket.tokens;
+        Err(self
+            .interval
+            .mul_f64(missing / f64::from(self.capacity))
+            .max(Duration::from_millis(1)))
+    }
+}
+
+impl RateLimitGate for RestTokenBucket {
+    fn acquire(&self, key: &RestRateLimitKey) -> RestFuture<RestResult<()>> {
+        let bucket = self.clone();
+        let key = key.clone();
+        Box::pin(async move {
+            if key.weight > bucket.capacity {
+                return Err(RestError::internal(format!(
+                    "rate limit weight {} exceeds bucket capacity {} for {}",
+                    key.weight, bucket.capacity, key.host
+                )));
+            }
+            while let Err(wait) = bucket.try_take(&key) {
+                tokio::time::sleep(wait).await;
+            }
+            Ok(())
+        })
+    }
+
+    fn observed_headers(&self) -> &'static [&'static str] {
+        self.used_quota_headers
+    }
+
+    fn observe(&self, key: &RestRateLimitKey, response: &RestRateLimitObservation<'_>) {
+        let Some(used) = self
+            .used_quota_headers
+            .iter()
+            .filter_map(|name| response.header(name)?.parse::<u64>().ok())
+            .max()
+        else {
+            return;
+        };
+        let remaining = u64::from(self.capacity).saturating_sub(used) as f64;
+        let mut buckets = self.lock();
+        let bucket = self.bucket(&mut buckets, key);
+        bucket.tokens = bucket.tokens.min(remaining);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(weight: u32) -> RestRateLimitKey {
+        RestRateLimitKey::for_request(
+            &RestRequest::get("https://api.example.com/api/v3/order")
+                .with_fixture_contract("binance.order")
+                .with_rate_limit_weight(weight),
+        )
+    }
+
+    #[test]
+    fn used_quota_headers_only_ever_tighten_the_bucket() {
+        let bucket = RestTokenBucket::new(1200, Duration::from_secs(60))
+            .with_used_quota_headers(&["x-mbx-used-weight-1m"]);
+        assert_eq!(bucket.try_take(&key(10)), Ok(()));
+        assert_eq!(bucket.available(&key(1)), 1190);
+
+        let headers = vec![("X-MBX-USED-WEIGHT-1M".to_string(), RestBytes::from("1100"))];
+        bucket.observe(&key(10), &RestRateLimitObservation::new(200, &headers));
+        assert_eq!(bucket.available(&key(1)), 100);
+
+        let headers = vec![("x-mbx-used-weight-1m".to_string(), RestBytes::from("5"))];
+        bucket.observe(&key(10), &RestRateLimitObservation::new(200, &headers));
+        assert_eq!(bucket.available(&key(1)), 100);
+    }
+
+    #[test]
+    fn empty_buckets_report_the_wait_for_the_missing_weight() {
+        let bucket = RestTokenBucket::new(10, Duration::from_secs(10)).per_contract();
+        assert_eq!(bucket.try_take(&key(10)), Ok(()));
+        let wait = bucket.try_take(&key(4)).expect_err("bucket is empty");
+        assert!(wait > Duration::from_millis(3_900) && wait <= Duration::from_secs(4));
+
+        let mut other = key(4);
+        other.contract = Some("binance.ticker".to_string());
+        assert_eq!(bucket.try_take(&other), Ok(()));
+    }
+}
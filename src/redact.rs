@@ -0,0 +1,316 @@
+//! Secret redaction for captured fixtures and logged requests.
+//!
+//! [`RestRedactionRules::default`] covers well-known credential headers, signature/key query
+//! parameters and token-shaped text; boundary crates add exchange-specific header names, JSON
+//! pointers into bodies (with `*` matching every array element) and regexes.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use sonic_rs::{JsonValueMutTrait, JsonValueTrait, Value};
+
+use crate::adapter::{RestBytes, RestError, RestRequest, RestResult};
+use crate::fixture::{RestFixture, RestFixtureBody};
+
+pub const REST_REDACTED: &str = "[REDACTED]";
+
+const DEFAULT_SECRET_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "api-key",
+    "x-mbx-apikey",
+    "x-bapi-api-key",
+    "x-bapi-sign",
+    "ok-access-key",
+    "ok-access-sign",
+    "ok-access-passphrase",
+];
+
+const DEFAULT_SECRET_QUERY_PARAMS: &[&str] = &[
+    "signature",
+    "sign",
+    "sig",
+    "api_key",
+    "apikey",
+    "access_token",
+    "client_secret",
+    "secret",
+    "token",
+];
+
+/// Patterns with a `secret` group only have that group replaced, so surrounding JSON stays valid.
+const DEFAULT_SECRET_PATTERNS: &[&str] = &[
+    r"(?i)\bbearer\s+(?P<secret>[a-z0-9._~+/=-]{8,})",
+    r"\b(?P<secret>AKIA[0-9A-Z]{16})\b",
+    r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+    r#"(?i)"(?:api_?key|api_?secret|client_secret|secret_?key|password|passphrase|access_token|refresh_token)"\s*:\s*"(?P<secret>[^"\[][^"]*)""#,
+];
+
+fn default_patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        DEFAULT_SECRET_PATTERNS
+            .iter()
+            .map(|pattern| Regex::new(pattern).expect("built-in secret pattern compiles"))
+            .collect()
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct RestRedactionRules {
+    headers: Vec<String>,
+    query_params: Vec<String>,
+    json_pointers: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl Default for RestRedactionRules {
+    /// Well-known credential headers, query parameters and token patterns.
+    fn default() -> Self {
+        Self {
+            headers: DEFAULT_SECRET_HEADERS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            query_params: DEFAULT_SECRET_QUERY_PARAMS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            json_pointers: Vec::new(),
+            patterns: default_patterns().to_vec(),
+        }
+    }
+}
+
+impl RestRedactionRules {
+    /// Rules that redact nothing.
+    pub fn none() -> Self {
+        Self {
+            headers: Vec::new(),
+            query_params: Vec::new(),
+            json_pointers: Vec::new(),
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Header names are matched case-insensitively.
+    pub fn with_header(mut self, name: impl Into<String>) -> Self {
+        self.headers.push(name.into().to_ascii_lowercase());
+        self
+    }
+
+    pub fn with_query_param(mut self, name: impl Into<String>) -> Self {
+        self.query_params.push(name.into().to_ascii_lowercase());
+        self
+    }
+
+    /// RFC 6901 pointer into JSON bodies, e.g. `/result/*/account_id`.
+    pub fn with_json_pointer(mut self, pointer: impl Into<String>) -> Self {
+        self.json_pointers.push(pointer.into());
+        self
+    }
+
+    /// Replace every match, or only its `secret` named group when present.
+    pub fn with_pattern(mut self, pattern: &str) -> RestResult<Self> {
+        let regex = Regex::new(pattern).map_err(|err| {
+            RestError::internal(format!("invalid redaction pattern {pattern:?}: {err}"))
+        })?;
+        self.patterns.push(regex);
+        Ok(self)
+    }
+
+    pub fn redact_request(&self, request: &RestRequest) -> RestRequest {
+        let mut redacted = request.clone();
+        let mut findings = Vec::new();
+        redacted.url = self.redact_url(&request.url, &mut findings);
+        for (name, value) in &mut redacted.headers {
+            if let Some(text) =
+                self.redact_header(name, &String::from_utf8_lossy(value), &mut findings)
+            {
+                *value = RestBytes::from(text);
+            }
+        }
+        if let Some(body) = &request.body
+            && let Ok(text) = std::str::from_utf8(body)
+            && let Some(text) = self.redact_body(text, &mut findings)
+        {
+            redacted.body = Some(RestBytes::from(text));
+        }
+        redacted
+    }
+
+    /// Redact a fixture in place, returning a description of each redaction.
+    pub fn redact_fixture(&self, fixture: &mut RestFixture) -> Vec<String> {
+        let mut findings = Vec::new();
+        fixture.request.url = self.redact_url(&fixture.request.url, &mut findings);
+        for (name, value) in fixture
+            .request
+            .headers
+            .iter_mut()
+            .chain(fixture.response.headers.iter_mut())
+        {
+            if let Some(text) = self.redact_header(name, value, &mut findings) {
+                *value = text;
+            }
+        }
+        for body in fixture
+            .request
+            .body
+            .iter_mut()
+            .chain(std::iter::once(&mut fixture.response.body))
+        {
+            if let RestFixtureBody::Text(text) = body
+                && let Some(redacted) = self.redact_body(text, &mut findings)
+            {
+                *text = redacted;
+            }
+        }
+        findings
+    }
+
+    /// Secrets these rules would redact from a fixture file's contents.
+    pub fn find_secrets(&self, fixture_bytes: &[u8]) -> Vec<String> {
+        match sonic_rs::from_slice::<RestFixture>(fixture_bytes) {
+            Ok(mut fixture) => self.redact_fixture(&mut fixture),
+            Err(_) => {
+                let mut findings = Vec::new();
+                self.redact_patterns(&String::from_utf8_lossy(fixture_bytes), &mut findings);
+                findings
+            }
+        }
+    }
+
+    fn redact_header(&self, name: &str, value: &str, findings: &mut Vec<String>) -> Option<String> {
+        let lower = name.to_ascii_lowercase();
+        if self.headers.contains(&lower) {
+            if value == REST_REDACTED {
+                return None;
+            }
+            findings.push(format!("header {lower}"));
+            return Some(REST_REDACTED.to_string());
+        }
+        self.redact_patterns(value, findings)
+    }
+
+    fn redact_url(&self, url: &str, findings: &mut Vec<String>) -> String {
+        let (without_fragment, fragment) = match url.split_once('#') {
+            Some((head, fragment)) => (head, Some(fragment)),
+            None => (url, None),
+        };
+        let mut redacted = match without_fragment.split_once('?') {
+            Some((base, query)) if !self.query_params.is_empty() => {
+                let params = query
+                    .split('&')
+                    .map(|param| {
+                        let (name, value) = param.split_once('=').unwrap_or((param, ""));
+                        let lower = name.to_ascii_lowercase();
+                        if self.query_params.contains(&lower) && value != REST_REDACTED {
+                            findings.push(format!("query parameter {lower}"));
+                            format!("{name}={REST_REDACTED}")
+                        } else {
+                            param.to_string()
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                format!("{base}?{}", params.join("&"))
+            }
+            _ => without_fragment.to_string(),
+        };
+        if let Some(fragment) = fragment {
+            redacted.push('#');
+            redacted.push_str(fragment);
+        }
+        self.redact_patterns(&redacted, findings)
+            .unwrap_or(redacted)
+    }
+
+    fn redact_body(&self, text: &str, findings: &mut Vec<String>) -> Option<String> {
+        let mut changed = None;
+        if !self.json_pointers.is_empty()
+            && let Ok(mut value) = sonic_rs::from_str::<Value>(text)
+        {
+            let before = findings.len();
+            for pointer in &self.json_pointers {
+                let tokens = pointer
+                    .split('/')
+                    .skip(1)
+                    .map(|token| token.replace("~1", "/").replace("~0", "~"))
+                    .collect::<Vec<_>>();
+                if redact_pointer(&mut value, &tokens) {
+                    findings.push(format!("json pointer {pointer}"));
+                }
+            }
+            if findings.len() > before {
+                changed = sonic_rs::to_string(&value).ok();
+            }
+        }
+        let current = changed.as_deref().unwrap_or(text);
+        self.redact_patterns(current, findings).or(changed)
+    }
+
+    fn redact_patterns(&self, text: &str, findings: &mut Vec<String>) -> Option<String> {
+        let mut current: Option<String> = None;
+        for regex in &self.patterns {
+            let input = current.as_deref().unwrap_or(text);
+            if !regex.is_match(input) {
+                continue;
+            }
+            let mut output = String::with_capacity(input.len());
+            let mut last = 0;
+            let mut replaced = false;
+            for captures in regex.captures_iter(input) {
+                let range = captures
+                    .name("secret")
+                    .unwrap_or_else(|| captures.get(0).expect("match has group 0"))
+                    .range();
+                if &input[range.clone()] == REST_REDACTED {
+                    continue;
+                }
+                output.push_str(&input[last..range.start]);
+                output.push_str(REST_REDACTED);
+                last = range.end;
+                replaced = true;
+            }
+            if replaced {
+                output.push_str(&input[last..]);
+                findings.push(format!("pattern {}", regex.as_str()));
+                current = Some(output);
+            }
+        }
+        current
+    }
+}
+
+/// Replace the value at `tokens` (with `*` fanning out over arrays); true when anything changed.
+fn redact_pointer(value: &mut Value, tokens: &[String]) -> bool {
+    let Some((token, rest)) = tokens.split_first() else {
+        if value.as_str() == Some(REST_REDACTED) {
+            return false;
+        }
+        *value = Value::from(REST_REDACTED);
+        return true;
+    };
+    if let Some(array) = value.as_array_mut() {
+        if token == "*" {
+            let mut changed = false;
+            for item in array.iter_mut() {
+                changed |= redact_pointer(item, rest);
+            }
+            return changed;
+        }
+        return token
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| array.get_mut(index))
+            .is_some_and(|item| redact_pointer(item, rest));
+    }
+    if let Some(object) = value.as_object_mut() {
+        return object
+            .get_mut(token)
+            .is_some_and(|field| redact_pointer(field, rest));
+    }
+    false
+}
@@ -0,0 +1,306 @@
+//! Server-Sent Events over [`RestTransport`](crate::RestTransport) streaming bodies.
+//!
+//! [`RestSseStream`] parses `event`, `data`, `id` and `retry` fields as the HTML event-stream
+//! format specifies, and when the body ends or the connection fails with a retryable error it waits
+//! the server-provided `retry` delay and reconnects with `Last-Event-ID`.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use sonic_rs::Deserialize;
+
+use crate::adapter::{Client, RestBytes, RestError, RestRequest, RestResult};
+use crate::stream::RestBodyStream;
+
+const DEFAULT_RETRY: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestSseEvent {
+    /// Event type; `message` when the server sent no `event` field.
+    pub event: String,
+    pub data: String,
+    /// Last event id seen on the stream when this event was dispatched.
+    pub id: Option<String>,
+}
+
+impl RestSseEvent {
+    pub fn json<T>(&self) -> RestResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        sonic_rs::from_str(&self.data).map_err(RestError::from)
+    }
+}
+
+/// Incremental event-stream parser; incomplete trailing events are dropped at end of body.
+#[derive(Debug, Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    event: String,
+    data: String,
+    last_event_id: String,
+    retry: Option<Duration>,
+}
+
+impl SseParser {
+    fn feed(&mut self, chunk: &[u8], events: &mut VecDeque<RestSseEvent>) {
+        self.buffer.extend_from_slice(chunk);
+        let mut start = 0;
+        while let Some(position) = self.buffer[start..]
+            .iter()
+            .position(|byte| *byte == b'\n' || *byte == b'\r')
+        {
+            let end = start + position;
+            let mut next = end + 1;
+            if self.buffer[end] == b'\r' {
+                match self.buffer.get(next) {
+                    Some(b'\n') => next += 1,
+                    Some(_) => {}
+                    // A trailing `\r` may be the first half of `\r\n`.
+                    None => break,
+                }
+            }
+            let line = String::from_utf8_lossy(&self.buffer[start..end]).into_owned();
+            self.line(&line, events);
+            start = next;
+        }
+        self.buffer.drain(..start);
+    }
+
+    /// Forget partial state when a connection ends; the last event id and retry survive.
+    fn reset_connection(&mut self) {
+        self.buffer.clear();
+        self.event.clear();
+        self.data.clear();
+    }
+
+    fn line(&mut self, line: &str, events: &mut VecDeque<RestSseEvent>) {
+        if line.is_empty() {
+            self.dispatch(events);
+            return;
+        }
+        if line.starts_with(':') {
+            return;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = value.to_string(),
+            "retry" if !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()) => {
+                if let Ok(millis) = value.parse() {
+                    self.retry = Some(Duration::from_millis(millis));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn dispatch(&mut self, events: &mut VecDeque<RestSseEvent>) {
+        if self.data.is_empty() {
+            self.event.clear();
+            return;
+        }
+        self.data.pop();
+        let event = std::mem::take(&mut self.event);
+        events.push_back(RestSseEvent {
+            event: if event.is_empty() {
+                "message".to_string()
+            } else {
+                event
+            },
+            data: std::mem::take(&mut self.data),
+            id: (!self.last_event_id.is_empty()).then(|| self.last_event_id.clone()),
+        });
+    }
+}
+
+/// Reads events from `request`, reconnecting as needed. Created with [`Client::event_stream`].
+pub struct RestSseStream {
+    client: Client,
+    request: RestRequest,
+    parser: SseParser,
+    events: VecDeque<RestSseEvent>,
+    body: Option<RestBodyStream>,
+    connections: u32,
+    max_reconnects: Option<u32>,
+    done: bool,
+}
+
+impl RestSseStream {
+    /// The request's timeout is cleared, since an event stream has no natural end.
+    pub fn new(client: Client, request: RestRequest) -> Self {
+        let mut request = request
+            .with_header("accept", "text/event-stream")
+            .with_header("cache-control", "no-cache");
+        request.timeout = None;
+        Self {
+            client,
+            request,
+            parser: SseParser::default(),
+            events: VecDeque::new(),
+            body: None,
+            connections: 0,
+            max_reconnects: None,
+            done: false,
+        }
+    }
+
+    /// Stop after `max_reconnects` reconnections; unlimited by default.
+    pub fn with_max_reconnects(mut self, max_reconnects: u32) -> Self {
+        self.max_reconnects = Some(max_reconnects);
+        self
+    }
+
+    /// Resume after `id`, as if it had been received on an earlier connection.
+    pub fn with_last_event_id(mut self, id: impl Into<String>) -> Self {
+        self.parser.last_event_id = id.into();
+        self
+    }
+
+    pub fn last_event_id(&self) -> Option<&str> {
+        Some(self.parser.last_event_id.as_str()).filter(|id| !id.is_empty())
+    }
+
+    /// Delay before reconnecting: the server's last `retry` field, else three seconds.
+    pub fn retry_delay(&self) -> Duration {
+        self.parser.retry.unwrap_or(DEFAULT_RETRY)
+    }
+
+    /// Connections opened so far, including the first.
+    pub fn connections(&self) -> u32 {
+        self.connections
+    }
+
+    /// The next event, or `None` once the server ends the stream (HTTP 204) or reconnects are
+    /// exhausted.
+    pub async fn next_event(&mut self) -> RestResult<Option<RestSseEvent>> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(Some(event));
+            }
+            if self.done {
+                return Ok(None);
+            }
+            let Some(body) = self.body.as_mut() else {
+                if !self.may_connect() {
+                    self.done = true;
+                    continue;
+                }
+                if self.connections > 0 {
+                    tokio::time::sleep(self.retry_delay()).await;
+                }
+                self.connect().await?;
+                continue;
+            };
+            match body.next_chunk().await {
+                Ok(Some(chunk)) => self.parser.feed(&chunk, &mut self.events),
+                Ok(None) => self.disconnect(),
+                Err(err) if err.is_retryable() => self.disconnect(),
+                Err(err) => {
+                    self.done = true;
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    fn may_connect(&self) -> bool {
+        self.connections == 0
+            || self
+                .max_reconnects
+                .is_none_or(|max| self.connections <= max)
+    }
+
+    fn disconnect(&mut self) {
+        self.body = None;
+        self.parser.reset_connection();
+    }
+
+    async fn connect(&mut self) -> RestResult<()> {
+        let mut request = self.request.clone();
+        if let Some(id) = self.last_event_id() {
+            request = request.with_header("last-event-id", RestBytes::from(id.to_string()));
+        }
+        self.connections += 1;
+        let response = match self.client.get_stream(request).await {
+            Ok(response) => response,
+            Err(err) if err.is_retryable() && self.connections > 1 => return Ok(()),
+            Err(err) => {
+                self.done = true;
+                return Err(err);
+            }
+        };
+        if response.status == 204 {
+            self.done = true;
+            return Ok(());
+        }
+        if !response.is_success() {
+            self.done = true;
+            return Err(response
+                .into_response()
+                .await?
+                .ensure_success()
+                .expect_err("non-2xx responses are rejected"));
+        }
+        self.body = Some(response.body);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(chunks: &[&str]) -> (Vec<RestSseEvent>, SseParser) {
+        let mut parser = SseParser::default();
+        let mut events = VecDeque::new();
+        for chunk in chunks {
+            parser.feed(chunk.as_bytes(), &mut events);
+        }
+        (events.into_iter().collect(), parser)
+    }
+
+    #[test]
+    fn parser_handles_fields_comments_and_split_line_endings() {
+        let (events, parser) = parse(&[
+            ": keep-alive\r",
+            "\nretry: 250\nevent: status\ndata: {\"a\":1}\ndata:",
+            "second\r\nid: 7\r\n\r\ndata: plain\n\n",
+            "data: unterminated",
+        ]);
+        assert_eq!(
+            events,
+            vec![
+                RestSseEvent {
+                    event: "status".to_string(),
+                    data: "{\"a\":1}\nsecond".to_string(),
+                    id: Some("7".to_string()),
+                },
+                RestSseEvent {
+                    event: "message".to_string(),
+                    data: "plain".to_string(),
+                    id: Some("7".to_string()),
+                },
+            ]
+        );
+        assert_eq!(parser.retry, Some(Duration::from_millis(250)));
+        assert!(parser.data.is_empty());
+        assert_eq!(parser.buffer, b"data: unterminated");
+    }
+
+    #[test]
+    fn events_without_data_only_update_the_last_event_id() {
+        let (events, parser) = parse(&["id: 9\nevent: ping\n\nretry: soon\n\n"]);
+        assert!(events.is_empty());
+        assert_eq!(parser.last_event_id, "9");
+        assert_eq!(parser.retry, None);
+        assert!(parser.event.is_empty());
+    }
+}
@@ -0,0 +1,324 @@
+//! Pull-based streaming response bodies.
+//!
+//! [`RestBodyStream::next_chunk`] reads the next chunk only when called, so a slow consumer
+//! applies backpressure all the way to the socket. The request's `max_body_bytes` is enforced as
+//! chunks arrive (and up front from `Content-Length`) with [`RestError::BodyTooLarge`].
+//! Content-encoded bodies are decoded chunk by chunk, and the limit applies to decoded bytes.
+
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+use bytes::BytesMut;
+
+use crate::adapter::{RestBytes, RestError, RestErrorKind, RestResponse, RestResult};
+use crate::compression::RestBodyDecoder;
+use crate::concurrency::RestConcurrencyPermit;
+use crate::headers::{self, RestRateLimit, RestRetryAfter};
+
+enum RestBodySource {
+    Reqwest(reqwest::Response),
+    /// Chunks delivered after their delay, as scripted by the mock.
+    Scripted(VecDeque<(Duration, RestBytes)>),
+    Done,
+}
+
+pub struct RestBodyStream {
+    source: RestBodySource,
+    decoder: Option<RestBodyDecoder>,
+    max_body_bytes: Option<u64>,
+    received: u64,
+    wire_received: u64,
+    status: Option<u16>,
+    /// Concurrency slot held until the body ends or the stream is dropped.
+    permit: Option<RestConcurrencyPermit>,
+}
+
+impl std::fmt::Debug for RestBodyStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RestBodyStream")
+            .field("max_body_bytes", &self.max_body_bytes)
+            .field("received", &self.received)
+            .field("wire_received", &self.wire_received)
+            .field(
+                "encoding",
+                &self.decoder.as_ref().map(RestBodyDecoder::encoding),
+            )
+            .field("done", &matches!(self.source, RestBodySource::Done))
+            .finish()
+    }
+}
+
+impl RestBodyStream {
+    /// A body that is already in memory, delivered as one chunk.
+    pub fn from_bytes(body: RestBytes) -> Self {
+        let chunks = if body.is_empty() {
+            VecDeque::new()
+        } else {
+            VecDeque::from([(Duration::ZERO, body)])
+        };
+        Self::from_source(RestBodySource::Scripted(chunks))
+    }
+
+    pub(crate) fn scripted(chunks: impl IntoIterator<Item = (Duration, RestBytes)>) -> Self {
+        Self::from_source(RestBodySource::Scripted(chunks.into_iter().collect()))
+    }
+
+    /// Fails immediately when an unencoded body's `Content-Length` already exceeds
+    /// `max_body_bytes`.
+    pub(crate) fn from_reqwest(
+        response: reqwest::Response,
+        max_body_bytes: Option<u64>,
+        decoder: Option<RestBodyDecoder>,
+    ) -> RestResult<Self> {
+        let status = response.status().as_u16();
+        if decoder.is_none()
+            && let (Some(limit), Some(length)) = (max_body_bytes, response.content_length())
+            && length > limit
+        {
+            return Err(RestError::body_too_large(limit, length, Some(status)));
+        }
+        Ok(Self {
+            status: Some(status),
+            decoder,
+            ..Self::from_source(RestBodySource::Reqwest(response))
+        }
+        .with_max_body_bytes(max_body_bytes))
+    }
+
+    fn from_source(source: RestBodySource) -> Self {
+        Self {
+            source,
+            decoder: None,
+            max_body_bytes: None,
+            received: 0,
+            wire_received: 0,
+            status: None,
+            permit: None,
+        }
+    }
+
+    /// Also bounds the decoder, so an encoded body fails once its decoded output passes the
+    /// limit rather than after a whole chunk has been expanded.
+    pub(crate) fn with_max_body_bytes(mut self, max_body_bytes: Option<u64>) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self.decoder = self
+            .decoder
+            .map(|decoder| decoder.with_limit(max_body_bytes));
+        self
+    }
+
+    pub(crate) fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub(crate) fn with_permit(mut self, permit: Option<RestConcurrencyPermit>) -> Self {
+        self.permit = permit;
+        self
+    }
+
+    /// Bytes delivered so far, after decoding.
+    pub fn received_bytes(&self) -> u64 {
+        self.received
+    }
+
+    /// Bytes read from the wire so far; equal to `received_bytes` for unencoded bodies.
+    pub fn wire_bytes(&self) -> u64 {
+        self.wire_received
+    }
+
+    pub fn is_encoded(&self) -> bool {
+        self.decoder.is_some()
+    }
+
+    pub fn is_done(&self) -> bool {
+        matches!(self.source, RestBodySource::Done)
+    }
+
+    /// The next chunk, or `None` once the body is complete.
+    pub async fn next_chunk(&mut self) -> RestResult<Option<RestBytes>> {
+        let chunk = match self.next_decoded_chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => {
+                self.source = RestBodySource::Done;
+                self.permit = None;
+                return Ok(None);
+            }
+            Err(err) => {
+                self.source = RestBodySource::Done;
+                self.decoder = None;
+                self.permit = None;
+                return Err(err);
+            }
+        };
+        self.received += chunk.len() as u64;
+        if let Some(limit) = self.max_body_bytes
+            && self.received > limit
+        {
+            self.source = RestBodySource::Done;
+            self.decoder = None;
+            self.permit = None;
+            return Err(RestError::body_too_large(limit, self.received, self.status));
+        }
+        Ok(Some(chunk))
+    }
+
+    /// Skips wire chunks that decode to nothing, and flushes the decoder at the end of the body.
+    async fn next_decoded_chunk(&mut self) -> RestResult<Option<RestBytes>> {
+        loop {
+            let wire = self.next_wire_chunk().await?;
+            let Some(decoder) = self.decoder.as_mut() else {
+                return Ok(wire);
+            };
+            let decoded = match wire {
+                Some(chunk) => decoder.write(&chunk),
+                None => {
+                    let tail = decoder.finish();
+                    self.decoder = None;
+                    tail
+                }
+            }
+            .map_err(|err| match err {
+                RestError::Decode {
+                    encoding, message, ..
+                } => RestError::Decode {
+                    encoding,
+                    status: self.status,
+                    message,
+                },
+                RestError::BodyTooLarge {
+                    limit, received, ..
+                } => RestError::body_too_large(limit, received, self.status),
+                other => other,
+            })?;
+            if !decoded.is_empty() {
+                return Ok(Some(decoded));
+            }
+            if self.decoder.is_none() {
+                return Ok(None);
+            }
+        }
+    }
+
+    async fn next_wire_chunk(&mut self) -> RestResult<Option<RestBytes>> {
+        let chunk = match &mut self.source {
+            RestBodySource::Reqwest(response) => response
+                .chunk()
+                .await
+                .map_err(|err| RestError::from_reqwest(RestErrorKind::Receive, err))?,
+            RestBodySource::Scripted(chunks) => match chunks.pop_front() {
+                Some((delay, chunk)) => {
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                    }
+                    Some(chunk)
+                }
+                None => None,
+            },
+            RestBodySource::Done => None,
+        };
+        if let Some(chunk) = &chunk {
+            self.wire_received += chunk.len() as u64;
+        }
+        Ok(chunk)
+    }
+
+    /// Buffer the rest of the body.
+    pub async fn read_to_end(mut self) -> RestResult<RestBytes> {
+        self.read_rest().await
+    }
+
+    /// Buffer the rest of the body, with the wire size when it was content-encoded.
+    pub(crate) async fn read_to_end_encoded(mut self) -> RestResult<(RestBytes, Option<u64>)> {
+        let encoded = self.is_encoded();
+        let body = self.read_rest().await?;
+        Ok((body, encoded.then_some(self.wire_received)))
+    }
+
+    async fn read_rest(&mut self) -> RestResult<RestBytes> {
+        let Some(first) = self.next_chunk().await? else {
+            return Ok(RestBytes::new());
+        };
+        let Some(second) = self.next_chunk().await? else {
+            return Ok(first);
+        };
+        let mut body = BytesMut::with_capacity(first.len() + second.len());
+        body.extend_from_slice(&first);
+        body.extend_from_slice(&second);
+        while let Some(chunk) = self.next_chunk().await? {
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body.freeze())
+    }
+}
+
+/// A response whose head has arrived and whose body is read on demand.
+#[derive(Debug)]
+pub struct RestStreamingResponse {
+    pub status: u16,
+    pub headers: Vec<(String, RestBytes)>,
+    pub body: RestBodyStream,
+    /// Time until the response head arrived.
+    pub elapsed: Duration,
+    /// Time spent waiting for a `Client` concurrency slot; the slot is held until the body ends.
+    pub queue_wait: Duration,
+}
+
+impl RestStreamingResponse {
+    /// A buffered response served as a single-chunk stream.
+    pub(crate) fn buffered(response: RestResponse) -> Self {
+        Self {
+            status: response.status,
+            headers: response.headers,
+            body: RestBodyStream::from_bytes(response.body).with_status(response.status),
+            elapsed: response.elapsed,
+            queue_wait: response.queue_wait,
+        }
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Buffer the rest of the body into a [`RestResponse`].
+    pub async fn into_response(self) -> RestResult<RestResponse> {
+        let (body, encoded_size) = self.body.read_to_end_encoded().await?;
+        Ok(RestResponse {
+            encoded_size,
+            queue_wait: self.queue_wait,
+            ..RestResponse::new(self.status, self.headers, body, self.elapsed)
+        })
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        headers::header(&self.headers, name)
+    }
+
+    pub fn header_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        headers::header_all(&self.headers, name)
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        headers::header(&self.headers, "content-type")
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        headers::content_length(&self.headers)
+    }
+
+    pub fn date(&self) -> Option<SystemTime> {
+        headers::date(&self.headers)
+    }
+
+    pub fn retry_after(&self) -> Option<RestRetryAfter> {
+        headers::retry_after(&self.headers)
+    }
+
+    pub fn rate_limit(&self) -> Option<RestRateLimit> {
+        headers::rate_limit(&self.headers)
+    }
+}
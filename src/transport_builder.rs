@@ -0,0 +1,370 @@
+//! Connection, TLS and proxy configuration for [`ReqwestTransport`].
+//!
+//! [`ReqwestTransportBuilder`] exposes the reqwest client settings boundary crates need without
+//! handing them a `reqwest::ClientBuilder`; certificates, client identities and proxies are
+//! described with the crate-owned [`RestCertificate`], [`RestClientIdentity`] and [`RestProxy`].
+
+use std::fmt;
+use std::time::Duration;
+
+use reqwest::{Certificate, ClientBuilder, Identity, NoProxy, Proxy, Url};
+
+use crate::adapter::{ReqwestTransport, RestBytes, RestError, RestResult};
+use crate::fixture_policy::RestFixtureRegistry;
+use crate::redact::REST_REDACTED;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RestCertificateEncoding {
+    Pem,
+    Der,
+}
+
+/// A root certificate trusted in addition to (or instead of) the built-in roots.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestCertificate {
+    encoding: RestCertificateEncoding,
+    bytes: RestBytes,
+}
+
+impl RestCertificate {
+    /// One PEM certificate, or a bundle of them.
+    pub fn from_pem(pem: impl Into<RestBytes>) -> Self {
+        Self {
+            encoding: RestCertificateEncoding::Pem,
+            bytes: pem.into(),
+        }
+    }
+
+    pub fn from_der(der: impl Into<RestBytes>) -> Self {
+        Self {
+            encoding: RestCertificateEncoding::Der,
+            bytes: der.into(),
+        }
+    }
+
+    fn to_reqwest(&self) -> RestResult<Vec<Certificate>> {
+        let certificates = match self.encoding {
+            RestCertificateEncoding::Pem => Certificate::from_pem_bundle(&self.bytes),
+            RestCertificateEncoding::Der => {
+                Certificate::from_der(&self.bytes).map(|cert| vec![cert])
+            }
+        }
+        .map_err(|err| RestError::internal(format!("invalid root certificate: {err}")))?;
+        if certificates.is_empty() {
+            return Err(RestError::internal(
+                "invalid root certificate: no certificates found in PEM",
+            ));
+        }
+        Ok(certificates)
+    }
+}
+
+/// Client certificate chain and private key presented for mutual TLS. `Debug` redacts the PEM.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RestClientIdentity {
+    pem: RestBytes,
+}
+
+impl RestClientIdentity {
+    /// A single PEM holding the certificate chain and a PKCS#8, PKCS#1 or SEC1 private key.
+    pub fn from_pem(pem: impl Into<RestBytes>) -> Self {
+        Self { pem: pem.into() }
+    }
+
+    /// Separate certificate chain and private key PEMs.
+    pub fn from_pem_parts(certificate_chain: &[u8], private_key: &[u8]) -> Self {
+        let mut pem = Vec::with_capacity(certificate_chain.len() + private_key.len() + 1);
+        pem.extend_from_slice(certificate_chain);
+        if !certificate_chain.ends_with(b"\n") {
+            pem.push(b'\n');
+        }
+        pem.extend_from_slice(private_key);
+        Self::from_pem(pem)
+    }
+
+    fn to_reqwest(&self) -> RestResult<Identity> {
+        Identity::from_pem(&self.pem)
+            .map_err(|err| RestError::internal(format!("invalid client identity: {err}")))
+    }
+}
+
+impl fmt::Debug for RestClientIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RestClientIdentity")
+            .field("pem", &REST_REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RestProxyScope {
+    All,
+    Http,
+    Https,
+}
+
+/// `Debug` redacts the basic-auth password and any password in the proxy URL.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RestProxy {
+    scope: RestProxyScope,
+    url: String,
+    basic_auth: Option<(String, String)>,
+    no_proxy: Option<String>,
+}
+
+impl RestProxy {
+    /// Proxy every request.
+    pub fn all(url: impl Into<String>) -> Self {
+        Self::scoped(RestProxyScope::All, url)
+    }
+
+    /// Proxy `http://` requests only.
+    pub fn http(url: impl Into<String>) -> Self {
+        Self::scoped(RestProxyScope::Http, url)
+    }
+
+    /// Proxy `https://` requests only.
+    pub fn https(url: impl Into<String>) -> Self {
+        Self::scoped(RestProxyScope::Https, url)
+    }
+
+    fn scoped(scope: RestProxyScope, url: impl Into<String>) -> Self {
+        Self {
+            scope,
+            url: url.into(),
+            basic_auth: None,
+            no_proxy: None,
+        }
+    }
+
+    pub fn with_basic_auth(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.basic_auth = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Hosts that bypass this proxy, in `NO_PROXY` syntax (`localhost,.internal,10.0.0.0/8`).
+    pub fn with_no_proxy(mut self, hosts: impl Into<String>) -> Self {
+        self.no_proxy = Some(hosts.into());
+        self
+    }
+
+    fn to_reqwest(&self) -> RestResult<Proxy> {
+        let proxy = match self.scope {
+            RestProxyScope::All => Proxy::all(&self.url),
+            RestProxyScope::Http => Proxy::http(&self.url),
+            RestProxyScope::Https => Proxy::https(&self.url),
+        }
+        .map_err(|err| {
+            RestError::internal(format!("invalid proxy url {}: {err}", self.redacted_url()))
+        })?;
+        let proxy = match &self.basic_auth {
+            Some((username, password)) => proxy.basic_auth(username, password),
+            None => proxy,
+        };
+        Ok(proxy.no_proxy(self.no_proxy.as_deref().and_then(NoProxy::from_string)))
+    }
+
+    /// The proxy URL with its password, if any, replaced by [`REST_REDACTED`].
+    fn redacted_url(&self) -> String {
+        match Url::parse(&self.url)
+            .ok()
+            .and_then(|url| url.password().map(str::to_string))
+        {
+            Some(password) => {
+                self.url
+                    .replacen(&format!(":{password}@"), &format!(":{REST_REDACTED}@"), 1)
+            }
+            None => self.url.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for RestProxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RestProxy")
+            .field("scope", &self.scope)
+            .field("url", &self.redacted_url())
+            .field(
+                "basic_auth",
+                &self
+                    .basic_auth
+                    .as_ref()
+                    .map(|(username, _)| (username, REST_REDACTED)),
+            )
+            .field("no_proxy", &self.no_proxy)
+            .finish()
+    }
+}
+
+/// Builds a [`ReqwestTransport`]; unset options keep reqwest's defaults. `Debug` redacts the
+/// client identity and proxy credentials.
+#[derive(Clone, Default)]
+pub struct ReqwestTransportBuilder {
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Option<Duration>>,
+    tcp_keepalive: Option<Duration>,
+    tcp_nodelay: Option<bool>,
+    connect_timeout: Option<Duration>,
+    http2_prior_knowledge: bool,
+    root_certificates: Vec<RestCertificate>,
+    built_in_root_certificates: Option<bool>,
+    identity: Option<RestClientIdentity>,
+    user_agent: Option<String>,
+    proxies: Vec<RestProxy>,
+    system_proxy: Option<bool>,
+    fixture_registry: Option<RestFixtureRegistry>,
+}
+
+impl fmt::Debug for ReqwestTransportBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReqwestTransportBuilder")
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("tcp_keepalive", &self.tcp_keepalive)
+            .field("tcp_nodelay", &self.tcp_nodelay)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("http2_prior_knowledge", &self.http2_prior_knowledge)
+            .field("root_certificates", &self.root_certificates.len())
+            .field(
+                "built_in_root_certificates",
+                &self.built_in_root_certificates,
+            )
+            .field("identity", &self.identity)
+            .field("user_agent", &self.user_agent)
+            .field("proxies", &self.proxies)
+            .field("system_proxy", &self.system_proxy)
+            .field("fixture_registry", &self.fixture_registry.is_some())
+            .finish()
+    }
+}
+
+impl ReqwestTransportBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Idle connections kept per host.
+    pub fn with_pool_max_idle_per_host(mut self, max_idle: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max_idle);
+        self
+    }
+
+    /// How long an idle pooled connection is kept; `None` keeps it indefinitely.
+    pub fn with_pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    pub fn with_tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = Some(nodelay);
+        self
+    }
+
+    /// Timeout for establishing a connection, separate from the per-request timeout.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Speak HTTP/2 without ALPN or upgrade negotiation.
+    pub fn with_http2_prior_knowledge(mut self) -> Self {
+        self.http2_prior_knowledge = true;
+        self
+    }
+
+    pub fn with_root_certificate(mut self, certificate: RestCertificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Whether the bundled web PKI roots are trusted; disable to trust only added roots.
+    pub fn with_built_in_root_certificates(mut self, enabled: bool) -> Self {
+        self.built_in_root_certificates = Some(enabled);
+        self
+    }
+
+    pub fn with_client_identity(mut self, identity: RestClientIdentity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn with_proxy(mut self, proxy: RestProxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Whether `HTTP_PROXY`/`HTTPS_PROXY`/`ALL_PROXY` from the environment are honoured.
+    pub fn with_system_proxy(mut self, enabled: bool) -> Self {
+        self.system_proxy = Some(enabled);
+        self
+    }
+
+    pub fn with_fixture_registry(mut self, registry: RestFixtureRegistry) -> Self {
+        self.fixture_registry = Some(registry);
+        self
+    }
+
+    pub fn build(self) -> RestResult<ReqwestTransport> {
+        let mut builder = ClientBuilder::new();
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(interval) = self.tcp_keepalive {
+            builder = builder.tcp_keepalive(interval);
+        }
+        if let Some(nodelay) = self.tcp_nodelay {
+            builder = builder.tcp_nodelay(nodelay);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        for certificate in &self.root_certificates {
+            for certificate in certificate.to_reqwest()? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        if let Some(enabled) = self.built_in_root_certificates {
+            builder = builder.tls_built_in_root_certs(enabled);
+        }
+        if let Some(identity) = &self.identity {
+            builder = builder.identity(identity.to_reqwest()?);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if self.system_proxy == Some(false) {
+            builder = builder.no_proxy();
+        }
+        for proxy in &self.proxies {
+            builder = builder.proxy(proxy.to_reqwest()?);
+        }
+        let client = builder
+            .build()
+            .map_err(|err| RestError::internal(format!("failed to build reqwest client: {err}")))?;
+        let transport = ReqwestTransport::with_client(client);
+        Ok(match self.fixture_registry {
+            Some(registry) => transport.with_fixture_registry(registry),
+            None => transport,
+        })
+    }
+}
@@ -0,0 +1,161 @@
+//! Helpers shared by the integration tests. Each test binary compiles its own copy and uses a
+//! subset, hence the `dead_code` allowance.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use shared_restapi::{
+    Client, ReqwestTransport, RestFixture, RestFixtureCaptureSelection, RestFixtureProvenance,
+    RestFixtureRegistry, RestFixtureRequirement, RestRequest, RestResponse,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// An empty temp directory unique to this test binary, process and `name`.
+pub fn fixture_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "shared-restapi-{}-{}-{name}",
+        env!("CARGO_CRATE_NAME"),
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create fixture dir");
+    dir
+}
+
+/// A live-capture fixture of `request` answered with `status` and `body`.
+pub fn live_fixture(
+    contract_id: &str,
+    request: &RestRequest,
+    status: u16,
+    body: &'static str,
+) -> RestFixture {
+    let response = RestResponse::new(status, Vec::new(), body, Duration::ZERO);
+    RestFixture::from_exchange(
+        contract_id,
+        request,
+        &response,
+        RestFixtureProvenance::live_capture("capture", "deribit_testnet"),
+    )
+}
+
+/// Save a live-capture fixture of `GET url` answered with `status` and `body`.
+pub fn write_fixture(path: &Path, contract_id: &str, url: &str, status: u16, body: &'static str) {
+    let request = RestRequest::get(url).with_fixture_contract(contract_id);
+    live_fixture(contract_id, &request, status, body)
+        .save(path)
+        .expect("write fixture");
+}
+
+/// A contract at `dir/<contract_id>/{success,error}.json` for
+/// `https://api.example.com/v1/<contract_id>`, failing with `400 {"code":10001}`.
+pub fn contract(
+    dir: &Path,
+    contract_id: &str,
+    success_body: &'static str,
+) -> RestFixtureRequirement {
+    let url = format!("https://api.example.com/v1/{contract_id}");
+    let requirement = RestFixtureRequirement::new(
+        contract_id,
+        dir.join(contract_id).join("success.json"),
+        dir.join(contract_id).join("error.json"),
+    );
+    write_fixture(
+        &requirement.success_path,
+        contract_id,
+        &url,
+        200,
+        success_body,
+    );
+    write_fixture(
+        &requirement.error_path,
+        contract_id,
+        &url,
+        400,
+        r#"{"code":10001}"#,
+    );
+    requirement
+}
+
+/// A request read by [`serve_once`].
+pub struct LocalRequest {
+    /// Request line and headers as sent.
+    pub head: String,
+    pub body: Vec<u8>,
+}
+
+/// Accept one connection on a local port, read one request (body by `content-length`), then
+/// write the raw HTTP response `parts`, pausing `pause` after each. Returns the server's base
+/// URL (`http://127.0.0.1:<port>`) and the request it read.
+pub async fn serve_once(parts: &[&[u8]], pause: Duration) -> (String, JoinHandle<LocalRequest>) {
+    let parts = parts.iter().map(|part| part.to_vec()).collect::<Vec<_>>();
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind local listener");
+    let addr = listener.local_addr().expect("listener address");
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.expect("accept connection");
+        let mut request = Vec::new();
+        let mut buf = [0_u8; 4096];
+        let head_len = loop {
+            if let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                break end + 4;
+            }
+            let read = stream.read(&mut buf).await.expect("read request");
+            if read == 0 {
+                break request.len();
+            }
+            request.extend_from_slice(&buf[..read]);
+        };
+        let head = String::from_utf8_lossy(&request[..head_len]).into_owned();
+        let body_len = head
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                if !name.eq_ignore_ascii_case("content-length") {
+                    return None;
+                }
+                value.trim().parse::<usize>().ok()
+            })
+            .unwrap_or(0);
+        while request.len() < head_len + body_len {
+            let read = stream.read(&mut buf).await.expect("read body");
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..read]);
+        }
+        // The client may hang up early, e.g. once a body exceeds `max_body_bytes`.
+        for part in parts {
+            if stream.write_all(&part).await.is_err() || stream.flush().await.is_err() {
+                break;
+            }
+            tokio::time::sleep(pause).await;
+        }
+        LocalRequest {
+            head,
+            body: request[head_len..].to_vec(),
+        }
+    });
+    (format!("http://{addr}"), server)
+}
+
+/// A scoped registry that lets every contract through the live gate.
+pub fn open_registry() -> RestFixtureRegistry {
+    let registry = RestFixtureRegistry::new();
+    registry.set_capture_selection(Some(RestFixtureCaptureSelection::All));
+    registry
+}
+
+/// A client on a real `ReqwestTransport` that ignores system proxies and gates nothing.
+pub fn live_client() -> Client {
+    Client::with_transport(
+        ReqwestTransport::builder()
+            .with_system_proxy(false)
+            .with_fixture_registry(open_registry())
+            .build()
+            .expect("transport builds"),
+    )
+}
@@ -0,0 +1,401 @@
+mod common;
+
+use std::time::Duration;
+
+#[cfg(feature = "gzip")]
+use common::{live_client, serve_once};
+use shared_restapi::{
+    Client, MockChunk, MockResponse, MockRestAdapter, RestContentEncoding, RestRequest,
+};
+#[cfg(feature = "gzip")]
+use shared_restapi::{MockRoute, RestBytes, RestError, RestErrorKind, RestRedactionRules};
+#[cfg(feature = "gzip")]
+use sonic_rs::{JsonContainerTrait, Value, json};
+
+const URL: &str = "https://api.example.com/v1/instruments";
+
+fn instruments_body() -> Vec<u8> {
+    let mut body = b"[".to_vec();
+    for index in 0..200 {
+        if index > 0 {
+            body.push(b',');
+        }
+        body.extend_from_slice(
+            format!("{{\"symbol\":\"BTC-{index}\",\"tick_size\":0.5,\"kind\":\"future\"}}")
+                .as_bytes(),
+        );
+    }
+    body.push(b']');
+    body
+}
+
+#[tokio::test]
+async fn enabled_encodings_are_decoded_with_wire_and_decoded_sizes() {
+    let body = instruments_body();
+    for encoding in RestContentEncoding::ALL
+        .into_iter()
+        .filter(|encoding| encoding.is_enabled())
+    {
+        let adapter = MockRestAdapter::new();
+        adapter.queue_get_response(
+            URL,
+            MockResponse::new(200, body.clone())
+                .with_header("content-type", "application/json")
+                .with_content_encoding(encoding)
+                .expect("mock body encodes"),
+        );
+        let response = Client::with_transport(adapter.clone())
+            .get_response(RestRequest::get(URL))
+            .await
+            .expect("mock responds");
+
+        assert_eq!(response.body(), &body[..], "{encoding} decodes");
+        assert_eq!(response.decoded_size(), body.len() as u64);
+        assert!(
+            response.wire_size() < response.decoded_size(),
+            "{encoding}: wire {} >= decoded {}",
+            response.wire_size(),
+            response.decoded_size()
+        );
+        assert_eq!(response.header("content-encoding"), None);
+        assert_eq!(response.content_type(), Some("application/json"));
+        assert_eq!(
+            adapter.inbound_responses()[0].encoded_size,
+            response.encoded_size
+        );
+    }
+}
+
+#[tokio::test]
+async fn unencoded_and_unsupported_bodies_pass_through() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(URL, MockResponse::text(200, "plain"));
+    adapter.queue_get_response(
+        URL,
+        MockResponse::text(200, "opaque").with_header("content-encoding", "compress"),
+    );
+    let client = Client::with_transport(adapter);
+
+    let plain = client
+        .get_response(RestRequest::get(URL))
+        .await
+        .expect("plain body");
+    assert_eq!(plain.encoded_size, None);
+    assert_eq!(plain.wire_size(), 5);
+    assert_eq!(plain.decoded_size(), 5);
+
+    let opaque = client
+        .get_response(RestRequest::get(URL))
+        .await
+        .expect("unsupported encoding");
+    assert_eq!(opaque.body(), b"opaque");
+    assert_eq!(opaque.header("content-encoding"), Some("compress"));
+    assert_eq!(opaque.encoded_size, None);
+}
+
+#[tokio::test]
+async fn streamed_encoded_bodies_decode_incrementally() {
+    let body = instruments_body();
+    for encoding in RestContentEncoding::ALL
+        .into_iter()
+        .filter(|encoding| encoding.is_enabled())
+    {
+        let third = body.len() / 3;
+        let chunks = [
+            MockChunk::new(body[..third].to_vec()),
+            MockChunk::new(body[third..2 * third].to_vec()).with_delay(Duration::from_millis(5)),
+            MockChunk::new(body[2 * third..].to_vec()),
+        ];
+        let adapter = MockRestAdapter::new();
+        adapter.queue_get_response(
+            URL,
+            MockResponse::chunked(200, chunks)
+                .with_content_encoding(encoding)
+                .expect("mock body encodes"),
+        );
+        let client = Client::with_transport(adapter);
+
+        let mut stream = client
+            .get_stream(RestRequest::get(URL))
+            .await
+            .expect("stream opens");
+        let mut decoded = Vec::new();
+        while let Some(chunk) = stream.body.next_chunk().await.expect("chunk decodes") {
+            decoded.extend_from_slice(&chunk);
+        }
+        assert_eq!(decoded, body, "{encoding} streams");
+        assert_eq!(stream.body.received_bytes(), body.len() as u64);
+    }
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn max_body_bytes_limits_the_decoded_size() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        URL,
+        MockResponse::new(200, vec![b'a'; 64 * 1024])
+            .with_content_encoding(RestContentEncoding::Gzip)
+            .expect("mock body encodes"),
+    );
+    let err = Client::with_transport(adapter)
+        .get_response(RestRequest::get(URL).with_max_body_bytes(4096))
+        .await
+        .expect_err("decoded body exceeds the limit");
+    assert_eq!(err.kind(), RestErrorKind::BodyTooLarge);
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn corrupt_bodies_fail_with_a_parse_error() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        URL,
+        MockResponse::text(200, "not gzip at all").with_header("content-encoding", "gzip"),
+    );
+    let err = Client::with_transport(adapter.clone())
+        .get_response(RestRequest::get(URL))
+        .await
+        .expect_err("corrupt gzip is rejected");
+    assert_eq!(err.kind(), RestErrorKind::Parse);
+    assert_eq!(err.status(), Some(200));
+    assert!(!err.is_retryable());
+    assert!(
+        err.to_string()
+            .starts_with("failed to decode gzip response body:")
+    );
+    assert!(adapter.snapshot().last_error.is_some());
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn reqwest_advertises_and_decodes_enabled_encodings() {
+    let body = instruments_body();
+    let encoded = RestContentEncoding::Gzip
+        .encode(&body)
+        .expect("gzip encodes");
+    let wire_len = encoded.len();
+    let head = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-encoding: gzip\r\ncontent-length: {wire_len}\r\nconnection: close\r\n\r\n"
+    );
+    let (url, server) = serve_once(&[head.as_bytes(), &encoded], Duration::ZERO).await;
+
+    let response = live_client()
+        .get_response(RestRequest::get(format!("{url}/")).with_fixture_contract("instruments"))
+        .await
+        .expect("live response");
+
+    let request = server.await.expect("server task").head.to_ascii_lowercase();
+    let accept = RestContentEncoding::accept_encoding().expect("gzip is enabled");
+    assert!(
+        request.contains(&format!("accept-encoding: {accept}\r\n")),
+        "request advertises {accept}: {request}"
+    );
+    assert_eq!(response.body(), &body[..]);
+    assert_eq!(response.wire_size(), wire_len as u64);
+    assert_eq!(response.decoded_size(), body.len() as u64);
+    assert_eq!(response.content_length(), None);
+    let parsed: Value = response.json().expect("decoded body parses");
+    assert_eq!(parsed.as_array().map(|items| items.len()), Some(200));
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn decompression_bombs_stop_at_max_body_bytes() {
+    // 64 MiB of zeros gzip to roughly 64 KiB on the wire.
+    let bomb = vec![0_u8; 64 * 1024 * 1024];
+    let encoded = RestContentEncoding::Gzip
+        .encode(&bomb)
+        .expect("gzip encodes");
+    drop(bomb);
+    let assert_stopped_early = |err: RestError| {
+        let RestError::BodyTooLarge {
+            limit,
+            received,
+            status,
+        } = err
+        else {
+            panic!("expected BodyTooLarge, got {err:?}");
+        };
+        assert_eq!(limit, 4096);
+        assert_eq!(status, Some(200));
+        assert!(
+            received < 4 * 1024 * 1024,
+            "decoding stops soon after the limit, not after {received} bytes"
+        );
+    };
+
+    let head = format!(
+        "HTTP/1.1 200 OK\r\ncontent-encoding: gzip\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+        encoded.len()
+    );
+    let (url, server) = serve_once(&[head.as_bytes(), &encoded], Duration::ZERO).await;
+    let err = live_client()
+        .get_response(
+            RestRequest::get(format!("{url}/"))
+                .with_fixture_contract("instruments")
+                .with_max_body_bytes(4096),
+        )
+        .await
+        .expect_err("bomb exceeds the limit");
+    server.await.expect("server task");
+    assert_stopped_early(err);
+
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        URL,
+        MockResponse::new(200, encoded).with_header("content-encoding", "gzip"),
+    );
+    let err = Client::with_transport(adapter)
+        .get_response(RestRequest::get(URL).with_max_body_bytes(4096))
+        .await
+        .expect_err("bomb exceeds the limit");
+    assert_stopped_early(err);
+}
+
+#[cfg(feature = "gzip")]
+const AMEND: &str = "https://api.example.com/v1/orders/amend";
+
+#[cfg(feature = "gzip")]
+fn amend_body(orders: usize) -> RestBytes {
+    let amends = (0..orders)
+        .map(|index| format!("{{\"order_id\":\"ord-{index}\",\"price\":101.5,\"qty\":2}}"))
+        .collect::<Vec<_>>()
+        .join(",");
+    RestBytes::from(format!("{{\"account\":\"acct-7\",\"amends\":[{amends}]}}"))
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn request_bodies_above_the_threshold_are_compressed() {
+    let adapter = MockRestAdapter::new();
+    let client = Client::with_transport(adapter.clone());
+    let bulk = amend_body(100);
+    let single = amend_body(1);
+    for body in [bulk.clone(), single.clone()] {
+        client
+            .get_response(
+                RestRequest::post(AMEND)
+                    .with_body(body)
+                    .with_body_compression(RestContentEncoding::Gzip, 1024),
+            )
+            .await
+            .expect("mock responds");
+    }
+
+    let outbound = adapter.outbound_requests();
+    let sent = outbound[0].body.as_deref().expect("bulk body");
+    assert_eq!(&sent[..2], &[0x1f, 0x8b], "gzip magic");
+    assert!(sent.len() < bulk.len());
+    assert_eq!(
+        outbound[0]
+            .headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("content-encoding"))
+            .map(|(_, value)| value.as_ref())
+            .collect::<Vec<_>>(),
+        vec![&b"gzip"[..]]
+    );
+    assert_eq!(outbound[0].decoded_body().expect("decodes"), Some(bulk));
+
+    // Below the threshold the body goes out untouched.
+    assert_eq!(outbound[1].body.as_ref(), Some(&single));
+    assert_eq!(outbound[1].decoded_body().expect("plain"), Some(single));
+
+    adapter
+        .expect(MockRoute::post(AMEND))
+        .times(2)
+        .with_json_body(json!({"account": "acct-7"}))
+        .verify()
+        .expect("expectations see the decoded body");
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn redaction_runs_before_request_compression() {
+    let adapter = MockRestAdapter::new()
+        .with_redaction(RestRedactionRules::none().with_json_pointer("/account"));
+    Client::with_transport(adapter.clone())
+        .get_response(
+            RestRequest::post(AMEND)
+                .with_body(amend_body(50))
+                .with_body_compression(RestContentEncoding::Gzip, 0),
+        )
+        .await
+        .expect("mock responds");
+
+    let logged = adapter.outbound_requests().remove(0);
+    let decoded = logged.decoded_body().expect("decodes").expect("body");
+    let decoded = std::str::from_utf8(&decoded).expect("utf-8");
+    assert!(decoded.contains("\"account\":\"[REDACTED]\""), "{decoded}");
+    assert!(!decoded.contains("acct-7"));
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn reqwest_sends_compressed_request_bodies() {
+    let (url, server) = serve_once(
+        &[b"HTTP/1.1 204 No Content\r\nconnection: close\r\n\r\n"],
+        Duration::ZERO,
+    )
+    .await;
+
+    let body = amend_body(100);
+    let response = live_client()
+        .get_response(
+            RestRequest::post(format!("{url}/amend"))
+                .with_fixture_contract("amend")
+                .with_body(body.clone())
+                .with_body_compression(RestContentEncoding::Gzip, 1024),
+        )
+        .await
+        .expect("live response");
+    assert_eq!(response.status(), 204);
+
+    let request = server.await.expect("server task");
+    let head = request.head.to_ascii_lowercase();
+    let sent = request.body;
+    assert!(head.contains("content-encoding: gzip\r\n"), "{head}");
+    assert!(sent.len() < body.len());
+    assert_eq!(
+        RestContentEncoding::Gzip
+            .decode(&sent)
+            .expect("server decodes"),
+        body
+    );
+}
+
+#[tokio::test]
+async fn truncated_bodies_fail_to_decode() {
+    let body = instruments_body();
+    for encoding in RestContentEncoding::ALL
+        .into_iter()
+        .filter(|encoding| encoding.is_enabled())
+    {
+        let encoded = encoding.encode(&body).expect("body encodes");
+        assert_eq!(
+            encoding.decode(&encoded).expect("whole body decodes"),
+            body,
+            "{encoding}"
+        );
+        let err = encoding
+            .decode(&encoded[..encoded.len() - 6])
+            .expect_err("truncated body is rejected");
+        assert!(
+            err.to_string()
+                .starts_with(&format!("failed to decode {encoding} response body:")),
+            "{encoding}: {err}"
+        );
+
+        let adapter = MockRestAdapter::new();
+        adapter.queue_get_response(
+            URL,
+            MockResponse::new(200, encoded[..encoded.len() - 6].to_vec())
+                .with_header("content-encoding", encoding.to_string()),
+        );
+        Client::with_transport(adapter)
+            .get_response(RestRequest::get(URL))
+            .await
+            .expect_err("truncated body is rejected");
+    }
+}
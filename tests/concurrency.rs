@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use shared_restapi::{
+    Client, MockResponse, MockRestAdapter, RestBytes, RestConcurrencyLimits, RestError,
+    RestErrorKind, RestFuture, RestRequest, RestResponse, RestResult, RestTransport,
+};
+
+/// Answers every request after `delay`, tracking the peak number in flight per host and overall.
+#[derive(Clone, Default)]
+struct SlowTransport {
+    delay: Duration,
+    state: Arc<Mutex<InFlight>>,
+}
+
+#[derive(Default)]
+struct InFlight {
+    total: usize,
+    peak: usize,
+    hosts: HashMap<String, (usize, usize)>,
+}
+
+impl SlowTransport {
+    fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            ..Self::default()
+        }
+    }
+
+    fn peak(&self) -> usize {
+        self.state.lock().expect("slow transport state").peak
+    }
+
+    fn host_peak(&self, host: &str) -> usize {
+        self.state.lock().expect("slow transport state").hosts[host].1
+    }
+}
+
+impl RestTransport for SlowTransport {
+    fn execute(&self, request: RestRequest) -> RestFuture<RestResult<RestResponse>> {
+        let transport = self.clone();
+        Box::pin(async move {
+            let host = request
+                .url
+                .split('/')
+                .nth(2)
+                .unwrap_or_default()
+                .to_string();
+            {
+                let mut state = transport.state.lock().expect("slow transport state");
+                state.total += 1;
+                state.peak = state.peak.max(state.total);
+                let (current, peak) = state.hosts.entry(host.clone()).or_default();
+                *current += 1;
+                *peak = (*peak).max(*current);
+            }
+            tokio::time::sleep(transport.delay).await;
+            {
+                let mut state = transport.state.lock().expect("slow transport state");
+                state.total -= 1;
+                state.hosts.get_mut(&host).expect("host counted on entry").0 -= 1;
+            }
+            Ok(RestResponse::new(
+                200,
+                Vec::new(),
+                RestBytes::from_static(b"{\"ok\":true}"),
+                transport.delay,
+            ))
+        })
+    }
+}
+
+async fn fan_out(client: &Client, urls: impl IntoIterator<Item = String>) -> Vec<RestResponse> {
+    let tasks = urls
+        .into_iter()
+        .map(|url| {
+            let client = client.clone();
+            tokio::spawn(async move { client.get_checked_response(RestRequest::get(url)).await })
+        })
+        .collect::<Vec<_>>();
+    let mut responses = Vec::new();
+    for task in tasks {
+        responses.push(task.await.expect("task joins").expect("request succeeds"));
+    }
+    responses
+}
+
+#[tokio::test]
+async fn per_host_and_global_limits_bound_in_flight_requests() {
+    let transport = SlowTransport::new(Duration::from_millis(20));
+    let client = Client::with_transport(transport.clone()).with_concurrency_limits(
+        RestConcurrencyLimits::new()
+            .with_per_host_limit(2)
+            .with_host_limit("slow.example.com", 1)
+            .with_global_limit(3),
+    );
+
+    let urls = (0..12).map(|i| match i % 3 {
+        0 => format!("https://a.example.com/v1/orders/{i}"),
+        1 => format!("https://b.example.com/v1/orders/{i}"),
+        _ => format!("https://slow.example.com/v1/orders/{i}"),
+    });
+    let responses = fan_out(&client, urls).await;
+
+    assert_eq!(responses.len(), 12);
+    assert!(transport.peak() <= 3, "global peak {}", transport.peak());
+    assert!(transport.host_peak("a.example.com") <= 2);
+    assert!(transport.host_peak("b.example.com") <= 2);
+    assert_eq!(transport.host_peak("slow.example.com"), 1);
+    assert!(
+        responses
+            .iter()
+            .any(|response| response.queue_wait >= Duration::from_millis(15))
+    );
+}
+
+#[tokio::test]
+async fn queue_wait_beyond_the_budget_is_a_typed_error() {
+    let transport = SlowTransport::new(Duration::from_millis(200));
+    let client = Client::with_transport(transport).with_concurrency_limits(
+        RestConcurrencyLimits::new()
+            .with_per_host_limit(1)
+            .with_queue_timeout(Duration::from_millis(20)),
+    );
+
+    let busy = {
+        let client = client.clone();
+        tokio::spawn(async move {
+            client
+                .get_response(RestRequest::get("https://api.example.com/v1/a"))
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let err = client
+        .execute_json_checked::<sonic_rs::Value>(RestRequest::get("https://api.example.com/v1/b"))
+        .await
+        .expect_err("queue is full");
+
+    assert_eq!(err.kind(), RestErrorKind::QueueTimeout);
+    assert!(!err.is_retryable());
+    let RestError::QueueTimeout {
+        host,
+        waited,
+        timeout,
+    } = &err
+    else {
+        panic!("unexpected error {err:?}");
+    };
+    assert_eq!(host.as_deref(), Some("api.example.com"));
+    assert!(*waited >= *timeout);
+    assert_eq!(*timeout, Duration::from_millis(20));
+
+    let first = busy.await.expect("task joins").expect("first request");
+    assert!(first.queue_wait < Duration::from_millis(20));
+}
+
+#[tokio::test]
+async fn streamed_bodies_hold_their_slot_until_read() {
+    let url = "https://api.example.com/v1/export";
+    let adapter = MockRestAdapter::new();
+    for _ in 0..3 {
+        adapter.queue_get_response(url, MockResponse::text(200, "a,b\n1,2\n"));
+    }
+    let client = Client::with_transport(adapter).with_concurrency_limits(
+        RestConcurrencyLimits::new()
+            .with_global_limit(1)
+            .with_queue_timeout(Duration::from_millis(10)),
+    );
+
+    let open = client
+        .get_stream(RestRequest::get(url))
+        .await
+        .expect("first stream");
+    let err = client
+        .get_stream(RestRequest::get(url))
+        .await
+        .expect_err("slot is held by the open body");
+    assert!(matches!(err, RestError::QueueTimeout { host: None, .. }));
+
+    assert_eq!(
+        open.body.read_to_end().await.expect("body reads").as_ref(),
+        b"a,b\n1,2\n"
+    );
+    let response = client
+        .get_response(RestRequest::get(url))
+        .await
+        .expect("slot freed");
+    assert_eq!(response.status(), 200);
+}
@@ -492,12 +492,12 @@ async fn allocation_profile_is_measurable_for_execute_json_checked() {
 
     reset_alloc_counter();
     let payload = b"[1,2,3,4,5,6,7,8,9,10]";
-    let response = RestResponse {
-        status: 200,
-        headers: Vec::new(),
-        body: Bytes::from_static(payload),
-        elapsed: std::time::Duration::from_millis(0),
-    };
+    let response = RestResponse::new(
+        200,
+        Vec::new(),
+        Bytes::from_static(payload),
+        std::time::Duration::from_millis(0),
+    );
     let parsed = response
         .json::<Vec<u32>>()
         .expect("json parse should succeed");
@@ -520,6 +520,53 @@ async fn allocation_profile_is_measurable_for_execute_json_checked() {
     );
 }
 
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn allocation_profile_is_measurable_for_gzip_encoded_execute_json() {
+    let mut payload = b"{\"ok\":true,\"n\":[".to_vec();
+    for index in 0..2048 {
+        if index > 0 {
+            payload.push(b',');
+        }
+        payload.extend_from_slice(index.to_string().as_bytes());
+    }
+    payload.extend_from_slice(b"]}");
+    let adapter = MockRestAdapter::new();
+    adapter.queue_response(MockResponse::new(200, payload.clone()));
+    adapter.queue_response(
+        MockResponse::new(200, payload.clone())
+            .with_content_encoding(shared_restapi::RestContentEncoding::Gzip)
+            .expect("gzip encodes"),
+    );
+    let client = Client::with_transport(adapter);
+
+    reset_alloc_counter();
+    let plain = client
+        .execute_json::<Value>(RestRequest::get("https://api.example.com/alloc"))
+        .await
+        .expect("plain parse should succeed");
+    let plain_allocation_count = take_allocs();
+
+    reset_alloc_counter();
+    let decoded = client
+        .execute_json::<Value>(RestRequest::get("https://api.example.com/alloc"))
+        .await
+        .expect("gzip parse should succeed");
+    let gzip_allocation_count = take_allocs();
+    assert_eq!(plain, decoded);
+    assert_eq!(decoded["n"].as_array().map(|items| items.len()), Some(2048));
+
+    eprintln!(
+        "allocation profile: execute_json plain={plain_allocation_count}, gzip={gzip_allocation_count} ({} body bytes)",
+        payload.len()
+    );
+    // Decoding adds the decoder state and output growth, not per-byte work.
+    assert!(
+        gzip_allocation_count <= plain_allocation_count + 64,
+        "gzip path should add a bounded number of allocations: plain {plain_allocation_count}, gzip {gzip_allocation_count}"
+    );
+}
+
 #[derive(Clone)]
 struct HeaderHeavyTransport {
     body: Bytes,
@@ -542,12 +589,12 @@ impl RestTransport for HeaderHeavyTransport {
             .collect();
         Box::pin(async move {
             let _ = request;
-            Ok(RestResponse {
-                status: 200,
+            Ok(RestResponse::new(
+                200,
                 headers,
                 body,
-                elapsed: std::time::Duration::from_millis(0),
-            })
+                std::time::Duration::from_millis(0),
+            ))
         })
     }
 
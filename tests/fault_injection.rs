@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use shared_restapi::{
+    Client, FaultInjectingTransport, FaultInjectionConfig, InjectedFault, MockResponse,
+    MockRestAdapter, RestErrorKind, RestRequest,
+};
+use sonic_rs::Value;
+
+const URL: &str = "https://api.example.com/v1/chaos";
+const BODY: &str = r#"{"symbol":"BTC-PERPETUAL","bids":[[1.0,2.0]],"asks":[[3.0,4.0]]}"#;
+
+fn chaos(seed: u64) -> FaultInjectingTransport<MockRestAdapter> {
+    let adapter = MockRestAdapter::new();
+    for _ in 0..64 {
+        adapter.queue_response(MockResponse::text(200, BODY));
+    }
+    FaultInjectingTransport::new(
+        adapter,
+        FaultInjectionConfig::new(seed)
+            .with_latency(0.2, Duration::from_micros(10), Duration::from_micros(50))
+            .with_connect_errors(0.1)
+            .with_receive_errors(0.1)
+            .with_server_errors(0.1, [502, 503])
+            .with_truncated_bodies(0.1)
+            .with_corrupted_json(0.1),
+    )
+}
+
+async fn soak(transport: &FaultInjectingTransport<MockRestAdapter>) -> Vec<String> {
+    let client = Client::with_transport(transport.clone());
+    let mut outcomes = Vec::new();
+    for _ in 0..48 {
+        let outcome = match client
+            .execute_json_checked::<Value>(RestRequest::get(URL))
+            .await
+        {
+            Ok(_) => "ok".to_string(),
+            Err(err) => format!("{:?}/{:?}", err.kind(), err.status()),
+        };
+        outcomes.push(outcome);
+    }
+    outcomes
+}
+
+#[tokio::test]
+async fn same_seed_reproduces_the_same_fault_sequence() {
+    let first = chaos(42);
+    let second = chaos(42);
+    let first_outcomes = soak(&first).await;
+    let second_outcomes = soak(&second).await;
+
+    assert_eq!(first_outcomes, second_outcomes);
+    assert_eq!(first.injected_faults(), second.injected_faults());
+    assert!(!first.injected_faults().is_empty());
+    assert!(first_outcomes.iter().any(|outcome| outcome == "ok"));
+
+    let other = chaos(7);
+    soak(&other).await;
+    assert_ne!(first.injected_faults(), other.injected_faults());
+
+    first.reset();
+    assert!(first.injected_faults().is_empty());
+}
+
+#[tokio::test]
+async fn certain_faults_map_to_typed_errors() {
+    let adapter = MockRestAdapter::new();
+    let transport = FaultInjectingTransport::new(
+        adapter.clone(),
+        FaultInjectionConfig::new(1).with_connect_errors(1.0),
+    );
+    let err = Client::with_transport(transport)
+        .execute_json::<Value>(RestRequest::get(URL))
+        .await
+        .expect_err("connect fault always fires");
+    assert_eq!(err.kind(), RestErrorKind::Connect);
+    assert_eq!(
+        adapter.snapshot().request_count,
+        0,
+        "connect faults must not reach the inner transport"
+    );
+
+    let transport = FaultInjectingTransport::new(
+        MockRestAdapter::new(),
+        FaultInjectionConfig::new(1).with_server_errors(1.0, [503]),
+    );
+    let err = Client::with_transport(transport)
+        .execute_json_checked::<Value>(RestRequest::get(URL))
+        .await
+        .expect_err("server fault always fires");
+    assert_eq!(err.kind(), RestErrorKind::Rejected);
+    assert_eq!(err.status(), Some(503));
+}
+
+#[tokio::test]
+async fn body_faults_break_json_decoding() {
+    for config in [
+        FaultInjectionConfig::new(3).with_truncated_bodies(1.0),
+        FaultInjectionConfig::new(3).with_corrupted_json(1.0),
+    ] {
+        let adapter = MockRestAdapter::new();
+        adapter.queue_response(MockResponse::text(200, BODY));
+        let transport = FaultInjectingTransport::new(adapter, config);
+        let err = Client::with_transport(transport.clone())
+            .execute_json::<Value>(RestRequest::get(URL))
+            .await
+            .expect_err("mangled body should not parse");
+        assert_eq!(err.kind(), RestErrorKind::Parse);
+
+        let faults = transport.injected_faults();
+        assert_eq!(faults.len(), 1);
+        assert!(matches!(
+            faults[0].faults[0],
+            InjectedFault::TruncatedBody { .. } | InjectedFault::CorruptedJson { .. }
+        ));
+    }
+}
@@ -0,0 +1,287 @@
+mod common;
+
+use common::fixture_dir;
+use shared_restapi::{
+    Client, MockResponse, MockRestAdapter, REST_FIXTURE_FORMAT_VERSION, RecordingTransport,
+    ReplayingTransport, RestFixture, RestFixtureBody, RestFixtureCaptureSelection,
+    RestFixtureProvenance, RestFixtureRegistry, RestFixtureRequest, RestFixtureRequirement,
+    RestFixtureResponse, RestRequest, register_required_rest_contracts,
+    validate_required_rest_contracts,
+};
+
+// Capture mode and the contract registry are process-wide, so tests in this binary serialize.
+static ENV_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+const URL: &str = "https://api.example.com/v1/instruments";
+
+fn set_capture_selection(selection: &str) {
+    // SAFETY: every test in this binary holds ENV_LOCK while touching the environment.
+    unsafe { std::env::set_var("SHARED_RESTAPI_FIXTURE_CAPTURE_MODE", selection) }
+}
+
+fn set_capture_mode(enabled: bool) {
+    // SAFETY: every test in this binary holds ENV_LOCK while touching the environment.
+    unsafe {
+        if enabled {
+            std::env::set_var("SHARED_RESTAPI_FIXTURE_CAPTURE_MODE", "1");
+        } else {
+            std::env::remove_var("SHARED_RESTAPI_FIXTURE_CAPTURE_MODE");
+        }
+    }
+}
+
+#[tokio::test]
+async fn capture_mode_records_compliant_fixtures_that_replay_through_the_mock() {
+    let _guard = ENV_LOCK.lock().await;
+    let dir = fixture_dir("roundtrip");
+    let requirement = RestFixtureRequirement::new(
+        "instruments",
+        dir.join("instruments/success.json"),
+        dir.join("instruments/error.json"),
+    );
+    register_required_rest_contracts([requirement.clone()]);
+
+    let live = MockRestAdapter::new();
+    live.queue_get_response(
+        URL,
+        MockResponse::text(200, r#"{"result":[{"name":"BTC-PERPETUAL"}]}"#)
+            .with_header("content-type", "application/json"),
+    );
+    live.queue_get_response(URL, MockResponse::text(400, r#"{"error":"bad currency"}"#));
+    let recorder = RecordingTransport::new(live)
+        .with_capture_command("cargo run --bin capture -- instruments")
+        .with_exchange_env("deribit_testnet");
+    let client = Client::with_transport(recorder.clone());
+    let request = RestRequest::get(URL).with_fixture_contract("instruments");
+
+    set_capture_mode(true);
+    client
+        .get_response(request.clone())
+        .await
+        .expect("success capture");
+    client
+        .get_response(request.clone())
+        .await
+        .expect("error capture");
+    set_capture_mode(false);
+
+    assert_eq!(
+        recorder.recorded_fixtures(),
+        vec![
+            requirement.success_path.clone(),
+            requirement.error_path.clone()
+        ]
+    );
+    validate_required_rest_contracts(std::slice::from_ref(&requirement))
+        .expect("recorded fixtures carry live provenance");
+    let written = RestFixture::load(&requirement.success_path).expect("success fixture loads");
+    assert_eq!(written.provenance.exchange_env, "deribit_testnet");
+    assert_eq!(written.request.url, URL);
+    assert_eq!(written.response.status, 200);
+
+    let replay = ReplayingTransport::from_requirements(&[requirement])
+        .expect("recorded fixtures load for replay");
+    let client = Client::with_transport(replay.clone());
+    let response = client
+        .get_response(request.clone())
+        .await
+        .expect("success fixture replays");
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.body(), br#"{"result":[{"name":"BTC-PERPETUAL"}]}"#);
+    assert_eq!(response.headers.len(), 1);
+
+    replay.serve_error("instruments");
+    let response = client
+        .get_response(request)
+        .await
+        .expect("error fixture replays");
+    assert_eq!(response.status(), 400);
+    assert_eq!(replay.adapter().snapshot().request_count, 2);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn recording_is_a_pass_through_outside_capture_mode() {
+    let _guard = ENV_LOCK.lock().await;
+    set_capture_mode(false);
+    let dir = fixture_dir("passthrough");
+    register_required_rest_contracts([RestFixtureRequirement::new(
+        "instruments",
+        dir.join("success.json"),
+        dir.join("error.json"),
+    )]);
+
+    let live = MockRestAdapter::new();
+    live.queue_get_response(URL, MockResponse::text(200, "{}"));
+    let recorder = RecordingTransport::new(live).with_exchange_env("deribit_testnet");
+    Client::with_transport(recorder.clone())
+        .get_response(RestRequest::get(URL).with_fixture_contract("instruments"))
+        .await
+        .expect("pass-through request succeeds");
+
+    assert!(recorder.recorded_fixtures().is_empty());
+    assert!(!dir.join("success.json").exists());
+}
+
+#[tokio::test]
+async fn typed_fixture_queues_into_the_mock_with_one_call() {
+    let fixture = RestFixture {
+        format_version: REST_FIXTURE_FORMAT_VERSION,
+        provenance: RestFixtureProvenance::live_capture("capture", "deribit_testnet"),
+        contract_id: "ticker".to_string(),
+        request: RestFixtureRequest {
+            method: "GET".to_string(),
+            url: URL.to_string(),
+            headers: Vec::new(),
+            body: None,
+        },
+        response: RestFixtureResponse {
+            status: 200,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: RestFixtureBody::Text(r#"{"last":1.5}"#.to_string()),
+        },
+    };
+    let adapter = MockRestAdapter::new();
+    adapter
+        .queue_fixture(&fixture)
+        .expect("fixture converts to a mock response");
+
+    let request = fixture.to_request().expect("fixture request rebuilds");
+    let response = Client::with_transport(adapter)
+        .get_response(request)
+        .await
+        .expect("queued fixture is served");
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.body(), br#"{"last":1.5}"#);
+}
+
+#[tokio::test]
+async fn scoped_registries_capture_and_gate_independently_of_the_global_one() {
+    let _guard = ENV_LOCK.lock().await;
+    let dir = fixture_dir("scoped");
+    register_required_rest_contracts([RestFixtureRequirement::new(
+        "global-only",
+        dir.join("global/success.json"),
+        dir.join("global/error.json"),
+    )]);
+    let registry = RestFixtureRegistry::from_requirements([RestFixtureRequirement::new(
+        "instruments",
+        dir.join("instruments/success.json"),
+        dir.join("instruments/error.json"),
+    )]);
+
+    let live = MockRestAdapter::new();
+    live.queue_get_response(URL, MockResponse::text(200, "{}"));
+    live.queue_get_response(URL, MockResponse::text(400, "{}"));
+    let recorder = RecordingTransport::new(live)
+        .with_exchange_env("deribit_testnet")
+        .with_fixture_registry(registry.clone());
+    let client = Client::with_transport(recorder.clone());
+    let request = RestRequest::get(URL).with_fixture_contract("instruments");
+    set_capture_mode(true);
+    client
+        .get_response(request.clone())
+        .await
+        .expect("success capture");
+    client
+        .get_response(request.clone())
+        .await
+        .expect("error capture");
+    set_capture_mode(false);
+    assert_eq!(recorder.recorded_fixtures().len(), 2);
+    registry
+        .validate()
+        .expect("scoped registry fixtures are compliant");
+
+    // The live gate consults the scoped registry before any network I/O.
+    let gated = Client::with_fixture_registry(registry.clone());
+    let err = gated
+        .get_response(RestRequest::get("http://127.0.0.1:9/").with_fixture_contract("global-only"))
+        .await
+        .expect_err("contract only in the global registry is blocked");
+    assert!(
+        err.to_string()
+            .contains("unregistered fixture contract global-only")
+    );
+
+    let replay = ReplayingTransport::from_registry(&registry).expect("scoped fixtures replay");
+    let response = Client::with_transport(replay)
+        .get_response(request)
+        .await
+        .expect("success fixture replays");
+    assert_eq!(response.status(), 200);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn capture_selection_records_only_selected_contracts_and_keeps_the_gate_for_others() {
+    let _guard = ENV_LOCK.lock().await;
+    let dir = fixture_dir("selection");
+    let registry = RestFixtureRegistry::from_requirements([
+        RestFixtureRequirement::new(
+            "instruments",
+            dir.join("instruments/success.json"),
+            dir.join("instruments/error.json"),
+        ),
+        RestFixtureRequirement::new(
+            "orders.open",
+            dir.join("orders/success.json"),
+            dir.join("orders/error.json"),
+        ),
+    ]);
+
+    let live = MockRestAdapter::new();
+    live.queue_get_response(URL, MockResponse::text(200, "{}"));
+    live.queue_get_response(URL, MockResponse::text(200, "{}"));
+    live.queue_get_response(URL, MockResponse::text(200, "{}"));
+    let recorder = RecordingTransport::new(live)
+        .with_exchange_env("deribit_testnet")
+        .with_fixture_registry(registry.clone());
+    let client = Client::with_transport(recorder.clone());
+
+    set_capture_selection("instr*");
+    client
+        .get_response(RestRequest::get(URL).with_fixture_contract("instruments"))
+        .await
+        .expect("selected contract captures");
+    client
+        .get_response(RestRequest::get(URL).with_fixture_contract("orders.open"))
+        .await
+        .expect("unselected contract passes through");
+    assert_eq!(
+        recorder.recorded_fixtures(),
+        vec![dir.join("instruments/success.json")]
+    );
+    let written =
+        RestFixture::load(dir.join("instruments/success.json")).expect("captured fixture loads");
+    assert_eq!(
+        written.provenance.capture_mode.as_deref(),
+        Some("contracts=instr*")
+    );
+
+    // The live gate still blocks contracts outside the selection.
+    let gated = Client::with_fixture_registry(registry.clone());
+    let err = gated
+        .get_response(RestRequest::get("http://127.0.0.1:9/").with_fixture_contract("orders.open"))
+        .await
+        .expect_err("unselected contract without fixtures is blocked");
+    assert!(err.to_string().contains("live REST request blocked"));
+    set_capture_mode(false);
+
+    // A registry-level selection overrides the environment.
+    registry.set_capture_selection(Some(RestFixtureCaptureSelection::contracts(["orders.*"])));
+    client
+        .get_response(RestRequest::get(URL).with_fixture_contract("orders.open"))
+        .await
+        .expect("override-selected contract captures");
+    assert_eq!(
+        recorder.recorded_fixtures().last(),
+        Some(&dir.join("orders/success.json"))
+    );
+    registry.set_capture_selection(None);
+    assert!(!registry.capture_enabled_for("orders.open"));
+
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{contract, fixture_dir, write_fixture};
+use shared_restapi::{
+    RestFixtureRequirement, discover_rest_fixture_contracts, load_rest_fixture_manifest,
+};
+
+#[test]
+fn discovery_follows_the_fixture_naming_convention() {
+    let dir = fixture_dir("discover");
+    contract(&dir, "ticker", "{}");
+    contract(&dir, "instruments", "{}");
+    write_fixture(
+        &dir.join("half/success.json"),
+        "half",
+        "https://api.example.com/v1/half",
+        200,
+        "{}",
+    );
+
+    let discovered = discover_rest_fixture_contracts(&dir).expect("discover fixtures");
+    assert_eq!(
+        discovered,
+        vec![
+            RestFixtureRequirement::new(
+                "instruments",
+                dir.join("instruments/success.json"),
+                dir.join("instruments/error.json"),
+            ),
+            RestFixtureRequirement::new(
+                "ticker",
+                dir.join("ticker/success.json"),
+                dir.join("ticker/error.json"),
+            ),
+        ]
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn json_manifest_resolves_paths_policies_and_discovered_contracts() {
+    let dir = fixture_dir("json");
+    let fixtures = dir.join("test/fixtures");
+    contract(&fixtures, "instruments", "{}");
+    contract(&fixtures, "book", "{}");
+    let manifest = dir.join("test/fixtures.json");
+    std::fs::write(
+        &manifest,
+        r#"{
+            "fixtures_dir": "fixtures",
+            "discover": true,
+            "policy": { "max_age_secs": 600, "allowed_exchange_envs": ["deribit_testnet"] },
+            "contracts": [
+                { "contract_id": "instruments" },
+                {
+                    "contract_id": "ticker",
+                    "success": "captures/ticker-ok.json",
+                    "error": "captures/ticker-bad.json",
+                    "policy": { "capture_command_prefix": "cargo run" }
+                }
+            ]
+        }"#,
+    )
+    .expect("write manifest");
+
+    let requirements = load_rest_fixture_manifest(&manifest).expect("manifest loads");
+    let ids = requirements
+        .iter()
+        .map(|requirement| requirement.contract_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["instruments", "ticker", "book"]);
+
+    assert_eq!(
+        requirements[0].success_path,
+        fixtures.join("instruments/success.json")
+    );
+    assert_eq!(
+        requirements[0].policy().max_age,
+        Some(Duration::from_secs(600))
+    );
+    assert_eq!(
+        requirements[1].error_path,
+        dir.join("test/captures/ticker-bad.json")
+    );
+    assert_eq!(requirements[1].policy().max_age, None);
+    assert_eq!(
+        requirements[1].policy().capture_command_prefix.as_deref(),
+        Some("cargo run")
+    );
+    assert_eq!(
+        requirements[2].policy().allowed_exchange_envs,
+        vec!["deribit_testnet".to_string()]
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn manifest_rejects_duplicate_contracts() {
+    let dir = fixture_dir("duplicate");
+    let manifest = dir.join("fixtures.json");
+    std::fs::write(
+        &manifest,
+        r#"{ "contracts": [{ "contract_id": "a" }, { "contract_id": "a" }] }"#,
+    )
+    .expect("write manifest");
+    let err = load_rest_fixture_manifest(&manifest).expect_err("duplicate ids should fail");
+    assert!(err.to_string().contains("contract a more than once"));
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml_manifest_loads_with_the_toml_feature() {
+    let dir = fixture_dir("toml");
+    let manifest = dir.join("fixtures.toml");
+    std::fs::write(
+        &manifest,
+        r#"
+[policy]
+max_age_secs = 60
+
+[[contracts]]
+contract_id = "ticker"
+"#,
+    )
+    .expect("write manifest");
+    let requirements = load_rest_fixture_manifest(&manifest).expect("manifest loads");
+    assert_eq!(requirements.len(), 1);
+    assert_eq!(
+        requirements[0].success_path,
+        dir.join("ticker/success.json")
+    );
+    assert_eq!(
+        requirements[0].policy().max_age,
+        Some(Duration::from_secs(60))
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
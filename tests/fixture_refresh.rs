@@ -0,0 +1,156 @@
+mod common;
+
+use common::{fixture_dir, live_fixture};
+use shared_restapi::{
+    Client, JsonShapeChangeKind, MockResponse, MockRestAdapter, RecordingTransport, RestFixture,
+    RestFixtureCaptureSelection, RestFixtureDiffOptions, RestFixtureKind, RestFixtureRegistry,
+    RestFixtureRequirement, RestRequest, diff_fixtures,
+};
+use sonic_rs::{JsonContainerTrait, JsonValueTrait};
+
+const URL: &str = "https://api.example.com/v1/ticker";
+
+fn fixture(status: u16, body: &'static str) -> RestFixture {
+    let request = RestRequest::get(URL).with_fixture_contract("ticker");
+    live_fixture("ticker", &request, status, body)
+}
+
+#[test]
+fn diff_reports_shape_and_value_changes_but_ignores_volatile_fields() {
+    let previous = fixture(
+        200,
+        r#"{"timestamp":1,"result":{"last":1.5,"venue":"deribit","levels":[1,2],"seq":7}}"#,
+    );
+    let current = fixture(
+        200,
+        r#"{"timestamp":2,"result":{"last":"1.6","venue":"deribit-v2","levels":[1,2,3],"seq":8}}"#,
+    );
+    let options = RestFixtureDiffOptions::default().with_volatile_field("seq");
+    let diff = diff_fixtures(
+        "ticker",
+        RestFixtureKind::Success,
+        "ticker/success.json",
+        Some(&previous),
+        &current,
+        &options,
+    );
+
+    assert!(diff.has_changes());
+    assert!(!diff.status_changed());
+    assert_eq!(diff.shape_changes.len(), 1);
+    assert_eq!(diff.shape_changes[0].path, "$.result.last");
+    assert!(matches!(
+        diff.shape_changes[0].kind,
+        JsonShapeChangeKind::Retyped { .. }
+    ));
+    let values = diff
+        .value_changes
+        .iter()
+        .map(|change| {
+            (
+                change.path.as_str(),
+                change.before.as_str(),
+                change.after.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![
+            ("$.result.venue", r#""deribit""#, r#""deribit-v2""#),
+            ("$.result.levels.length", "2", "3"),
+        ]
+    );
+
+    let unchanged = diff_fixtures(
+        "ticker",
+        RestFixtureKind::Success,
+        "ticker/success.json",
+        Some(&previous),
+        &fixture(
+            200,
+            r#"{"timestamp":9,"result":{"last":1.5,"venue":"deribit","levels":[1,2],"seq":7}}"#,
+        ),
+        &options,
+    );
+    assert!(!unchanged.has_changes());
+}
+
+#[test]
+fn value_changes_past_the_cap_still_count_as_changes() {
+    let diff = diff_fixtures(
+        "ticker",
+        RestFixtureKind::Success,
+        "ticker/success.json",
+        Some(&fixture(200, r#"{"result":{"venue":"deribit"}}"#)),
+        &fixture(200, r#"{"result":{"venue":"deribit-v2"}}"#),
+        &RestFixtureDiffOptions::default().with_max_value_changes(0),
+    );
+
+    assert!(diff.value_changes.is_empty());
+    assert_eq!(diff.value_changes_truncated, 1);
+    assert!(diff.has_changes());
+}
+
+#[tokio::test]
+async fn recorder_writes_a_refresh_report_against_previous_captures() {
+    let dir = fixture_dir("recorder");
+    let requirement = RestFixtureRequirement::new(
+        "ticker",
+        dir.join("ticker/success.json"),
+        dir.join("ticker/error.json"),
+    );
+    fixture(200, r#"{"last":1.5}"#)
+        .save(&requirement.success_path)
+        .expect("previous success fixture");
+    fixture(400, r#"{"code":10001}"#)
+        .save(&requirement.error_path)
+        .expect("previous error fixture");
+
+    let live = MockRestAdapter::new();
+    live.queue_response(MockResponse::text(200, r#"{"last":1.7}"#));
+    live.queue_response(MockResponse::text(429, r#"{"code":10028}"#));
+    live.queue_response(MockResponse::text(200, r#"{"last":1.8}"#));
+    let report_path = dir.join("refresh-report.json");
+    let registry = RestFixtureRegistry::from_requirements([requirement.clone()]);
+    registry.set_capture_selection(Some(RestFixtureCaptureSelection::All));
+    let recorder = RecordingTransport::new(live)
+        .with_exchange_env("deribit_testnet")
+        .with_fixture_registry(registry)
+        .with_refresh_report(&report_path);
+    let client = Client::with_transport(recorder.clone());
+    let request = RestRequest::get(URL).with_fixture_contract("ticker");
+
+    for _ in 0..3 {
+        client
+            .get_response(request.clone())
+            .await
+            .expect("capture succeeds");
+    }
+
+    let report = recorder.refresh_report();
+    assert_eq!(report.fixtures.len(), 2, "one entry per fixture path");
+    let success = &report.fixtures[0];
+    assert_eq!(success.fixture, RestFixtureKind::Success);
+    assert_eq!(
+        success.value_changes[0].before, "1.5",
+        "diffed against the pre-run capture"
+    );
+    assert_eq!(success.value_changes[0].after, "1.8");
+    let error = &report.fixtures[1];
+    assert!(error.status_changed());
+    assert_eq!((error.previous_status, error.status), (Some(400), 429));
+
+    let written: sonic_rs::Value =
+        sonic_rs::from_slice(&std::fs::read(&report_path).expect("report written"))
+            .expect("report is JSON");
+    let fixtures = written["fixtures"].as_array().expect("fixtures array");
+    assert_eq!(fixtures.len(), 2);
+    assert_eq!(fixtures[1]["fixture"].as_str(), Some("error"));
+    assert_eq!(fixtures[1]["previous_status"].as_u64(), Some(400));
+    assert_eq!(
+        fixtures[0]["value_changes"][0]["path"].as_str(),
+        Some("$.last")
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
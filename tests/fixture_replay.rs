@@ -0,0 +1,82 @@
+mod common;
+
+use common::{contract, fixture_dir};
+use serde::Deserialize;
+use shared_restapi::{RestError, RestFixtureKind, RestReplayHarness};
+
+#[derive(Debug, Deserialize)]
+struct Ticker {
+    last: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    code: i64,
+}
+
+#[tokio::test]
+async fn harness_replays_every_contract_through_its_decoder() {
+    let dir = fixture_dir("pass");
+    let requirements = [
+        contract(&dir, "ticker", r#"{"last":1.5}"#),
+        contract(&dir, "index", r#"{"last":2.5}"#),
+    ];
+
+    let report = RestReplayHarness::new(requirements)
+        .with_json_decoder::<Ticker, ApiError>("ticker")
+        .with_decoder("index", |kind, response| {
+            match kind {
+                RestFixtureKind::Success => assert!(response.json_owned::<Ticker>()?.last > 0.0),
+                RestFixtureKind::Error => {
+                    assert_eq!(response.json_owned::<ApiError>()?.code, 10001)
+                }
+            }
+            Ok(())
+        })
+        .run()
+        .await;
+
+    report.ensure_all_passed().expect("all fixtures decode");
+    assert_eq!(report.outcomes.len(), 4);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn harness_reports_decode_failures_and_missing_coverage() {
+    let dir = fixture_dir("fail");
+    let mut missing_file = contract(&dir, "positions", r#"{"last":1.0}"#);
+    missing_file.error_path = dir.join("positions/missing.json");
+    let requirements = [
+        contract(&dir, "ticker", r#"{"price":1.5}"#),
+        contract(&dir, "book", r#"{"last":1.0}"#),
+        missing_file,
+    ];
+
+    let report = RestReplayHarness::new(requirements)
+        .with_json_decoder::<Ticker, ApiError>("ticker")
+        .with_json_decoder::<Ticker, ApiError>("positions")
+        .with_decoder("retired", |_, _| Err(RestError::internal("unused")))
+        .run()
+        .await;
+
+    assert!(!report.is_success());
+    assert_eq!(report.missing_decoders, vec!["book".to_string()]);
+    assert_eq!(report.unknown_decoders, vec!["retired".to_string()]);
+    assert_eq!(
+        report.failed_contracts(),
+        vec![
+            "book".to_string(),
+            "positions".to_string(),
+            "ticker".to_string()
+        ]
+    );
+    let message = report
+        .ensure_all_passed()
+        .expect_err("report should fail")
+        .to_string();
+    assert!(message.contains("contract=book: no replay decoder"));
+    assert!(message.contains("contract=ticker fixture=success"));
+    assert!(message.contains("contract=positions fixture=error: "));
+    assert!(message.contains("failed to read fixture"));
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use std::path::PathBuf;
+
+use common::{contract, fixture_dir};
+use serde::Deserialize;
+use shared_restapi::{
+    Client, JsonShapeChangeKind, MockResponse, MockRestAdapter, RestFixtureRegistry, RestRequest,
+    RestSchemaCheckMode, SchemaCheckingTransport,
+};
+
+const URL: &str = "https://api.example.com/v1/ticker";
+
+#[derive(Debug, Deserialize)]
+struct Ticker {
+    last: f64,
+}
+
+fn registry(name: &str) -> (PathBuf, RestFixtureRegistry) {
+    let dir = fixture_dir(name);
+    let requirement = contract(
+        &dir,
+        "ticker",
+        r#"{"last":1.5,"bid":1.4,"venue":"deribit"}"#,
+    );
+    (dir, RestFixtureRegistry::from_requirements([requirement]))
+}
+
+#[tokio::test]
+async fn report_mode_records_drift_and_returns_the_response() {
+    let (dir, registry) = registry("report");
+    let live = MockRestAdapter::new();
+    live.queue_get_response(
+        URL,
+        MockResponse::text(200, r#"{"last":1.6,"bid":1.5,"venue":"deribit"}"#),
+    );
+    live.queue_get_response(
+        URL,
+        MockResponse::text(200, r#"{"last":"1.6","venue":"deribit","ask":1.7}"#),
+    );
+    live.queue_get_response(URL, MockResponse::text(400, r#"{"message":"bad"}"#));
+    let checker = SchemaCheckingTransport::new(live, RestSchemaCheckMode::Report)
+        .with_fixture_registry(registry);
+    let client = Client::with_transport(checker.clone());
+    let request = RestRequest::get(URL).with_fixture_contract("ticker");
+
+    for _ in 0..3 {
+        client
+            .get_response(request.clone())
+            .await
+            .expect("report mode never fails the request");
+    }
+
+    let drift = checker.drift();
+    assert_eq!(
+        drift.len(),
+        1,
+        "matching and non-2xx responses are not drift"
+    );
+    assert_eq!(drift[0].contract_id, "ticker");
+    let kinds = drift[0]
+        .changes
+        .iter()
+        .map(|change| (change.path.as_str(), &change.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            (
+                "$.bid",
+                &JsonShapeChangeKind::Removed {
+                    expected: "number".to_string()
+                }
+            ),
+            (
+                "$.last",
+                &JsonShapeChangeKind::Retyped {
+                    expected: "number".to_string(),
+                    actual: "string".to_string(),
+                }
+            ),
+            (
+                "$.ask",
+                &JsonShapeChangeKind::Added {
+                    actual: "number".to_string()
+                }
+            ),
+        ]
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn enforce_mode_fails_drifting_responses_on_every_entrypoint() {
+    let (dir, registry) = registry("enforce");
+    let live = MockRestAdapter::new();
+    live.queue_get_response(URL, MockResponse::text(200, r#"{"last":1.6}"#));
+    live.queue_get_response(URL, MockResponse::text(200, r#"{"last":1.6}"#));
+    let checker = SchemaCheckingTransport::new(live, RestSchemaCheckMode::Enforce)
+        .with_fixture_registry(registry);
+    let client = Client::with_transport(checker.clone());
+    let request = RestRequest::get(URL).with_fixture_contract("ticker");
+
+    let err = client
+        .get_response(request.clone())
+        .await
+        .expect_err("drift should fail in enforce mode");
+    assert!(
+        err.to_string()
+            .contains("response schema drift for contract=ticker")
+    );
+    assert!(err.to_string().contains("removed $.venue (string)"));
+    let err = client
+        .execute_json::<Ticker>(request)
+        .await
+        .expect_err("raw path is checked too");
+    assert!(err.to_string().contains("removed $.bid"));
+    assert_eq!(checker.drift().len(), 2);
+
+    let untagged =
+        SchemaCheckingTransport::new(MockRestAdapter::new(), RestSchemaCheckMode::Enforce);
+    untagged
+        .inner()
+        .queue_get_response(URL, MockResponse::text(200, r#"{"last":2.0}"#));
+    let ticker = Client::with_transport(untagged)
+        .execute_json::<Ticker>(RestRequest::get(URL))
+        .await
+        .expect("requests without a contract are not checked");
+    assert_eq!(ticker.last, 2.0);
+    let _ = std::fs::remove_dir_all(dir);
+}
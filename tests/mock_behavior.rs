@@ -0,0 +1,270 @@
+use std::time::Duration;
+
+use shared_restapi::{
+    Client, Method, MockBehavior, MockBehaviorPlan, MockResponse, MockRestAdapter,
+    MockRestStateSnapshot, MockRoute, MockScenario, RestErrorKind, RestRequest,
+};
+use sonic_rs::Value;
+
+fn comparable(snapshot: MockRestStateSnapshot) -> (usize, Option<u16>, usize, usize, usize) {
+    (
+        snapshot.request_count,
+        snapshot.last_status,
+        snapshot.behavior_remaining,
+        snapshot.inbound_count,
+        snapshot.outbound_count,
+    )
+}
+
+#[tokio::test]
+async fn execute_and_execute_raw_share_one_pipeline() {
+    let url = "https://api.example.com/v1/pipeline";
+    let build = || {
+        let mut plan = MockBehaviorPlan::default();
+        plan.push(MockBehavior::Delay(Duration::from_millis(1)))
+            .push(MockBehavior::connect_error("refused", None, true));
+        let adapter = MockRestAdapter::with_behavior_plan(plan);
+        adapter.queue_get_response(
+            url,
+            MockResponse::text(200, r#"{"ok":true}"#).with_header("x-ratelimit-remaining", "9"),
+        );
+        adapter
+    };
+
+    let full = build();
+    let full_client = Client::with_transport(full.clone());
+    full_client
+        .get_response(RestRequest::get(url))
+        .await
+        .expect("queued response should be delivered");
+    full_client
+        .get_response(RestRequest::get(url))
+        .await
+        .expect_err("planned connect error should surface");
+    // The fallback path must be accounted for identically as well.
+    full_client
+        .get_response(RestRequest::get(url))
+        .await
+        .expect("empty queue falls back to 200");
+
+    let raw = build();
+    let raw_client = Client::with_transport(raw.clone());
+    raw_client
+        .execute_json_direct::<Value>(RestRequest::get(url))
+        .await
+        .expect("queued response should be delivered");
+    raw_client
+        .execute_json_direct::<Value>(RestRequest::get(url))
+        .await
+        .expect_err("planned connect error should surface");
+    raw_client
+        .execute_json_direct::<Value>(RestRequest::get(url))
+        .await
+        .expect_err("empty fallback body fails to parse");
+
+    let full_snapshot = full.snapshot();
+    let raw_snapshot = raw.snapshot();
+    assert_eq!(comparable(full_snapshot), comparable(raw_snapshot));
+
+    let full_inbound = full.inbound_responses();
+    let raw_inbound = raw.inbound_responses();
+    assert_eq!(full_inbound.len(), 2);
+    assert_eq!(raw_inbound.len(), 2);
+    for (full, raw) in full_inbound.iter().zip(raw_inbound.iter()) {
+        assert_eq!(full.status, raw.status);
+        assert_eq!(full.headers, raw.headers);
+        assert_eq!(full.body, raw.body);
+    }
+    assert_eq!(raw_inbound[0].headers.len(), 1);
+}
+
+#[tokio::test]
+async fn route_behavior_plans_advance_independently_of_other_routes() {
+    let orders = "https://api.example.com/v1/orders";
+    let ticker = "https://api.example.com/v1/ticker?symbol=BTC";
+    let mut plan = MockBehaviorPlan::default();
+    plan.push_for(
+        MockRoute::path(Method::POST, "/v1/orders"),
+        MockBehavior::Pass,
+    )
+    .push_for(
+        MockRoute::path(Method::POST, "/v1/orders"),
+        MockBehavior::connect_error("orders refused", None, true),
+    )
+    .push(MockBehavior::reject(503, "global fallback"));
+    let adapter = MockRestAdapter::with_behavior_plan(plan);
+    let client = Client::with_transport(adapter.clone());
+
+    for _ in 0..3 {
+        adapter.queue_get_response(ticker, MockResponse::text(200, "{}"));
+    }
+    let poll_ticker = || client.get_response(RestRequest::get(ticker));
+
+    let first_ticker = poll_ticker()
+        .await
+        .expect_err("global plan applies to ticker");
+    assert_eq!(first_ticker.status(), Some(503));
+    assert_eq!(adapter.snapshot().route_behavior_remaining, 2);
+
+    client
+        .get_response(RestRequest::post(orders))
+        .await
+        .expect("first orders call passes");
+    poll_ticker()
+        .await
+        .expect("ticker does not consume order behaviors");
+    poll_ticker()
+        .await
+        .expect("ticker does not consume order behaviors");
+    let err = client
+        .get_response(RestRequest::post(orders))
+        .await
+        .expect_err("second orders call hits connect error");
+    assert_eq!(err.kind(), RestErrorKind::Connect);
+
+    client
+        .get_response(RestRequest::post(orders))
+        .await
+        .expect("exhausted route plan falls back to the empty global plan");
+
+    let snapshot = adapter.snapshot();
+    assert_eq!(snapshot.route_behavior_remaining, 0);
+    assert_eq!(snapshot.behavior_remaining, 0);
+}
+
+#[tokio::test]
+async fn route_behaviors_can_be_added_at_runtime() {
+    let url = "https://api.example.com/v1/positions";
+    let adapter = MockRestAdapter::new();
+    adapter.push_behavior_for(
+        MockRoute::get(url),
+        MockBehavior::timeout_error("slow", None, true),
+    );
+    let client = Client::with_transport(adapter.clone());
+
+    client
+        .get_response(RestRequest::get("https://api.example.com/v1/other"))
+        .await
+        .expect("unrelated route is unaffected");
+    let err = client
+        .get_response(RestRequest::get(url))
+        .await
+        .expect_err("route behavior applies");
+    assert_eq!(err.kind(), RestErrorKind::Timeout);
+}
+
+#[tokio::test]
+async fn scenario_replay_steps_serve_their_frames() {
+    let url = "https://api.example.com/v1/replay";
+    let adapter = MockRestAdapter::from_scenario(
+        MockScenario::new()
+            .replay([MockResponse::text(200, r#"{"frame":1}"#)])
+            .reject(429, "slow down"),
+    );
+    let client = Client::with_transport(adapter.clone());
+
+    let response = client
+        .get_response(RestRequest::get(url))
+        .await
+        .expect("replay step serves its frame");
+    assert_eq!(response.body(), br#"{"frame":1}"#);
+    assert_eq!(adapter.snapshot().scenario_step, 1);
+
+    let err = client
+        .get_response(RestRequest::get(url))
+        .await
+        .expect_err("second step rejects");
+    assert_eq!(err.status(), Some(429));
+    let snapshot = adapter.snapshot();
+    assert_eq!(snapshot.scenario_step, 2);
+    assert_eq!(snapshot.behavior_remaining, 0);
+}
+
+#[tokio::test]
+async fn routed_replay_frames_stay_with_their_route() {
+    let orders = "https://api.example.com/v1/orders";
+    let ticker = "https://api.example.com/v1/ticker";
+    let adapter = MockRestAdapter::from_scenario(
+        MockScenario::new()
+            .replay([
+                MockResponse::text(200, r#"{"frame":1}"#),
+                MockResponse::text(200, r#"{"frame":2}"#),
+            ])
+            .routed(MockRoute::get(orders)),
+    );
+    adapter.queue_response(MockResponse::text(200, r#"{"default":true}"#));
+    let client = Client::with_transport(adapter.clone());
+
+    let first = client
+        .get_response(RestRequest::get(orders))
+        .await
+        .expect("first frame");
+    assert_eq!(first.body(), br#"{"frame":1}"#);
+    assert_eq!(adapter.snapshot().scenario_step, 0);
+
+    let other = client
+        .get_response(RestRequest::get(ticker))
+        .await
+        .expect("other route gets the default response");
+    assert_eq!(other.body(), br#"{"default":true}"#);
+
+    let second = client
+        .get_response(RestRequest::get(orders))
+        .await
+        .expect("second frame");
+    assert_eq!(second.body(), br#"{"frame":2}"#);
+    let snapshot = adapter.snapshot();
+    assert_eq!(snapshot.scenario_step, 1);
+    assert_eq!(snapshot.route_behavior_remaining, 0);
+
+    let after = client
+        .get_response(RestRequest::get(orders))
+        .await
+        .expect("step is used up");
+    assert!(after.body().is_empty());
+}
+
+#[tokio::test]
+async fn composed_scenarios_target_routes_and_repeat() {
+    let orders = "https://api.example.com/v1/orders";
+    let ticker = "https://api.example.com/v1/ticker";
+    let order_faults = MockScenario::new()
+        .pass()
+        .drop_response()
+        .routed(MockRoute::post(orders));
+    let ticker_faults = MockScenario::new()
+        .reject(503, "ticker down")
+        .routed(MockRoute::get(ticker))
+        .repeat(2);
+    let scenario = MockScenario::interleave([order_faults, ticker_faults]);
+    assert_eq!(scenario.len(), 4);
+    assert_eq!(
+        scenario.steps()[1].route.as_ref(),
+        Some(&MockRoute::get(ticker))
+    );
+
+    let adapter = MockRestAdapter::from_scenario(scenario.then(MockScenario::new().pass()));
+    let client = Client::with_transport(adapter.clone());
+
+    for _ in 0..2 {
+        let err = client
+            .get_response(RestRequest::get(ticker))
+            .await
+            .expect_err("ticker steps reject twice");
+        assert_eq!(err.status(), Some(503));
+    }
+    client
+        .get_response(RestRequest::post(orders))
+        .await
+        .expect("first order step passes");
+    let err = client
+        .get_response(RestRequest::post(orders))
+        .await
+        .expect_err("second order step drops");
+    assert_eq!(err.kind(), RestErrorKind::Timeout);
+
+    let snapshot = adapter.snapshot();
+    assert_eq!(snapshot.scenario_step, 4);
+    assert_eq!(snapshot.route_behavior_remaining, 0);
+    assert_eq!(snapshot.behavior_remaining, 1);
+}
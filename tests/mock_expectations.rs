@@ -0,0 +1,106 @@
+use bytes::Bytes;
+use shared_restapi::{Client, Method, MockResponse, MockRestAdapter, MockRoute, RestRequest};
+use sonic_rs::{JsonValueTrait, Value};
+
+const ORDERS: &str = "https://api.example.com/v1/orders";
+const TICKER: &str = "https://api.example.com/v1/ticker?symbol=BTC";
+
+async fn drive(adapter: &MockRestAdapter) {
+    adapter.queue_post_response(ORDERS, MockResponse::text(201, r#"{"id":1}"#));
+    adapter.queue_get_response(TICKER, MockResponse::text(200, r#"{"px":1.0}"#));
+    let client = Client::with_transport(adapter.clone());
+
+    client
+        .get_response(
+            RestRequest::post(ORDERS)
+                .with_header("X-Api-Key", "key-1")
+                .with_body(Bytes::from_static(
+                    br#"{"symbol":"BTC","side":"buy","qty":2,"tags":["a","b"]}"#,
+                )),
+        )
+        .await
+        .expect("order post should succeed");
+    client
+        .get_response(RestRequest::get(TICKER))
+        .await
+        .expect("ticker get should succeed");
+}
+
+#[tokio::test]
+async fn expectations_match_route_count_header_and_partial_json_body() {
+    let adapter = MockRestAdapter::new();
+    drive(&adapter).await;
+
+    adapter
+        .expect(MockRoute::post(ORDERS))
+        .once()
+        .with_header("x-api-key", "key-1")
+        .with_json_body(sonic_rs::json!({"symbol": "BTC", "tags": ["a", "b"]}))
+        .assert();
+    adapter
+        .expect(MockRoute::path(Method::GET, "/v1/ticker"))
+        .times(1)
+        .assert();
+    adapter
+        .expect(MockRoute::prefix(Method::DELETE, ORDERS))
+        .never()
+        .assert();
+    adapter.assert_order([MockRoute::post(ORDERS), MockRoute::get(TICKER)]);
+
+    let requests = adapter.outbound_requests();
+    let responses = adapter.inbound_responses();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(requests[1].url, TICKER);
+    assert_eq!(responses[0].status, 201);
+}
+
+#[tokio::test]
+async fn expectation_failure_lists_recorded_requests_with_reasons() {
+    let adapter = MockRestAdapter::new();
+    drive(&adapter).await;
+
+    let err = adapter
+        .expect(MockRoute::post(ORDERS))
+        .with_json_body(sonic_rs::json!({"side": "sell"}))
+        .verify()
+        .expect_err("mismatched json body should fail");
+    let message = err.to_string();
+    assert!(message.contains("expected POST https://api.example.com/v1/orders"));
+    assert!(message.contains("matched 0"));
+    assert!(message.contains(r#"- [0] POST https://api.example.com/v1/orders: json body at /side expected "sell", found "buy""#));
+    assert!(message.contains("    [1] GET https://api.example.com/v1/ticker?symbol=BTC"));
+
+    let err = adapter
+        .expect(MockRoute::post(ORDERS))
+        .with_header("x-api-key", "other")
+        .verify()
+        .expect_err("mismatched header should fail");
+    assert!(
+        err.to_string()
+            .contains(r#"header `x-api-key` expected "other", found "key-1""#)
+    );
+}
+
+#[tokio::test]
+async fn order_expectation_reports_out_of_order_requests() {
+    let adapter = MockRestAdapter::new();
+    drive(&adapter).await;
+
+    let err = adapter
+        .verify_order([MockRoute::get(TICKER), MockRoute::post(ORDERS)])
+        .expect_err("reversed order should fail");
+    let message = err.to_string();
+    assert!(
+        message.contains("no request matching POST https://api.example.com/v1/orders after [1]")
+    );
+    assert!(
+        message.contains("- [0] POST https://api.example.com/v1/orders: matches but out of order")
+    );
+    assert!(message.contains("+ [1] GET"));
+
+    let parsed: Value = adapter.inbound_responses()[1]
+        .json()
+        .expect("recorded response body should stay parseable");
+    assert!(parsed.get("px").is_some());
+}
@@ -0,0 +1,105 @@
+use serde::Deserialize;
+use shared_restapi::{
+    Client, MockChunk, MockResponse, MockRestAdapter, RestBodyStream, RestError, RestErrorKind,
+    RestNdjsonStream, RestRequest,
+};
+
+const URL: &str = "https://api.example.com/v1/export/trades";
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Trade {
+    id: u64,
+    price: f64,
+}
+
+#[tokio::test]
+async fn lines_split_across_chunks_decode_incrementally() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        URL,
+        MockResponse::chunked(
+            200,
+            [
+                MockChunk::new("{\"id\":1,\"pri"),
+                MockChunk::new("ce\":1.5}\r\n\n{\"id\":2,"),
+                MockChunk::new("\"price\":2.5}\n  \n{\"id\":3,\"price\":3.5}"),
+            ],
+        ),
+    );
+    let mut trades = Client::with_transport(adapter)
+        .get_ndjson::<Trade>(RestRequest::get(URL))
+        .await
+        .expect("stream opens");
+
+    assert_eq!(
+        trades.next_item().await.expect("first trade"),
+        Some(Trade { id: 1, price: 1.5 })
+    );
+    assert_eq!(trades.lines_read(), 1);
+    assert_eq!(
+        trades.next_item().await.expect("second trade"),
+        Some(Trade { id: 2, price: 2.5 })
+    );
+    // The trailing line has no newline.
+    assert_eq!(
+        trades.next_item().await.expect("third trade"),
+        Some(Trade { id: 3, price: 3.5 })
+    );
+    assert_eq!(trades.next_item().await.expect("end of body"), None);
+    assert_eq!(trades.lines_read(), 5);
+}
+
+#[tokio::test]
+async fn parse_failures_report_line_and_byte_offset() {
+    let body = "{\"id\":1,\"price\":1.5}\n{\"id\":2,\"price\":2.5}\n{\"id\":3,\"price\":oops}\n";
+    let mut trades = RestNdjsonStream::<Trade>::new(RestBodyStream::from_bytes(body.into()));
+    let items = [
+        trades.next_item().await.expect("line 1"),
+        trades.next_item().await.expect("line 2"),
+    ];
+    assert!(items.iter().all(Option::is_some));
+
+    let err = trades.next_item().await.expect_err("line 3 is malformed");
+    assert_eq!(err.kind(), RestErrorKind::Parse);
+    match &err {
+        RestError::NdjsonParse { line, offset, .. } => {
+            assert_eq!(*line, 3);
+            assert_eq!(*offset, 42);
+            assert_eq!(&body[*offset as usize..][..7], "{\"id\":3");
+        }
+        other => panic!("expected an ndjson parse error, got {other:?}"),
+    }
+    assert!(
+        err.to_string()
+            .starts_with("ndjson parse error at line 3 (byte offset 42):")
+    );
+}
+
+#[tokio::test]
+async fn non_success_responses_are_rejected_before_decoding() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(URL, MockResponse::text(503, "{\"error\":\"busy\"}"));
+    let err = Client::with_transport(adapter)
+        .get_ndjson::<Trade>(RestRequest::get(URL))
+        .await
+        .err()
+        .expect("503 is rejected");
+    assert_eq!(err.kind(), RestErrorKind::Rejected);
+    assert_eq!(err.status(), Some(503));
+    assert!(err.is_retryable());
+    assert!(err.to_string().contains("busy"));
+}
+
+#[tokio::test]
+async fn collect_items_drains_the_stream() {
+    let trades = RestNdjsonStream::<Trade>::new(RestBodyStream::from_bytes(
+        "{\"id\":7,\"price\":1.0}\n{\"id\":8,\"price\":2.0}\n".into(),
+    ))
+    .collect_items()
+    .await
+    .expect("all lines decode");
+    assert_eq!(
+        trades.iter().map(|trade| trade.id).collect::<Vec<_>>(),
+        vec![7, 8]
+    );
+}
@@ -0,0 +1,278 @@
+use serde::Deserialize;
+use shared_restapi::{
+    Client, MockResponse, MockRestAdapter, RestErrorKind, RestPageStrategy, RestRequest,
+};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Trade {
+    id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TradesPage {
+    result: TradesResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct TradesResult {
+    trades: Vec<Trade>,
+    continuation: Option<String>,
+}
+
+const TRADES: &str = "https://api.example.com/v2/trades?currency=BTC";
+
+fn urls(adapter: &MockRestAdapter) -> Vec<String> {
+    adapter
+        .outbound_requests()
+        .into_iter()
+        .map(|request| request.url)
+        .collect()
+}
+
+#[tokio::test]
+async fn cursor_pages_follow_the_body_cursor() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        TRADES,
+        MockResponse::text(
+            200,
+            r#"{"result":{"trades":[{"id":1},{"id":2}],"continuation":"c-2"}}"#,
+        ),
+    );
+    adapter.queue_get_response(
+        format!("{TRADES}&continuation=c-2"),
+        MockResponse::text(
+            200,
+            r#"{"result":{"trades":[{"id":3}],"continuation":"c 3"}}"#,
+        ),
+    );
+    adapter.queue_get_response(
+        format!("{TRADES}&continuation=c+3"),
+        MockResponse::text(200, r#"{"result":{"trades":[],"continuation":null}}"#),
+    );
+
+    let pages = Client::with_transport(adapter.clone())
+        .paginate(
+            RestRequest::get(TRADES).with_header("x-api-key", "key-1"),
+            RestPageStrategy::cursor("/result/continuation", "continuation"),
+        )
+        .collect_pages::<TradesPage>()
+        .await
+        .expect("pages decode");
+    assert_eq!(pages.len(), 3);
+    assert_eq!(pages[1].result.trades, vec![Trade { id: 3 }]);
+    assert_eq!(pages[2].result.continuation, None);
+    assert_eq!(
+        urls(&adapter),
+        vec![
+            TRADES.to_string(),
+            format!("{TRADES}&continuation=c-2"),
+            format!("{TRADES}&continuation=c+3"),
+        ]
+    );
+    // Headers carry over to every page.
+    assert!(adapter.outbound_requests().iter().all(|request| {
+        request
+            .headers
+            .iter()
+            .any(|(name, value)| name == "x-api-key" && value.as_ref() == b"key-1")
+    }));
+}
+
+#[tokio::test]
+async fn offset_pages_stop_on_a_short_page_and_respect_max_items() {
+    let base = "https://api.example.com/v1/fills";
+    let adapter = MockRestAdapter::new();
+    let page = |ids: &[u64]| {
+        let items = ids
+            .iter()
+            .map(|id| format!("{{\"id\":{id}}}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        MockResponse::text(200, format!("{{\"data\":[{items}]}}"))
+    };
+    for _ in 0..2 {
+        adapter.queue_get_response(format!("{base}?offset=0&limit=2"), page(&[1, 2]));
+        adapter.queue_get_response(format!("{base}?offset=2&limit=2"), page(&[3, 4]));
+    }
+    adapter.queue_get_response(format!("{base}?offset=4&limit=2"), page(&[5]));
+    let client = Client::with_transport(adapter.clone());
+    let strategy = RestPageStrategy::offset("offset", "limit", 2, "/data");
+
+    let fills = client
+        .paginate(RestRequest::get(base), strategy.clone())
+        .items::<Trade>("/data")
+        .collect_items()
+        .await
+        .expect("items decode");
+    assert_eq!(
+        fills.iter().map(|fill| fill.id).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4, 5]
+    );
+    assert_eq!(adapter.snapshot().request_count, 3);
+
+    let mut limited = client
+        .paginate(RestRequest::get(base), strategy)
+        .items::<Trade>("/data")
+        .with_max_items(3);
+    let mut ids = Vec::new();
+    while let Some(fill) = limited.next_item().await.expect("item decodes") {
+        ids.push(fill.id);
+    }
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert_eq!(limited.items_yielded(), 3);
+    assert_eq!(limited.pages_fetched(), 2);
+    assert_eq!(adapter.snapshot().request_count, 5);
+}
+
+#[tokio::test]
+async fn link_header_pages_resolve_relative_targets() {
+    let first = "https://api.example.com/v1/orders?per_page=2";
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        first,
+        MockResponse::text(200, r#"[{"id":1},{"id":2}]"#).with_header(
+            "Link",
+            r#"</v1/orders?per_page=2&page=2>; rel="next", </v1/orders?per_page=2&page=9>; rel="last""#,
+        ),
+    );
+    adapter.queue_get_response(
+        "https://api.example.com/v1/orders?per_page=2&page=2",
+        MockResponse::text(200, r#"[{"id":3}]"#)
+            .with_header("link", r#"</v1/orders?per_page=2&page=1>; rel="prev""#),
+    );
+
+    let orders = Client::with_transport(adapter.clone())
+        .paginate(RestRequest::get(first), RestPageStrategy::link_header())
+        .items::<Trade>("")
+        .collect_items()
+        .await
+        .expect("items decode");
+    assert_eq!(
+        orders.iter().map(|order| order.id).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(adapter.snapshot().request_count, 2);
+}
+
+#[tokio::test]
+async fn custom_strategies_and_max_pages_bound_iteration() {
+    let adapter = MockRestAdapter::new();
+    for page in 1..=5 {
+        adapter.queue_get_response(
+            format!("https://api.example.com/v1/klines?page={page}"),
+            MockResponse::text(200, format!(r#"{{"page":{page},"pages":5,"rows":[]}}"#)),
+        );
+    }
+    let strategy = RestPageStrategy::custom(|request, response| {
+        #[derive(Deserialize)]
+        struct Meta {
+            page: u64,
+            pages: u64,
+        }
+        let meta: Meta = response.json()?;
+        Ok((meta.page < meta.pages).then(|| {
+            let mut next = request.clone();
+            next.url = format!("https://api.example.com/v1/klines?page={}", meta.page + 1);
+            next
+        }))
+    });
+
+    let mut pages = Client::with_transport(adapter.clone())
+        .paginate(
+            RestRequest::get("https://api.example.com/v1/klines?page=1"),
+            strategy,
+        )
+        .with_max_pages(3);
+    let mut seen = Vec::new();
+    while let Some(response) = pages.next_response().await.expect("page fetches") {
+        seen.push(response.body().to_vec());
+    }
+    assert_eq!(seen.len(), 3);
+    assert_eq!(pages.pages_fetched(), 3);
+    assert_eq!(adapter.snapshot().request_count, 3);
+}
+
+#[tokio::test]
+async fn rejected_pages_end_iteration_with_the_error() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        TRADES,
+        MockResponse::text(
+            200,
+            r#"{"result":{"trades":[{"id":1}],"continuation":"c-2"}}"#,
+        ),
+    );
+    adapter.queue_get_response(
+        format!("{TRADES}&continuation=c-2"),
+        MockResponse::text(429, r#"{"error":"too many requests"}"#),
+    );
+
+    let mut trades = Client::with_transport(adapter)
+        .paginate(
+            RestRequest::get(TRADES),
+            RestPageStrategy::cursor("/result/continuation", "continuation"),
+        )
+        .items::<Trade>("/result/trades");
+    assert_eq!(
+        trades.next_item().await.expect("first page"),
+        Some(Trade { id: 1 })
+    );
+    let err = trades
+        .next_item()
+        .await
+        .expect_err("second page is rejected");
+    assert_eq!(err.kind(), RestErrorKind::Rejected);
+    assert_eq!(err.status(), Some(429));
+    assert_eq!(trades.next_item().await.expect("iteration ended"), None);
+}
+
+#[tokio::test]
+async fn zero_page_sizes_fail_before_the_first_request() {
+    let adapter = MockRestAdapter::new();
+    let mut paginator = Client::with_transport(adapter.clone()).paginate(
+        RestRequest::get("https://api.example.com/v1/fills"),
+        RestPageStrategy::offset("offset", "limit", 0, "/data"),
+    );
+    let err = paginator
+        .next_response()
+        .await
+        .expect_err("page_size 0 is rejected");
+    assert_eq!(err.kind(), RestErrorKind::Internal);
+    assert!(err.to_string().contains("page_size"), "{err}");
+    assert_eq!(adapter.snapshot().request_count, 0);
+    assert!(paginator.next_response().await.expect("ended").is_none());
+}
+
+#[tokio::test]
+async fn repeated_cursors_fail_instead_of_looping() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        TRADES,
+        MockResponse::text(
+            200,
+            r#"{"result":{"trades":[{"id":1}],"continuation":"c-2"}}"#,
+        ),
+    );
+    adapter.queue_get_response(
+        format!("{TRADES}&continuation=c-2"),
+        MockResponse::text(
+            200,
+            r#"{"result":{"trades":[{"id":2}],"continuation":"c-2"}}"#,
+        ),
+    );
+
+    let mut paginator = Client::with_transport(adapter.clone()).paginate(
+        RestRequest::get(TRADES),
+        RestPageStrategy::cursor("/result/continuation", "continuation"),
+    );
+    paginator.next_response().await.expect("first page");
+    let err = paginator
+        .next_response()
+        .await
+        .expect_err("the second page repeats its own cursor");
+    assert_eq!(err.kind(), RestErrorKind::Internal);
+    assert!(err.to_string().contains("repeats"), "{err}");
+    assert!(paginator.next_response().await.expect("ended").is_none());
+    assert_eq!(adapter.snapshot().request_count, 2);
+}
@@ -0,0 +1,171 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use shared_restapi::{
+    Client, MockResponse, MockRestAdapter, RateLimitGate, RestErrorKind, RestFuture,
+    RestRateLimitKey, RestRateLimitObservation, RestRequest, RestResult, RestTokenBucket,
+};
+use sonic_rs::JsonValueTrait;
+
+const ORDER: &str = "https://api.example.com/api/v3/order?symbol=BTCUSDT";
+
+/// Status and `x-used-weight` of each observed response.
+type Observed = Vec<(u16, Option<String>)>;
+
+/// Lets everything through, recording what it was asked and shown.
+#[derive(Clone, Default)]
+struct RecordingGate {
+    acquired: Arc<Mutex<Vec<RestRateLimitKey>>>,
+    observed: Arc<Mutex<Observed>>,
+}
+
+impl RateLimitGate for RecordingGate {
+    fn acquire(&self, key: &RestRateLimitKey) -> RestFuture<RestResult<()>> {
+        self.acquired.lock().unwrap().push(key.clone());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn observed_headers(&self) -> &'static [&'static str] {
+        &["x-used-weight"]
+    }
+
+    fn observe(&self, _key: &RestRateLimitKey, response: &RestRateLimitObservation<'_>) {
+        self.observed.lock().unwrap().push((
+            response.status,
+            response.header("x-used-weight").map(str::to_string),
+        ));
+    }
+}
+
+#[tokio::test]
+async fn every_attempt_passes_the_gate_including_retries() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        ORDER,
+        MockResponse::text(503, "busy").with_header("x-used-weight", "10"),
+    );
+    adapter.queue_get_response(
+        ORDER,
+        MockResponse::text(503, "busy").with_header("x-used-weight", "20"),
+    );
+    adapter.queue_get_response(
+        ORDER,
+        MockResponse::text(200, r#"{"orderId":7}"#).with_header("x-used-weight", "30"),
+    );
+    let gate = RecordingGate::default();
+    let client = Client::with_transport(adapter).with_rate_limit_gate(gate.clone());
+
+    let order: sonic_rs::Value = client
+        .execute_json_checked(
+            RestRequest::get(ORDER)
+                .with_fixture_contract("binance.order")
+                .with_rate_limit_weight(4)
+                .with_retry_on_status(503, 2),
+        )
+        .await
+        .expect("third attempt succeeds");
+    assert_eq!(order["orderId"].as_u64(), Some(7));
+
+    let acquired = gate.acquired.lock().unwrap().clone();
+    assert_eq!(acquired.len(), 3);
+    assert!(acquired.iter().all(|key| *key
+        == RestRateLimitKey {
+            host: "api.example.com".to_string(),
+            contract: Some("binance.order".to_string()),
+            weight: 4,
+        }));
+    assert_eq!(
+        *gate.observed.lock().unwrap(),
+        vec![
+            (503, Some("10".to_string())),
+            (503, Some("20".to_string())),
+            (200, Some("30".to_string())),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn token_bucket_paces_requests_per_host() {
+    let adapter = MockRestAdapter::new();
+    for _ in 0..4 {
+        adapter.queue_get_response(ORDER, MockResponse::text(200, "{}"));
+    }
+    adapter.queue_get_response(
+        "https://other.example.com/v1",
+        MockResponse::text(200, "{}"),
+    );
+    let client = Client::with_transport(adapter)
+        .with_rate_limit_gate(RestTokenBucket::new(2, Duration::from_millis(100)));
+
+    let started = Instant::now();
+    for _ in 0..2 {
+        client
+            .get_response(RestRequest::get(ORDER))
+            .await
+            .expect("within burst");
+    }
+    client
+        .get_response(RestRequest::get("https://other.example.com/v1"))
+        .await
+        .expect("other host has its own bucket");
+    assert!(started.elapsed() < Duration::from_millis(40));
+
+    for _ in 0..2 {
+        client
+            .get_response(RestRequest::get(ORDER))
+            .await
+            .expect("after refill");
+    }
+    assert!(started.elapsed() >= Duration::from_millis(90));
+}
+
+#[tokio::test]
+async fn used_quota_headers_drain_the_bucket() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        ORDER,
+        MockResponse::text(200, "{}").with_header("X-MBX-USED-WEIGHT-1M", "1195"),
+    );
+    let bucket = RestTokenBucket::new(1200, Duration::from_secs(60))
+        .with_used_quota_headers(&["x-mbx-used-weight-1m"]);
+    let client = Client::with_transport(adapter.clone()).with_rate_limit_gate(bucket.clone());
+
+    let _: sonic_rs::Value = client
+        .execute_json(RestRequest::get(ORDER).with_rate_limit_weight(2))
+        .await
+        .expect("first order");
+    let key = RestRateLimitKey::for_request(&RestRequest::get(ORDER));
+    assert_eq!(bucket.available(&key), 5);
+
+    let err = client
+        .get_response(RestRequest::get(ORDER).with_rate_limit_weight(1201))
+        .await
+        .expect_err("weight beyond capacity");
+    assert_eq!(err.kind(), RestErrorKind::Internal);
+    assert_eq!(adapter.snapshot().request_count, 1);
+}
+
+#[tokio::test]
+async fn selected_header_requests_still_show_the_gate_its_headers() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        ORDER,
+        MockResponse::text(200, r#"{"orderId":7}"#)
+            .with_header("ETag", "\"v1\"")
+            .with_header("X-Used-Weight", "12"),
+    );
+    let gate = RecordingGate::default();
+    let client = Client::with_transport(adapter).with_rate_limit_gate(gate.clone());
+
+    let response = client
+        .get_raw_with_headers(RestRequest::get(ORDER), &["etag"])
+        .await
+        .expect("mock responds");
+    assert_eq!(
+        *gate.observed.lock().expect("gate state"),
+        vec![(200, Some("12".to_string()))]
+    );
+    assert_eq!(response.header("etag"), Some("\"v1\""));
+    assert_eq!(response.header("x-used-weight"), None);
+    assert_eq!(response.headers.len(), 1);
+}
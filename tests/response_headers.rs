@@ -0,0 +1,176 @@
+mod common;
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use common::{live_client, serve_once};
+use shared_restapi::{
+    Client, FaultInjectingTransport, FaultInjectionConfig, MockResponse, MockRestAdapter,
+    RecordingTransport, RestBytes, RestError, RestFixtureRegistry, RestFuture, RestHeaderNames,
+    RestRateLimit, RestRawHeaderResponse, RestRequest, RestResponse, RestResult, RestRetryAfter,
+    RestSchemaCheckMode, RestTransport, SchemaCheckingTransport,
+};
+
+const URL: &str = "https://api.example.com/v1/orders";
+
+/// Answers selected-header requests directly and fails the buffered `execute` path.
+struct HeadersOnlyTransport;
+
+impl RestTransport for HeadersOnlyTransport {
+    fn execute(&self, _request: RestRequest) -> RestFuture<RestResult<RestResponse>> {
+        Box::pin(async { Err(RestError::internal("buffered path used")) })
+    }
+
+    fn execute_raw_with_headers(
+        &self,
+        _request: RestRequest,
+        _keep: RestHeaderNames,
+    ) -> RestFuture<RestResult<RestRawHeaderResponse>> {
+        Box::pin(async {
+            Ok(RestRawHeaderResponse {
+                status: 200,
+                headers: vec![("etag", RestBytes::from_static(b"\"abc\""))],
+                body: RestBytes::from_static(b"{}"),
+                elapsed: Duration::ZERO,
+            })
+        })
+    }
+}
+
+fn throttled_response() -> MockResponse {
+    MockResponse::text(429, r#"{"error":"slow down"}"#)
+        .with_header("Content-Type", "application/json; charset=utf-8")
+        .with_header("Content-Length", "21")
+        .with_header("Date", "Sun, 06 Nov 1994 08:49:37 GMT")
+        .with_header("Retry-After", "Sun, 06 Nov 1994 08:50:07 GMT")
+        .with_header("ETag", "\"v42\"")
+        .with_header("X-RateLimit-Limit", "1200")
+        .with_header("X-RateLimit-Remaining", "0")
+        .with_header("X-RateLimit-Reset", "30")
+        .with_header("Set-Cookie", "a=1")
+        .with_header("set-cookie", "b=2")
+}
+
+#[tokio::test]
+async fn typed_header_accessors_ignore_case() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(URL, throttled_response());
+    let response = Client::with_transport(adapter)
+        .get_response(RestRequest::get(URL))
+        .await
+        .expect("mock responds");
+
+    assert_eq!(
+        response.content_type(),
+        Some("application/json; charset=utf-8")
+    );
+    assert_eq!(response.content_length(), Some(21));
+    let date = response.date().expect("date parses");
+    assert_eq!(date, UNIX_EPOCH + Duration::from_secs(784_111_777));
+    let retry_after = response.retry_after().expect("retry-after parses");
+    assert_eq!(
+        retry_after,
+        RestRetryAfter::At(UNIX_EPOCH + Duration::from_secs(784_111_807))
+    );
+    assert_eq!(retry_after.delay_from(date), Duration::from_secs(30));
+    assert_eq!(response.etag(), Some("\"v42\""));
+    assert_eq!(
+        response.rate_limit(),
+        Some(RestRateLimit {
+            limit: Some(1200),
+            remaining: Some(0),
+            reset: Some(30),
+        })
+    );
+    assert_eq!(response.header("SET-COOKIE"), Some("a=1"));
+    assert_eq!(
+        response.header_all("set-cookie").collect::<Vec<_>>(),
+        vec!["a=1", "b=2"]
+    );
+    assert_eq!(response.header("x-missing"), None);
+}
+
+#[tokio::test]
+async fn raw_responses_keep_only_selected_headers() {
+    const KEEP: &[&str] = &["retry-after", "x-ratelimit-remaining", "set-cookie"];
+
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(URL, throttled_response().with_header("Retry-After", "5"));
+    let response = Client::with_transport(adapter)
+        .get_raw_with_headers(RestRequest::get(URL), KEEP)
+        .await
+        .expect("mock responds");
+
+    assert_eq!(response.status, 429);
+    assert!(!response.is_success());
+    assert_eq!(response.body.as_ref(), br#"{"error":"slow down"}"#);
+    assert_eq!(
+        response
+            .headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>(),
+        vec![
+            "retry-after",
+            "x-ratelimit-remaining",
+            "set-cookie",
+            "set-cookie",
+            "retry-after"
+        ]
+    );
+    assert_eq!(response.content_type(), None);
+    assert_eq!(
+        response.rate_limit(),
+        Some(RestRateLimit {
+            remaining: Some(0),
+            ..RestRateLimit::default()
+        })
+    );
+    assert_eq!(response.header_all("Retry-After").last(), Some("5"));
+}
+
+#[tokio::test]
+async fn reqwest_transport_keeps_selected_headers_from_the_wire() {
+    let (url, _server) = serve_once(
+        &[b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nETag: \"abc\"\r\nx-other: 1\r\nconnection: close\r\n\r\n{}"],
+        Duration::ZERO,
+    )
+    .await;
+
+    let response = live_client()
+        .get_raw_with_headers(
+            RestRequest::get(format!("{url}/")).with_fixture_contract("ping"),
+            &["etag", "content-length"],
+        )
+        .await
+        .expect("local server responds");
+    assert_eq!(response.status, 200);
+    assert_eq!(response.etag(), Some("\"abc\""));
+    assert_eq!(response.content_length(), Some(2));
+    assert_eq!(response.headers.len(), 2);
+    assert_eq!(response.body.as_ref(), b"{}");
+}
+
+#[tokio::test]
+async fn wrapping_transports_forward_selected_header_requests() {
+    let registry = RestFixtureRegistry::new();
+    let clients = [
+        Client::with_transport(FaultInjectingTransport::new(
+            HeadersOnlyTransport,
+            FaultInjectionConfig::new(7),
+        )),
+        Client::with_transport(
+            RecordingTransport::new(HeadersOnlyTransport).with_fixture_registry(registry.clone()),
+        ),
+        Client::with_transport(
+            SchemaCheckingTransport::new(HeadersOnlyTransport, RestSchemaCheckMode::Enforce)
+                .with_fixture_registry(registry),
+        ),
+    ];
+    for client in clients {
+        let response = client
+            .get_raw_with_headers(RestRequest::get(URL), &["etag"])
+            .await
+            .expect("wrapper forwards to the inner transport");
+        assert_eq!(response.etag(), Some("\"abc\""));
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use shared_restapi::{
+    Client, MockResponse, MockRestAdapter, MockSseFrame, RestErrorKind, RestRequest,
+};
+
+const URL: &str = "https://api.example.com/v1/stream/quotes";
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Quote {
+    bid: f64,
+}
+
+fn header<'a>(request: &'a RestRequest, name: &str) -> Option<&'a [u8]> {
+    request
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_ref())
+}
+
+#[tokio::test]
+async fn reconnects_with_last_event_id_after_the_body_ends() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        URL,
+        MockResponse::sse([
+            MockSseFrame::new("{\"bid\":1.5}")
+                .with_event("quote")
+                .with_id("41")
+                .with_retry(Duration::from_millis(10)),
+            MockSseFrame::new("line one\nline two").with_id("42"),
+        ]),
+    );
+    adapter.queue_get_response(
+        URL,
+        MockResponse::sse([MockSseFrame::new("{\"bid\":2.5}").with_event("quote")]),
+    );
+    let mut events = Client::with_transport(adapter.clone())
+        .event_stream(RestRequest::get(URL))
+        .with_max_reconnects(1);
+
+    let first = events
+        .next_event()
+        .await
+        .expect("first event")
+        .expect("some");
+    assert_eq!(first.event, "quote");
+    assert_eq!(first.id.as_deref(), Some("41"));
+    assert_eq!(
+        first.json::<Quote>().expect("json data"),
+        Quote { bid: 1.5 }
+    );
+    let second = events
+        .next_event()
+        .await
+        .expect("second event")
+        .expect("some");
+    assert_eq!(second.event, "message");
+    assert_eq!(second.data, "line one\nline two");
+    assert_eq!(events.retry_delay(), Duration::from_millis(10));
+
+    let started = Instant::now();
+    let third = events
+        .next_event()
+        .await
+        .expect("third event")
+        .expect("some");
+    assert!(started.elapsed() >= Duration::from_millis(10));
+    assert_eq!(
+        third.json::<Quote>().expect("json data"),
+        Quote { bid: 2.5 }
+    );
+    // The id carries over from the first connection.
+    assert_eq!(third.id.as_deref(), Some("42"));
+    assert_eq!(events.connections(), 2);
+
+    assert_eq!(
+        events.next_event().await.expect("reconnects exhausted"),
+        None
+    );
+
+    let requests = adapter.outbound_requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        header(&requests[0], "accept"),
+        Some(&b"text/event-stream"[..])
+    );
+    assert_eq!(header(&requests[0], "last-event-id"), None);
+    assert_eq!(header(&requests[1], "last-event-id"), Some(&b"42"[..]));
+}
+
+#[tokio::test]
+async fn no_content_ends_the_stream_without_reconnecting() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(URL, MockResponse::new(204, ""));
+    let mut events = Client::with_transport(adapter.clone())
+        .event_stream(RestRequest::get(URL))
+        .with_last_event_id("7");
+
+    assert_eq!(events.next_event().await.expect("stream ends"), None);
+    assert_eq!(events.connections(), 1);
+    let requests = adapter.outbound_requests();
+    assert_eq!(header(&requests[0], "last-event-id"), Some(&b"7"[..]));
+}
+
+#[tokio::test]
+async fn non_success_responses_are_rejected() {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(URL, MockResponse::text(401, "unauthorized"));
+    let mut events = Client::with_transport(adapter).event_stream(RestRequest::get(URL));
+
+    let err = events.next_event().await.expect_err("401 is rejected");
+    assert_eq!(err.kind(), RestErrorKind::Rejected);
+    assert_eq!(err.status(), Some(401));
+    assert_eq!(events.next_event().await.expect("stream stays done"), None);
+}
@@ -0,0 +1,248 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{live_client, serve_once};
+use shared_restapi::{
+    Client, FaultInjectingTransport, FaultInjectionConfig, MockChunk, MockResponse,
+    MockRestAdapter, RecordingTransport, ReplayingTransport, RestBytes, RestErrorKind,
+    RestFixtureRegistry, RestRequest, RestSchemaCheckMode, RestTransport, SchemaCheckingTransport,
+};
+
+const URL: &str = "https://api.example.com/v1/trades/history";
+
+#[tokio::test]
+async fn mock_delivers_scripted_chunks_with_delays() {
+    let adapter = MockRestAdapter::new();
+    let response = MockResponse::chunked(
+        200,
+        [
+            MockChunk::new("{\"id\":1}\n"),
+            MockChunk::new("{\"id\":2}\n").with_delay(Duration::from_millis(40)),
+            MockChunk::new("{\"id\":3}\n"),
+        ],
+    )
+    .with_header("content-type", "application/x-ndjson");
+    adapter.queue_get_response(URL, response.clone());
+    adapter.queue_get_response(URL, response);
+    let client = Client::with_transport(adapter.clone());
+
+    let mut stream = client
+        .get_stream(RestRequest::get(URL))
+        .await
+        .expect("stream opens");
+    assert_eq!(stream.status(), 200);
+    assert_eq!(stream.content_type(), Some("application/x-ndjson"));
+
+    let started = Instant::now();
+    let first = stream.body.next_chunk().await.expect("first chunk");
+    assert_eq!(first.as_deref(), Some(&b"{\"id\":1}\n"[..]));
+    assert!(started.elapsed() < Duration::from_millis(40));
+    let second = stream.body.next_chunk().await.expect("second chunk");
+    assert_eq!(second.as_deref(), Some(&b"{\"id\":2}\n"[..]));
+    assert!(started.elapsed() >= Duration::from_millis(40));
+    assert_eq!(
+        stream.body.read_to_end().await.expect("rest of body"),
+        &b"{\"id\":3}\n"[..]
+    );
+
+    // Buffered reads of a chunked mock see the whole body.
+    let buffered = client
+        .get_response(RestRequest::get(URL))
+        .await
+        .expect("buffered read");
+    assert_eq!(buffered.body(), b"{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n");
+    assert_eq!(adapter.snapshot().request_count, 2);
+}
+
+#[tokio::test]
+async fn max_body_bytes_aborts_streamed_and_buffered_reads() {
+    let adapter = MockRestAdapter::new();
+    let response = MockResponse::chunked(
+        200,
+        [
+            MockChunk::new("aaaa"),
+            MockChunk::new("bbbb"),
+            MockChunk::new("cccc"),
+        ],
+    );
+    adapter.queue_get_response(URL, response.clone());
+    adapter.queue_get_response(URL, response);
+    let client = Client::with_transport(adapter);
+    let request = RestRequest::get(URL).with_max_body_bytes(10);
+
+    let mut stream = client
+        .get_stream(request.clone())
+        .await
+        .expect("stream opens");
+    assert!(stream.body.next_chunk().await.expect("chunk 1").is_some());
+    assert!(stream.body.next_chunk().await.expect("chunk 2").is_some());
+    let err = stream
+        .body
+        .next_chunk()
+        .await
+        .expect_err("third chunk exceeds the limit");
+    assert_eq!(err.kind(), RestErrorKind::BodyTooLarge);
+    assert_eq!(err.status(), Some(200));
+    assert!(!err.is_retryable());
+    assert!(
+        err.to_string()
+            .contains("response body exceeds 10 bytes (received 12)")
+    );
+    assert!(stream.body.is_done());
+    assert!(
+        stream
+            .body
+            .next_chunk()
+            .await
+            .expect("stream stays done")
+            .is_none()
+    );
+
+    let err = client
+        .get_response(request)
+        .await
+        .expect_err("buffered body exceeds the limit");
+    assert_eq!(err.kind(), RestErrorKind::BodyTooLarge);
+}
+
+fn scripted_adapter() -> MockRestAdapter {
+    let adapter = MockRestAdapter::new();
+    adapter.queue_get_response(
+        URL,
+        MockResponse::chunked(
+            200,
+            [
+                MockChunk::new("{\"id\":1}\n"),
+                MockChunk::new("{\"id\":2}\n"),
+            ],
+        ),
+    );
+    adapter
+}
+
+async fn streamed_chunks(transport: impl RestTransport + 'static) -> Vec<RestBytes> {
+    let mut stream = Client::with_transport(transport)
+        .get_stream(RestRequest::get(URL))
+        .await
+        .expect("stream opens");
+    let mut chunks = Vec::new();
+    while let Some(chunk) = stream.body.next_chunk().await.expect("chunk arrives") {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+#[tokio::test]
+async fn wrapping_transports_stream_the_inner_body() {
+    let registry = RestFixtureRegistry::new();
+    let expected = [&b"{\"id\":1}\n"[..], &b"{\"id\":2}\n"[..]];
+    assert_eq!(
+        streamed_chunks(FaultInjectingTransport::new(
+            scripted_adapter(),
+            FaultInjectionConfig::new(7).with_latency(
+                1.0,
+                Duration::from_millis(1),
+                Duration::from_millis(1),
+            ),
+        ))
+        .await,
+        expected
+    );
+    assert_eq!(
+        streamed_chunks(
+            RecordingTransport::new(scripted_adapter()).with_fixture_registry(registry.clone())
+        )
+        .await,
+        expected
+    );
+    assert_eq!(
+        streamed_chunks(ReplayingTransport::with_adapter(scripted_adapter(), &[]).expect("replay"))
+            .await,
+        expected
+    );
+    assert_eq!(
+        streamed_chunks(
+            SchemaCheckingTransport::new(scripted_adapter(), RestSchemaCheckMode::Enforce)
+                .with_fixture_registry(registry)
+        )
+        .await,
+        expected
+    );
+
+    let faulty = FaultInjectingTransport::new(
+        scripted_adapter(),
+        FaultInjectionConfig::new(7).with_receive_errors(1.0),
+    );
+    let err = Client::with_transport(faulty.clone())
+        .get_stream(RestRequest::get(URL))
+        .await
+        .expect_err("receive fault fails the stream");
+    assert_eq!(err.kind(), RestErrorKind::Receive);
+    assert_eq!(faulty.injected_faults().len(), 1);
+}
+
+#[tokio::test]
+async fn reqwest_streams_chunked_bodies_as_they_arrive() {
+    let (url, _server) = serve_once(
+        &[
+            b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\nconnection: close\r\n\r\n",
+            b"5\r\nhello\r\n",
+            b"6\r\n world\r\n",
+            b"0\r\n\r\n",
+        ],
+        Duration::from_millis(20),
+    )
+    .await;
+    let mut stream = live_client()
+        .get_stream(RestRequest::get(format!("{url}/")).with_fixture_contract("trades"))
+        .await
+        .expect("stream opens");
+    assert_eq!(stream.status(), 200);
+
+    let mut chunks = Vec::new();
+    while let Some(chunk) = stream.body.next_chunk().await.expect("chunk arrives") {
+        chunks.push(chunk);
+    }
+    assert!(chunks.len() >= 2, "body arrived in pieces: {chunks:?}");
+    assert_eq!(chunks.concat(), b"hello world");
+    assert_eq!(stream.body.received_bytes(), 11);
+}
+
+#[tokio::test]
+async fn reqwest_enforces_max_body_bytes() {
+    let (url, _server) = serve_once(
+        &[b"HTTP/1.1 200 OK\r\ncontent-length: 64\r\nconnection: close\r\n\r\n"],
+        Duration::ZERO,
+    )
+    .await;
+    let err = live_client()
+        .get_stream(
+            RestRequest::get(format!("{url}/"))
+                .with_fixture_contract("trades")
+                .with_max_body_bytes(16),
+        )
+        .await
+        .expect_err("content-length over the limit fails before reading");
+    assert_eq!(err.kind(), RestErrorKind::BodyTooLarge);
+
+    let (url, _server) = serve_once(
+        &[
+            b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\nconnection: close\r\n\r\n",
+            b"a\r\n0123456789\r\n",
+            b"a\r\n0123456789\r\n",
+            b"0\r\n\r\n",
+        ],
+        Duration::from_millis(5),
+    )
+    .await;
+    let err = live_client()
+        .get_response(
+            RestRequest::get(format!("{url}/"))
+                .with_fixture_contract("trades")
+                .with_max_body_bytes(16),
+        )
+        .await
+        .expect_err("chunked body over the limit aborts the buffered read");
+    assert_eq!(err.kind(), RestErrorKind::BodyTooLarge);
+}